@@ -0,0 +1,51 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+
+use freeout::entities::core::freeout::{Freeout, FreeoutOptions};
+use freeout::outliners::batch_outliner::outline_sources;
+use freeout::readers::markdown::MarkdownReader;
+
+/// Documents of a documentation repository, each with a few sections of content.
+fn generate_documents(count: usize) -> Vec<String> {
+    (0..count)
+        .map(|i| {
+            let mut document = format!("# Page {}\n\nIntroduction of the page.\n\n", i);
+            for j in 0..10 {
+                document.push_str(&format!(
+                    "## Section {}\n\n{}\n\n### Details {}\n\n{}\n\n",
+                    j,
+                    "Some words of the section. ".repeat(20),
+                    j,
+                    "More words of the details. ".repeat(10)
+                ));
+            }
+            document
+        })
+        .collect()
+}
+
+fn batch_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("batch outlining");
+    group.sample_size(10);
+
+    let documents = generate_documents(3_000);
+    let reader = MarkdownReader::default();
+    let options = FreeoutOptions::default();
+
+    group.bench_function("sequential", |b| {
+        b.iter(|| {
+            for document in &documents {
+                let mut freeout = Freeout::new(document.clone(), Some(options.clone()));
+                freeout.process(&reader).unwrap();
+            }
+        })
+    });
+    group.bench_function("parallel", |b| {
+        b.iter(|| {
+            let _outlines = outline_sources(documents.clone(), &reader, &options);
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, batch_benchmark);
+criterion_main!(benches);
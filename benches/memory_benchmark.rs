@@ -0,0 +1,114 @@
+//! Memory used to outline the generated thousands-of-depths document, with a borrowed `OutlineRef`, and with an
+//! `Outline` copied from it with `Freeout::to_outline`.
+//!
+//! The content of the blocks stays in the source, but blocks still own their `title` and `marker`, so the borrowed
+//! outline is not zero-copy.
+//!
+//! Allocations are counted by a global allocator, so this benchmark prints its own report instead of going through
+//! criterion.
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::hint::black_box;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use freeout::entities::core::freeout::Freeout;
+use freeout::readers::markdown::MarkdownReader;
+
+/// Counts the bytes currently allocated, and the peak since the last reset.
+struct CountingAllocator {
+    current: AtomicUsize,
+    peak: AtomicUsize,
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let pointer = System.alloc(layout);
+        if !pointer.is_null() {
+            let current = self.current.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            self.peak.fetch_max(current, Ordering::Relaxed);
+        }
+        pointer
+    }
+
+    unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+        System.dealloc(pointer, layout);
+        self.current.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator {
+    current: AtomicUsize::new(0),
+    peak: AtomicUsize::new(0),
+};
+
+/// Memory of a variant, in bytes, including its copy of the source.
+struct MemoryUsage {
+    /// Held by the results once the outline is built.
+    retained: usize,
+    /// Highest while outlining.
+    peak: usize,
+}
+
+/// Measure the memory of `run`, which calls `record` while its results are alive, e.g. an outline borrowing its
+/// `Freeout`.
+fn measure(run: impl FnOnce(&mut dyn FnMut())) -> MemoryUsage {
+    let start = ALLOCATOR.current.load(Ordering::Relaxed);
+    ALLOCATOR.peak.store(start, Ordering::Relaxed);
+
+    let mut retained = 0;
+    run(&mut || retained = ALLOCATOR.current.load(Ordering::Relaxed) - start);
+    MemoryUsage {
+        retained,
+        peak: ALLOCATOR.peak.load(Ordering::Relaxed) - start,
+    }
+}
+
+fn generate_markdown_with_thousands_of_depths() -> String {
+    let mut markdown_with_thousands_of_depths = "".to_string();
+    for i in 0..1600 {
+        // for a batch of 5, add a new depth and start again at 1
+        for j in 0..5 {
+            let marker = "#".repeat(j);
+            markdown_with_thousands_of_depths.push_str(&format!("{} Title {}\n", marker, i));
+        }
+    }
+    markdown_with_thousands_of_depths
+}
+
+fn main() {
+    let markdown = generate_markdown_with_thousands_of_depths();
+    let reader = MarkdownReader::default();
+
+    let variants = [
+        (
+            "borrowed outline",
+            measure(|record| {
+                let mut freeout = Freeout::new(markdown.clone(), None);
+                let outline = freeout.outline(&reader).unwrap();
+                record();
+                black_box(outline);
+            }),
+        ),
+        (
+            "copied outline",
+            measure(|record| {
+                let mut freeout = Freeout::new(markdown.clone(), None);
+                freeout.process(&reader).unwrap();
+                let outline = freeout.to_outline();
+                record();
+                black_box((freeout, outline));
+            }),
+        ),
+    ];
+
+    println!("source: {} KiB", markdown.len() / 1024);
+    for (name, usage) in variants {
+        println!(
+            "{:<16} retained: {:>6} KiB ({:.1}x source), peak: {:>6} KiB",
+            name,
+            usage.retained / 1024,
+            usage.retained as f64 / markdown.len() as f64,
+            usage.peak / 1024
+        );
+    }
+}
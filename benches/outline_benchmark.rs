@@ -1,6 +1,11 @@
 use criterion::{criterion_group, criterion_main, Bencher, Criterion};
 
+use eyre::Report;
+use freeout::entities::core::block::{Block, Blocks};
+use freeout::entities::core::block_range::BlockRange;
 use freeout::entities::core::freeout::{Freeout, FreeoutOptions};
+use freeout::entities::core::position::Position;
+use freeout::entities::reader::reader_trait::ReaderTrait;
 use freeout::readers::markdown::MarkdownReader;
 
 fn standard_markdown_benchmark(c: &mut Criterion) {
@@ -26,6 +31,7 @@ fn standard_markdown_benchmark(c: &mut Criterion) {
                     markdown_with_increasing_depths,
                     FreeoutOptions {
                         include_content: true,
+                        ..Default::default()
                     },
                 );
             },
@@ -34,21 +40,114 @@ fn standard_markdown_benchmark(c: &mut Criterion) {
     group.finish();
 }
 
+/// Reads the blocks of a document of sections at depths 1 to 4, without parsing, so that the benchmark measures the
+/// processing of the block tree alone.
+struct GeneratedReader {}
+
+impl GeneratedReader {
+    fn generate_markdown(headings: usize) -> String {
+        let mut markdown_with_many_headings = "".to_string();
+        for i in 0..headings {
+            let marker = "#".repeat(i % 4 + 1);
+            markdown_with_many_headings
+                .push_str(&format!("{} Title {}\n\nContent {}\n\n", marker, i, i));
+        }
+        markdown_with_many_headings
+    }
+}
+
+impl ReaderTrait for GeneratedReader {
+    fn read(&self, source: &str, _options: &FreeoutOptions) -> Result<Blocks, Report> {
+        let mut blocks = Blocks::new();
+        // the last block at each depth
+        let mut last_ids = [0; 5];
+        let mut offset = 0;
+        for (line_index, line) in source.split_inclusive('\n').enumerate() {
+            if let Some((marker, title)) = line
+                .trim_end()
+                .split_once(' ')
+                .filter(|_| line.starts_with('#'))
+            {
+                let id = blocks.len() + 1;
+                let depth = marker.len();
+                let parent_id = (depth > 1).then(|| last_ids[depth - 1]);
+                if let Some(parent_id) = parent_id {
+                    blocks.get_mut(&parent_id).unwrap().children_ids.push(id);
+                }
+                last_ids[depth] = id;
+
+                let position = |column: usize| Position {
+                    line: line_index,
+                    column,
+                    offset: offset + column,
+                };
+                blocks.insert(
+                    id,
+                    Block {
+                        id,
+                        depth,
+                        marker: marker.to_string(),
+                        title: title.to_string(),
+                        parent_id,
+                        header_range: BlockRange {
+                            start: position(0),
+                            end: position(line.trim_end().len()),
+                        },
+                        ..Default::default()
+                    },
+                );
+            } else if !line.trim().is_empty() {
+                let block = blocks.get_mut(&blocks.len()).unwrap();
+                let start = offset + line.len() - line.trim_start().len();
+                block
+                    .content_ranges
+                    .push(start..offset + line.trim_end().len());
+            }
+            offset += line.len();
+        }
+        Ok(blocks)
+    }
+}
+
+fn heading_count_benchmark(c: &mut Criterion) {
+    let mut group = c.benchmark_group("outline heading scaling");
+    group.sample_size(10);
+
+    for headings in [1_000, 10_000, 100_000].iter() {
+        group.throughput(criterion::Throughput::Elements(*headings as u64));
+        group.bench_with_input(
+            criterion::BenchmarkId::from_parameter(headings),
+            headings,
+            |b, headings| {
+                let markdown_with_many_headings = GeneratedReader::generate_markdown(*headings);
+                b.iter(|| {
+                    let mut freeout = Freeout::new(markdown_with_many_headings.clone(), None);
+                    freeout.process(&GeneratedReader {}).unwrap();
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
 fn generate_outline(
     b: &mut Bencher,
-    mut markdown_with_increasing_depths: String,
+    markdown_with_increasing_depths: String,
     options: FreeoutOptions,
 ) {
     let reader = MarkdownReader::default();
     b.iter(|| {
-        let _outline = Freeout::new(
+        let mut freeout = Freeout::new(
             markdown_with_increasing_depths.clone(),
             Some(options.clone()),
-        )
-        .outline(&reader)
-        .unwrap();
+        );
+        freeout.process(&reader).unwrap();
     })
 }
 
-criterion_group!(benches, standard_markdown_benchmark);
+criterion_group!(
+    benches,
+    standard_markdown_benchmark,
+    heading_count_benchmark
+);
 criterion_main!(benches);
@@ -0,0 +1,117 @@
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use eyre::{eyre, Report};
+use freeout::entities::core::freeout::Freeout;
+use freeout::entities::reader::reader_trait::ReaderTrait;
+use freeout::readers::get_reader_by_extension;
+use freeout::readers::markdown::MarkdownReader;
+
+/// Name of the standard input in messages.
+pub const STDIN_NAME: &str = "<stdin>";
+
+/// # Input
+///
+/// A document to outline, read from a file or the standard input.
+pub struct Input {
+    /// `None` for the standard input.
+    pub path: Option<PathBuf>,
+    pub source: String,
+}
+
+impl Input {
+    /// Read the files in order. No file, or `-`, reads the standard input.
+    pub fn read_all(files: &[PathBuf]) -> Result<Vec<Input>, Report> {
+        if files.is_empty() {
+            return Ok(vec![Self::read_stdin()?]);
+        }
+
+        files
+            .iter()
+            .map(|file| {
+                if file.as_os_str() == "-" {
+                    return Self::read_stdin();
+                }
+                let source = std::fs::read_to_string(file)
+                    .map_err(|err| eyre!("Failed to read {}: {}", file.display(), err))?;
+                Ok(Input {
+                    path: Some(file.clone()),
+                    source,
+                })
+            })
+            .collect()
+    }
+
+    fn read_stdin() -> Result<Input, Report> {
+        let mut source = String::new();
+        std::io::stdin().read_to_string(&mut source)?;
+        Ok(Input { path: None, source })
+    }
+
+    pub fn name(&self) -> String {
+        self.path
+            .as_ref()
+            .map_or(STDIN_NAME.to_string(), |path| path.display().to_string())
+    }
+
+    /// The reader of the input: the one named by `--reader`, or else the one of the file extension, or else Markdown.
+    pub fn get_reader(&self, reader_name: Option<&str>) -> Result<Box<dyn ReaderTrait>, Report> {
+        if let Some(reader_name) = reader_name {
+            return get_reader_by_extension(reader_name)
+                .ok_or_else(|| eyre!("Unknown reader: {}", reader_name));
+        }
+
+        Ok(self
+            .path
+            .as_deref()
+            .and_then(Path::extension)
+            .and_then(|extension| get_reader_by_extension(&extension.to_string_lossy()))
+            .unwrap_or_else(|| Box::new(MarkdownReader::default())))
+    }
+
+    /// Outline the input, prefixing errors with its name. The outline is then borrowed with `Freeout::get_outline`.
+    pub fn outline(&self, reader_name: Option<&str>) -> Result<Freeout, Report> {
+        let reader = self.get_reader(reader_name)?;
+        let mut freeout = Freeout::new(self.source.clone(), None);
+        if let Some(path) = &self.path {
+            freeout = freeout.with_path(path.clone());
+        }
+        freeout
+            .process(reader.as_ref())
+            .map_err(|err| eyre!("{}: {}", self.name(), err))?;
+        Ok(freeout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::input::Input;
+
+    #[test]
+    fn should_pick_reader_by_extension() {
+        let input = |path: Option<&str>| Input {
+            path: path.map(PathBuf::from),
+            source: "".to_string(),
+        };
+
+        assert_eq!(
+            input(Some("plan.OPML")).get_reader(None).unwrap().name(),
+            "OPML Reader"
+        );
+        assert_eq!(
+            input(Some("book.opml"))
+                .get_reader(Some("md"))
+                .unwrap()
+                .render_marker(2),
+            Some("##".to_string())
+        );
+        // Markdown by default, e.g. for the standard input
+        assert_eq!(
+            input(None).get_reader(None).unwrap().render_marker(1),
+            Some("#".to_string())
+        );
+        assert!(input(None).get_reader(Some("docx")).is_err());
+    }
+}
@@ -0,0 +1,330 @@
+//! # Freeout CLI
+//!
+//! Outlines documents, reports their word counts, exports them and checks them, e.g. in build scripts. `freeout lsp`
+//! serves the same features to editors.
+//!
+//! Each command takes files, or reads the standard input when no file or `-` is given. The reader is picked from
+//! the file extension, Markdown by default, unless `--reader` names one.
+mod input;
+mod render;
+
+use std::collections::BTreeMap;
+use std::ops::ControlFlow;
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand, ValueEnum};
+use eyre::{eyre, Report};
+use freeout::entities::lint::diagnostic::Severity;
+use freeout::entities::lint::lint_config::LintConfig;
+use freeout::entities::writer::writer_trait::WriterTrait;
+use freeout::watchers::outline_watcher::{watch, WatchEvent, WatchOptions};
+use freeout::watchers::watch_session::{WatchSession, WatchUpdate};
+use freeout::writers::mermaid::MermaidWriter;
+use freeout::writers::opml::OpmlWriter;
+use freeout::writers::table::TableWriter;
+use freeout::writers::toc::TocWriter;
+use rayon::prelude::*;
+use serde::Serialize;
+
+use crate::input::Input;
+use crate::render::{render_diagnostics, render_stats, render_tree, render_watch_update};
+
+#[derive(Parser)]
+#[command(name = "freeout", version, about = "Outline documents across formats")]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+
+    /// Reader to use instead of the one of the file extension, e.g. `md` or `opml`
+    #[arg(long, global = true)]
+    reader: Option<String>,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Print the outline of documents
+    Outline {
+        files: Vec<PathBuf>,
+        #[arg(long, value_enum, default_value_t = OutlineFormat::Tree)]
+        format: OutlineFormat,
+    },
+    /// Print the self and aggregate word counts of each section
+    Stats {
+        files: Vec<PathBuf>,
+        /// Maximum number of outline levels to print
+        #[arg(long)]
+        depth: Option<usize>,
+        /// Watch a file or a directory, and print the word counts again on every change
+        #[arg(long)]
+        watch: bool,
+        /// Output format. JSON prints a line per document, and per change in watch mode.
+        #[arg(long, value_enum, default_value_t = StatsFormat::Text)]
+        format: StatsFormat,
+    },
+    /// Export the outline of a document
+    Export {
+        files: Vec<PathBuf>,
+        #[arg(long, value_enum)]
+        format: ExportFormat,
+    },
+    /// Validate and lint documents, and exit with a failure on errors
+    Check {
+        files: Vec<PathBuf>,
+        /// Lint config file in JSON or YAML
+        #[arg(long)]
+        config: Option<PathBuf>,
+        /// Fail on warnings as well
+        #[arg(long)]
+        strict: bool,
+        #[arg(long, value_enum, default_value_t = CheckFormat::Text)]
+        format: CheckFormat,
+    },
+    /// Run a language server over the standard input and output, for editors
+    Lsp,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum OutlineFormat {
+    Tree,
+    Json,
+    Yaml,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum StatsFormat {
+    Text,
+    Json,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    Opml,
+    Csv,
+    Toc,
+    Mermaid,
+}
+
+impl ExportFormat {
+    fn get_writer(&self) -> Box<dyn WriterTrait> {
+        match self {
+            ExportFormat::Opml => Box::<OpmlWriter>::default(),
+            ExportFormat::Csv => Box::<TableWriter>::default(),
+            ExportFormat::Toc => Box::<TocWriter>::default(),
+            ExportFormat::Mermaid => Box::<MermaidWriter>::default(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum CheckFormat {
+    Text,
+    Json,
+}
+
+/// The outline or lint report of a document, in the JSON and YAML outputs.
+#[derive(Serialize)]
+struct DocumentOutput<T> {
+    path: String,
+    #[serde(flatten)]
+    output: T,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match run(&cli) {
+        Ok(code) => code,
+        Err(err) => {
+            eprintln!("error: {}", err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: &Cli) -> Result<ExitCode, Report> {
+    let reader = cli.reader.as_deref();
+    match &cli.command {
+        Command::Outline { files, format } => {
+            let mut outputs = vec![];
+            for input in Input::read_all(files)? {
+                let freeout = input.outline(reader)?;
+                match format {
+                    OutlineFormat::Tree => {
+                        print_header(&input, files.len());
+                        print!("{}", render_tree(&freeout.get_outline()));
+                    }
+                    _ => outputs.push(DocumentOutput {
+                        path: input.name(),
+                        output: freeout.to_outline(),
+                    }),
+                }
+            }
+            match format {
+                OutlineFormat::Json => println!("{}", serde_json::to_string_pretty(&outputs)?),
+                OutlineFormat::Yaml => print!("{}", serde_yaml::to_string(&outputs)?),
+                OutlineFormat::Tree => {}
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Stats {
+            files,
+            depth,
+            watch: true,
+            format,
+        } => watch_stats(files, reader, *depth, *format),
+        Command::Stats {
+            files,
+            depth,
+            format,
+            ..
+        } => {
+            for input in Input::read_all(files)? {
+                let freeout = input.outline(reader)?;
+                match format {
+                    StatsFormat::Text => {
+                        print_header(&input, files.len());
+                        print!("{}", render_stats(&freeout.get_outline(), *depth));
+                    }
+                    StatsFormat::Json => {
+                        let update =
+                            WatchSession::new(PathBuf::from(input.name())).update(&freeout);
+                        println!("{}", serde_json::to_string(&update)?);
+                    }
+                }
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Export { files, format } => {
+            // the outputs of several documents cannot be concatenated, e.g. CSV headers or OPML documents
+            if files.len() > 1 {
+                return Err(eyre!(
+                    "Export writes a single document, so it takes a single file"
+                ));
+            }
+            let writer = format.get_writer();
+            for input in Input::read_all(files)? {
+                let freeout = input.outline(reader)?;
+                print!("{}", writer.write(&freeout.to_outline())?);
+            }
+            Ok(ExitCode::SUCCESS)
+        }
+        Command::Check {
+            files,
+            config,
+            strict,
+            format,
+        } => check(files, reader, config.as_ref(), *strict, *format),
+        Command::Lsp => {
+            freeout::lsp::server::run_stdio()?;
+            Ok(ExitCode::SUCCESS)
+        }
+    }
+}
+
+fn check(
+    files: &[PathBuf],
+    reader: Option<&str>,
+    config: Option<&PathBuf>,
+    strict: bool,
+    format: CheckFormat,
+) -> Result<ExitCode, Report> {
+    let config: LintConfig = match config {
+        // YAML is a superset of JSON
+        Some(path) => serde_yaml::from_str(&std::fs::read_to_string(path)?)
+            .map_err(|err| eyre!("Invalid lint config {}: {}", path.display(), err))?,
+        None => LintConfig::default(),
+    };
+
+    let inputs = Input::read_all(files)?;
+    // documents are checked in parallel, e.g. a whole documentation repository, and reported in order
+    let checked: Vec<_> = inputs
+        .par_iter()
+        .map(|input| {
+            let freeout = input.outline(reader)?;
+            let report = freeout.lint(&config);
+            Ok::<_, Report>((freeout, report))
+        })
+        .collect();
+
+    let mut is_failed = false;
+    let mut outputs = vec![];
+    for (input, checked) in inputs.iter().zip(checked) {
+        // a document that cannot be outlined fails the check, but the others are still checked
+        let (freeout, report) = match checked {
+            Ok(checked) => checked,
+            Err(err) => {
+                eprintln!("error: {}", err);
+                is_failed = true;
+                continue;
+            }
+        };
+
+        is_failed |= report.has_errors() || (strict && report.count(Severity::Warning) > 0);
+        match format {
+            CheckFormat::Text => print!("{}", render_diagnostics(&input.name(), &freeout, &report)),
+            CheckFormat::Json => outputs.push(DocumentOutput {
+                path: input.name(),
+                output: report,
+            }),
+        }
+    }
+
+    if let CheckFormat::Json = format {
+        println!("{}", serde_json::to_string_pretty(&outputs)?);
+    }
+
+    Ok(if is_failed {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    })
+}
+
+/// Print the word counts of a watched file or directory on every change, until interrupted.
+fn watch_stats(
+    files: &[PathBuf],
+    reader: Option<&str>,
+    depth: Option<usize>,
+    format: StatsFormat,
+) -> Result<ExitCode, Report> {
+    let [path] = files else {
+        return Err(eyre!("Watch mode takes a single file or directory"));
+    };
+
+    // the latest update of each document, to refresh them all at once
+    let mut updates: BTreeMap<PathBuf, WatchUpdate> = BTreeMap::new();
+    let options = WatchOptions {
+        reader: reader.map(String::from),
+        ..WatchOptions::default()
+    };
+    watch(path, &options, |event| {
+        match (event, format) {
+            (WatchEvent::Updated(update), StatsFormat::Json) => {
+                match serde_json::to_string(&update) {
+                    Ok(line) => println!("{}", line),
+                    Err(err) => eprintln!("error: {}", err),
+                }
+            }
+            (WatchEvent::Updated(update), StatsFormat::Text) => {
+                updates.insert(update.path.clone(), update);
+                // clear the terminal, and move the cursor home
+                print!("\x1b[2J\x1b[H");
+                for update in updates.values() {
+                    println!("{}", render_watch_update(update, depth));
+                }
+            }
+            (WatchEvent::Failed { path, error }, _) => {
+                eprintln!("error: {}: {}", path.display(), error);
+            }
+        }
+        ControlFlow::Continue(())
+    })?;
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Print the name of the input before its output, when there are several inputs.
+fn print_header(input: &Input, file_count: usize) {
+    if file_count > 1 {
+        println!("==> {} <==", input.name());
+    }
+}
@@ -0,0 +1,204 @@
+use freeout::entities::core::block::Block;
+use freeout::entities::core::freeout::Freeout;
+use freeout::entities::core::outline::{OutlineItemRef, OutlineRef};
+use freeout::entities::lint::diagnostic::{LintReport, Severity};
+use freeout::watchers::watch_session::WatchUpdate;
+
+/// Render an outline as an indented tree, with the aggregate word count of each block.
+///
+/// # Example
+///
+/// ```text
+/// Book (120 words)
+///   Chapter 1 (80 words)
+///   Notes (40 words) [excluded]
+/// ```
+pub fn render_tree(outline: &OutlineRef) -> String {
+    let mut tree = String::new();
+    render_tree_items(&outline.items, 0, &mut tree);
+    tree
+}
+
+fn render_tree_items(items: &[OutlineItemRef], indent: usize, tree: &mut String) {
+    for item in items {
+        let block = item.block;
+        tree.push_str(&format!(
+            "{}{} ({} words){}\n",
+            "  ".repeat(indent),
+            block.title,
+            block.aggregate_stats.count.words,
+            if block.exclude { " [excluded]" } else { "" }
+        ));
+        render_tree_items(&item.subitems, indent + 1, tree);
+    }
+}
+
+/// Render the self and aggregate word counts of the blocks as a table, down to a depth in the outline.
+pub fn render_stats(outline: &OutlineRef, max_depth: Option<usize>) -> String {
+    let mut stats = format!(
+        "{:>8} {:>8} {:>8}  {}\n",
+        "words", "total", "target", "title"
+    );
+    render_stats_items(&outline.items, 1, max_depth, &mut stats);
+    stats
+}
+
+fn render_stats_items(
+    items: &[OutlineItemRef],
+    level: usize,
+    max_depth: Option<usize>,
+    stats: &mut String,
+) {
+    if max_depth.is_some_and(|max_depth| level > max_depth) {
+        return;
+    }
+
+    for item in items {
+        stats.push_str(&get_stats_row(item.block, level));
+        render_stats_items(&item.subitems, level + 1, max_depth, stats);
+    }
+}
+
+fn get_stats_row(block: &Block, level: usize) -> String {
+    let target = block
+        .aggregate_stats
+        .target
+        .as_ref()
+        .map_or("".to_string(), |target| target.words.to_string());
+    format!(
+        "{:>8} {:>8} {:>8}  {}{}{}\n",
+        block.self_stats.count.words,
+        block.aggregate_stats.count.words,
+        target,
+        "  ".repeat(level - 1),
+        block.title,
+        if block.exclude { " [excluded]" } else { "" }
+    )
+}
+
+/// Render the word counts of a watched document and their deltas, down to a depth in the outline.
+///
+/// # Example
+///
+/// ```text
+/// book.md: 1200 words (+15 since last run, +340 since start)
+///    words     last    start   target  title
+///     1200      +15     +340     2000  Book
+/// ```
+pub fn render_watch_update(update: &WatchUpdate, max_depth: Option<usize>) -> String {
+    let mut stats = format!(
+        "{}: {} words ({:+} since last run, {:+} since start)\n",
+        update.path.display(),
+        update.words,
+        update.since_last,
+        update.since_start
+    );
+    stats.push_str(&format!(
+        "{:>8} {:>8} {:>8} {:>8}  {}\n",
+        "words", "last", "start", "target", "title"
+    ));
+    for block in &update.blocks {
+        if max_depth.is_some_and(|max_depth| block.depth > max_depth) {
+            continue;
+        }
+        stats.push_str(&format!(
+            "{:>8} {:>+8} {:>+8} {:>8}  {}{}{}\n",
+            block.words,
+            block.since_last,
+            block.since_start,
+            block
+                .target
+                .map_or("".to_string(), |target| target.to_string()),
+            "  ".repeat(block.depth - 1),
+            block.title,
+            if block.exclude { " [excluded]" } else { "" }
+        ));
+    }
+    stats
+}
+
+/// Render diagnostics as `name:line:column: severity [rule] message`, with 1-indexed lines and columns.
+pub fn render_diagnostics(name: &str, freeout: &Freeout, report: &LintReport) -> String {
+    report
+        .diagnostics
+        .iter()
+        .map(|diagnostic| {
+            // blocks included from another file are reported in that file
+            let (name, position) = match &diagnostic.source_path {
+                Some(source_path) => (
+                    source_path.display().to_string(),
+                    diagnostic.range.start.clone(),
+                ),
+                None => (
+                    name.to_string(),
+                    freeout.get_position(diagnostic.range.start.offset),
+                ),
+            };
+            let severity = match diagnostic.severity {
+                Severity::Error => "error",
+                Severity::Warning => "warning",
+                _ => "info",
+            };
+            format!(
+                "{}:{}:{}: {} [{}] {}\n",
+                name,
+                position.line + 1,
+                position.column + 1,
+                severity,
+                diagnostic.rule.name(),
+                diagnostic.message
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use freeout::entities::core::freeout::Freeout;
+    use freeout::entities::lint::lint_config::LintConfig;
+    use freeout::readers::markdown::MarkdownReader;
+    use freeout::watchers::watch_session::WatchSession;
+
+    use crate::render::{render_diagnostics, render_stats, render_tree, render_watch_update};
+
+    const MARKDOWN: &str =
+        "# Book\n\nOne two\n\n### Chapter\n\nThree\n\n## Notes {.exclude}\n\nFour\n";
+
+    #[test]
+    fn should_render_tree_stats_and_diagnostics() {
+        let mut freeout = Freeout::new(MARKDOWN.to_string(), None);
+        let outline = freeout.outline(&MarkdownReader::default()).unwrap();
+
+        assert_eq!(
+            render_tree(&outline),
+            "Book (5 words)\n  Chapter (2 words)\n  Notes (3 words) [excluded]\n"
+        );
+        assert_eq!(
+            render_stats(&outline, Some(1)),
+            "   words    total   target  title\n       3        5           Book\n"
+        );
+
+        let mut session = WatchSession::new(PathBuf::from("book.md"));
+        session.update(&freeout);
+        let mut freeout = Freeout::new(MARKDOWN.replace("Three", "Three four"), None);
+        freeout.outline(&MarkdownReader::default()).unwrap();
+        assert_eq!(
+            render_watch_update(&session.update(&freeout), Some(2)),
+            "book.md: 6 words (+1 since last run, +1 since start)\n   words     last    start   target  title\n       6       +1       +1           Book\n       3       +1       +1             Chapter\n       3       +0       +0             Notes [excluded]\n"
+        );
+
+        let report = freeout.lint(&LintConfig::default());
+        assert_eq!(
+            render_diagnostics("book.md", &freeout, &report),
+            "book.md:5:1: warning [skipped-heading-level] Heading level skipped: depth 3 under depth 1\n"
+        );
+
+        // included blocks are reported in their own file
+        let mut report = report;
+        report.diagnostics[0].source_path = Some(PathBuf::from("snippets/setup.md"));
+        assert!(render_diagnostics("book.md", &freeout, &report)
+            .starts_with("snippets/setup.md:5:1: warning [skipped-heading-level]"));
+    }
+}
@@ -0,0 +1,11 @@
+//! # Editors
+//!
+//! Operations that restructure the source of an outlined `Freeout`.
+//!
+//! Editors do not modify the `Freeout`. Each operation returns an `EditResult`: the new source, and the `TextEdit`s
+//! computed from the `header_range` and `block_range` of the blocks, so that an editor can apply them in place. To
+//! keep editing, outline the new source again.
+//!
+//! Rewriting section markers relies on `ReaderTrait::render_marker`, so the reader of the source has to be given.
+pub mod section_editor;
+pub mod structure_editor;
@@ -0,0 +1,388 @@
+use std::path::Path;
+
+use eyre::{eyre, Report};
+
+use crate::entities::core::block::Block;
+use crate::entities::core::freeout::Freeout;
+use crate::entities::core::text_edit::EditResult;
+use crate::entities::reader::reader_trait::ReaderTrait;
+use crate::utils::anchor_utils::AnchorUtils;
+use crate::utils::text_utils::TextUtils;
+
+/// How the index of a split document refers to its parts.
+#[derive(Clone, Debug, PartialEq, Default)]
+pub enum IndexStyle {
+    /// A Markdown link, e.g. `[Methods](02-methods.md)`.
+    #[default]
+    Link,
+    /// An mdBook include directive, e.g. `{{#include 02-methods.md}}`.
+    Include,
+}
+
+impl IndexStyle {
+    /// The reference to a part, as a paragraph of its own.
+    fn get_reference(&self, title: &str, path: &str) -> String {
+        match self {
+            IndexStyle::Link => format!(
+                "[{}]({})\n\n",
+                TextUtils::escape_link_text(title),
+                TextUtils::get_link_destination(path)
+            ),
+            IndexStyle::Include => format!("{{{{#include {}}}}}\n\n", path),
+        }
+    }
+}
+
+/// # Split Options
+#[derive(Clone, Debug)]
+pub struct SplitOptions {
+    /// Logical depth of the blocks that get a file of their own, with their subtree, so that skipped heading levels
+    /// do not matter.
+    pub depth: usize,
+    /// Extension of the files, without the dot.
+    pub extension: String,
+    pub index_style: IndexStyle,
+    /// Prefix file names with their number, e.g. `02-methods.md`, so that they sort in document order.
+    pub number_files: bool,
+    /// Shift the depth of each part, so that its block is at depth 1 in its file.
+    ///
+    /// Leave it off for `IndexStyle::Include`, as included parts are expected at their original depth.
+    pub rebase_depth: bool,
+}
+
+impl Default for SplitOptions {
+    fn default() -> Self {
+        Self {
+            depth: 1,
+            extension: "md".to_string(),
+            index_style: IndexStyle::default(),
+            number_files: true,
+            rebase_depth: true,
+        }
+    }
+}
+
+/// A document to be saved, with a path relative to its index or source.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DocumentFile {
+    pub path: String,
+    pub text: String,
+}
+
+impl DocumentFile {
+    /// Save the document under a folder, creating the folders of its path.
+    pub fn save(&self, folder: &Path) -> Result<(), Report> {
+        let path = folder.join(&self.path);
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(path, &self.text)?;
+        Ok(())
+    }
+}
+
+/// # Split Result
+///
+/// The index keeps everything outside the parts, e.g. the title of the document, and refers to each part in its place.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SplitResult {
+    pub index: String,
+    pub files: Vec<DocumentFile>,
+}
+
+/// # Extract Result
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExtractResult {
+    /// The edit of the source, which replaces the subtree with a link to the file.
+    pub source: EditResult,
+    pub file: DocumentFile,
+}
+
+/// A document to merge, and the depth of its top level in the merged document.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MergePart {
+    pub text: String,
+    pub depth: usize,
+}
+
+impl Freeout {
+    /// # Split sections into files
+    ///
+    /// Gives every block at the logical depth `SplitOptions::depth` a file of its own, with its subtree, and replaces
+    /// its section with a reference to the file in the index.
+    pub fn split_sections<R>(
+        &self,
+        reader: &R,
+        options: &SplitOptions,
+    ) -> Result<SplitResult, Report>
+    where
+        R: ReaderTrait,
+    {
+        // block ids follow the document order
+        let blocks: Vec<&Block> = (1..=self.blocks.len())
+            .map(|block_id| &self.blocks[&block_id])
+            .collect();
+        let anchors = AnchorUtils::get_anchors(blocks.iter().copied());
+
+        let mut files = vec![];
+        let mut edits = vec![];
+        for block in blocks
+            .iter()
+            .filter(|block| block.logical_depth == options.depth)
+        {
+            let number = files.len() + 1;
+            let name = match anchors[&block.id].as_str() {
+                "" => format!("section-{}", number),
+                anchor => anchor.to_string(),
+            };
+            let path = if options.number_files {
+                format!("{:02}-{}.{}", number, name, options.extension)
+            } else {
+                format!("{}.{}", name, options.extension)
+            };
+
+            let shift = if options.rebase_depth {
+                1 - block.depth as isize
+            } else {
+                0
+            };
+            let text = self.get_shifted_section(reader, block.id, shift)?;
+
+            let (start, end) = self.get_section_offsets(block.id);
+            edits.push(self.get_text_edit(
+                start,
+                end,
+                options.index_style.get_reference(&block.title, &path),
+            ));
+            files.push(DocumentFile {
+                path,
+                text: get_document_text(&text),
+            });
+        }
+
+        if files.is_empty() {
+            return Err(eyre!("No block at depth {} to split", options.depth));
+        }
+
+        Ok(SplitResult {
+            index: get_document_text(&EditResult::new(&self.text, edits).text),
+            files,
+        })
+    }
+
+    /// # Extract a section into a file
+    ///
+    /// Moves a block and its subtree into a file at `path`, at depth 1, and leaves a link to the file in its place.
+    pub fn extract_section<R>(
+        &self,
+        reader: &R,
+        block_id: usize,
+        path: &str,
+    ) -> Result<ExtractResult, Report>
+    where
+        R: ReaderTrait,
+    {
+        let block = self.get_existing_block(block_id)?;
+        let text = self.get_shifted_section(reader, block_id, 1 - block.depth as isize)?;
+
+        let (start, end) = self.get_section_offsets(block_id);
+        let mut reference = IndexStyle::Link.get_reference(&block.title, path);
+        if end == self.text.len() {
+            reference = get_document_text(&reference);
+        }
+        let edits = vec![self.get_text_edit(start, end, reference)];
+
+        Ok(ExtractResult {
+            source: EditResult::new(&self.text, edits),
+            file: DocumentFile {
+                path: path.to_string(),
+                text: get_document_text(&text),
+            },
+        })
+    }
+
+    /// # Merge documents
+    ///
+    /// Concatenates documents in order, shifting the depth of each one so that its top level is at
+    /// `MergePart::depth`, e.g. to merge the files of `split_sections` back under the headings of their index.
+    pub fn merge_documents<R>(reader: &R, parts: &[MergePart]) -> Result<String, Report>
+    where
+        R: ReaderTrait,
+    {
+        let mut documents = vec![];
+        for part in parts {
+            let mut freeout = Freeout::new(part.text.clone(), None);
+            freeout.process(reader)?;
+
+            // content before the first heading stays as is
+            let first_offset = if freeout.blocks.is_empty() {
+                freeout.text.len()
+            } else {
+                freeout.get_section_offsets(1).0
+            };
+            let mut document = freeout.text[..first_offset].to_string();
+
+            // the whole document is shifted alike, to keep the depths relative to each other
+            let top_depth = freeout.blocks.values().map(|block| block.depth).min();
+            let shift = top_depth.map_or(0, |top_depth| part.depth as isize - top_depth as isize);
+            let mut block_id = 1;
+            while freeout.blocks.contains_key(&block_id) {
+                document.push_str(&freeout.get_shifted_section(reader, block_id, shift)?);
+                block_id += freeout.get_subtree_ids(block_id).len();
+            }
+
+            documents.push(get_document_text(&document).trim_end().to_string());
+        }
+
+        Ok(get_document_text(&documents.join("\n\n")))
+    }
+}
+
+/// The text with a single trailing newline.
+fn get_document_text(text: &str) -> String {
+    format!("{}\n", text.trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::editors::section_editor::{IndexStyle, MergePart, SplitOptions};
+    use crate::entities::core::freeout::Freeout;
+    use crate::readers::markdown::MarkdownReader;
+
+    const MARKDOWN: &str =
+        "# Report\n\nSummary\n\n## Methods\n\nOne\n\n### Data\n\nTwo\n\n## Results\n\nThree\n";
+
+    fn get_freeout() -> Freeout {
+        let mut freeout = Freeout::new(MARKDOWN.to_string(), None);
+        freeout.outline(&MarkdownReader::default()).unwrap();
+        freeout
+    }
+
+    #[test]
+    fn should_split_and_merge_back() {
+        let reader = MarkdownReader::default();
+        let result = get_freeout()
+            .split_sections(
+                &reader,
+                &SplitOptions {
+                    depth: 2,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        assert_eq!(
+            result.index,
+            "# Report\n\nSummary\n\n[Methods](01-methods.md)\n\n[Results](02-results.md)\n"
+        );
+        assert_eq!(result.files.len(), 2);
+        assert_eq!(result.files[0].path, "01-methods.md");
+        assert_eq!(result.files[0].text, "# Methods\n\nOne\n\n## Data\n\nTwo\n");
+        assert_eq!(result.files[1].text, "# Results\n\nThree\n");
+
+        let merged = Freeout::merge_documents(
+            &reader,
+            &[
+                MergePart {
+                    text: "# Report\n\nSummary\n".to_string(),
+                    depth: 1,
+                },
+                MergePart {
+                    text: result.files[0].text.clone(),
+                    depth: 2,
+                },
+                MergePart {
+                    text: result.files[1].text.clone(),
+                    depth: 2,
+                },
+            ],
+        )
+        .unwrap();
+        assert_eq!(merged, MARKDOWN);
+    }
+
+    #[test]
+    fn should_split_with_include_directives() {
+        let result = get_freeout()
+            .split_sections(
+                &MarkdownReader::default(),
+                &SplitOptions {
+                    depth: 2,
+                    index_style: IndexStyle::Include,
+                    number_files: false,
+                    rebase_depth: false,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        assert_eq!(
+            result.index,
+            "# Report\n\nSummary\n\n{{#include methods.md}}\n\n{{#include results.md}}\n"
+        );
+        assert_eq!(
+            result.files[0].text,
+            "## Methods\n\nOne\n\n### Data\n\nTwo\n"
+        );
+
+        let freeout = get_freeout();
+        assert!(freeout
+            .split_sections(
+                &MarkdownReader::default(),
+                &SplitOptions {
+                    depth: 4,
+                    ..Default::default()
+                }
+            )
+            .is_err());
+    }
+
+    #[test]
+    fn should_split_at_logical_depth() {
+        let mut freeout = Freeout::new(
+            "# Report\n\n### Methods\n\nOne\n\n### Results\n\nTwo\n".to_string(),
+            None,
+        );
+        freeout.process(&MarkdownReader::default()).unwrap();
+        let result = freeout
+            .split_sections(
+                &MarkdownReader::default(),
+                &SplitOptions {
+                    depth: 2,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+        assert_eq!(
+            result.index,
+            "# Report\n\n[Methods](01-methods.md)\n\n[Results](02-results.md)\n"
+        );
+        assert_eq!(result.files[0].text, "# Methods\n\nOne\n");
+    }
+
+    #[test]
+    fn should_extract_section_and_leave_link() {
+        let result = get_freeout()
+            .extract_section(&MarkdownReader::default(), 3, "parts/data.md")
+            .unwrap();
+
+        assert_eq!(result.file.text, "# Data\n\nTwo\n");
+        assert_eq!(
+            result.source.text,
+            "# Report\n\nSummary\n\n## Methods\n\nOne\n\n[Data](parts/data.md)\n\n## Results\n\nThree\n"
+        );
+        assert_eq!(result.source.edits[0].range.start.line, 8);
+
+        // the title and path of the link are escaped
+        let mut freeout = Freeout::new("# Data [draft]\n\nTwo\n".to_string(), None);
+        freeout.process(&MarkdownReader::default()).unwrap();
+        let result = freeout
+            .extract_section(&MarkdownReader::default(), 1, "parts/my data.md")
+            .unwrap();
+        assert_eq!(
+            result.source.text,
+            "[Data \\[draft\\]](<parts/my data.md>)\n"
+        );
+    }
+}
@@ -0,0 +1,420 @@
+use eyre::{eyre, Report};
+
+use crate::entities::core::block::Block;
+use crate::entities::core::block_range::BlockRange;
+use crate::entities::core::freeout::Freeout;
+use crate::entities::core::text_edit::{EditResult, TextEdit};
+use crate::entities::reader::reader_trait::ReaderTrait;
+use crate::utils::block_utils::BlockUtils;
+
+/// Where to move a block, relative to another block.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MoveTarget {
+    /// Right before the block, as its previous sibling.
+    Before(usize),
+    /// Right after the block and its subtree, as its next sibling.
+    After(usize),
+    /// As the last child of the block.
+    Under(usize),
+}
+
+impl Freeout {
+    /// # Move a block
+    ///
+    /// Moves a block together with its subtree, and rewrites the markers of the subtree for its new depth.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a block does not exist, if the target is in the subtree of the moved block, or if a marker
+    /// cannot be rewritten.
+    pub fn move_block<R>(
+        &self,
+        reader: &R,
+        block_id: usize,
+        target: MoveTarget,
+    ) -> Result<EditResult, Report>
+    where
+        R: ReaderTrait,
+    {
+        let block = self.get_existing_block(block_id)?;
+        let target_id = match target {
+            MoveTarget::Before(id) | MoveTarget::After(id) | MoveTarget::Under(id) => id,
+        };
+        let target_block = self.get_existing_block(target_id)?;
+        if self.get_subtree_ids(block_id).contains(&target_id) {
+            return Err(eyre!(
+                "Cannot move block {} relative to block {} in its own subtree",
+                block_id,
+                target_id
+            ));
+        }
+
+        let (target_start, target_end) = self.get_section_offsets(target_id);
+        let (insert_offset, new_depth) = match target {
+            MoveTarget::Before(_) => (target_start, target_block.depth),
+            MoveTarget::After(_) => (target_end, target_block.depth),
+            MoveTarget::Under(_) => (target_end, target_block.depth + 1),
+        };
+
+        // rewrite the markers within the moved section only, as its original range is deleted
+        let (start, end) = self.get_section_offsets(block_id);
+        let mut section =
+            self.get_shifted_section(reader, block_id, new_depth as isize - block.depth as isize)?;
+        if !section.ends_with('\n') {
+            section.push('\n');
+        }
+        // keep a blank line between the moved section and the headings around it
+        let following_text = if start == insert_offset {
+            &self.text[end..]
+        } else {
+            &self.text[insert_offset..]
+        };
+        if !following_text.is_empty() && !section.ends_with("\n\n") {
+            section.push('\n');
+        }
+        let preceding_text = if end == insert_offset {
+            &self.text[..start]
+        } else {
+            &self.text[..insert_offset]
+        };
+        if !preceding_text.is_empty() && !preceding_text.ends_with("\n\n") {
+            let missing_newlines = if preceding_text.ends_with('\n') { 1 } else { 2 };
+            section.insert_str(0, &"\n".repeat(missing_newlines));
+        }
+
+        let edits = vec![
+            self.get_text_edit(start, end, "".to_string()),
+            self.get_text_edit(insert_offset, insert_offset, section),
+        ];
+        Ok(EditResult::new(&self.text, edits))
+    }
+
+    /// # Promote a block
+    ///
+    /// Decreases the depth of a block and its whole subtree by one, e.g. from `###` to `##` in Markdown.
+    pub fn promote_block<R>(&self, reader: &R, block_id: usize) -> Result<EditResult, Report>
+    where
+        R: ReaderTrait,
+    {
+        let edits = self.get_marker_edits(reader, block_id, -1)?;
+        Ok(EditResult::new(&self.text, edits))
+    }
+
+    /// # Demote a block
+    ///
+    /// Increases the depth of a block and its whole subtree by one, e.g. from `##` to `###` in Markdown.
+    pub fn demote_block<R>(&self, reader: &R, block_id: usize) -> Result<EditResult, Report>
+    where
+        R: ReaderTrait,
+    {
+        let edits = self.get_marker_edits(reader, block_id, 1)?;
+        Ok(EditResult::new(&self.text, edits))
+    }
+
+    /// # Normalize heading levels
+    ///
+    /// Rewrites the marker of every block whose `depth` differs from its `logical_depth`, so that the top level of
+    /// the document is at depth 1 and no level is skipped, e.g. `##`, `####` becomes `#`, `##`.
+    ///
+    /// Returns one edit per rewritten marker, and none if the document is already normalized.
+    pub fn normalize_headings<R>(&self, reader: &R) -> Result<EditResult, Report>
+    where
+        R: ReaderTrait,
+    {
+        let edits = (1..=self.blocks.len())
+            .filter(|block_id| {
+                let block = &self.blocks[block_id];
+                block.depth != block.logical_depth && block.source_path.is_none()
+            })
+            .map(|block_id| {
+                self.get_marker_edit(reader, block_id, self.blocks[&block_id].logical_depth)
+            })
+            .collect::<Result<Vec<TextEdit>, Report>>()?;
+        Ok(EditResult::new(&self.text, edits))
+    }
+
+    /// # Delete a block
+    ///
+    /// Deletes a block together with its subtree.
+    pub fn delete_block(&self, block_id: usize) -> Result<EditResult, Report> {
+        self.get_existing_block(block_id)?;
+        let (start, end) = self.get_section_offsets(block_id);
+        let edits = vec![self.get_text_edit(start, end, "".to_string())];
+        Ok(EditResult::new(&self.text, edits))
+    }
+
+    pub(crate) fn get_existing_block(&self, block_id: usize) -> Result<&Block, Report> {
+        let block = self
+            .blocks
+            .get(&block_id)
+            .ok_or_else(|| eyre!("Block {} does not exist", block_id))?;
+        match &block.source_path {
+            Some(path) => Err(eyre!(
+                "Block {} is included from {}, and can only be edited there",
+                block_id,
+                path.display()
+            )),
+            None => Ok(block),
+        }
+    }
+
+    /// Ids of a block and its descendants, which follow it in document order.
+    pub(crate) fn get_subtree_ids(&self, block_id: usize) -> Vec<usize> {
+        let last_id = self.tree.get_last_descendant(block_id).unwrap_or(block_id);
+        (block_id..=last_id).collect()
+    }
+
+    /// Byte offsets of the section of a block: from the start of its header line to the start of the header line of
+    /// its next sibling or uncle, or the end of the source.
+    ///
+    /// Unlike the `block_range`, the section includes the trailing newline, so that sections can be moved as whole
+    /// lines.
+    pub(crate) fn get_section_offsets(&self, block_id: usize) -> (usize, usize) {
+        let block = &self.blocks[&block_id];
+        let start = self.get_header_line_start(block.header_range.start.offset);
+        let end = BlockUtils::get_next_sibling_or_uncle_id_in_tree(&self.tree, &block_id)
+            .map(|next_id| {
+                self.get_header_line_start(self.blocks[&next_id].header_range.start.offset)
+            })
+            .unwrap_or(self.text.len());
+        (start, end)
+    }
+
+    /// The start of the line of a header, if only indentation precedes the header on its line.
+    fn get_header_line_start(&self, offset: usize) -> usize {
+        let line_start = offset - self.get_position(offset).column;
+        if self.text[line_start..offset].trim().is_empty() {
+            line_start
+        } else {
+            offset
+        }
+    }
+
+    /// The section of a block with the depth of its subtree shifted, e.g. to move it elsewhere.
+    pub(crate) fn get_shifted_section<R>(
+        &self,
+        reader: &R,
+        block_id: usize,
+        shift: isize,
+    ) -> Result<String, Report>
+    where
+        R: ReaderTrait,
+    {
+        let (start, end) = self.get_section_offsets(block_id);
+        let mut section = self.text[start..end].to_string();
+        for edit in self.get_marker_edits(reader, block_id, shift)?.iter().rev() {
+            section.replace_range(
+                edit.range.start.offset - start..edit.range.end.offset - start,
+                &edit.new_text,
+            );
+        }
+        Ok(section)
+    }
+
+    /// Edits that shift the depth of a block and its subtree, by replacing their markers.
+    pub(crate) fn get_marker_edits<R>(
+        &self,
+        reader: &R,
+        block_id: usize,
+        shift: isize,
+    ) -> Result<Vec<TextEdit>, Report>
+    where
+        R: ReaderTrait,
+    {
+        self.get_existing_block(block_id)?;
+        if shift == 0 {
+            return Ok(vec![]);
+        }
+
+        // included blocks follow the depth of their include directive
+        self.get_subtree_ids(block_id)
+            .into_iter()
+            .filter(|id| self.blocks[id].source_path.is_none())
+            .map(|id| {
+                let new_depth = self.blocks[&id].depth as isize + shift;
+                if new_depth < 1 {
+                    return Err(eyre!("Block {} cannot be promoted above depth 1", id));
+                }
+                self.get_marker_edit(reader, id, new_depth as usize)
+            })
+            .collect()
+    }
+
+    /// Edit that replaces the marker of a block with the marker of a new depth.
+    fn get_marker_edit<R>(
+        &self,
+        reader: &R,
+        block_id: usize,
+        new_depth: usize,
+    ) -> Result<TextEdit, Report>
+    where
+        R: ReaderTrait,
+    {
+        let block = &self.blocks[&block_id];
+        let marker = reader.render_marker(new_depth).ok_or_else(|| {
+            eyre!(
+                "{} has no marker at depth {} for block {}",
+                reader.name(),
+                new_depth,
+                block_id
+            )
+        })?;
+
+        // e.g. a setext heading in Markdown has no marker at the start of its header
+        let start = block.header_range.start.offset;
+        if block.marker.is_empty() || !self.text[start..].starts_with(&block.marker) {
+            return Err(eyre!(
+                "Block {} has no marker `{}` at the start of its header",
+                block_id,
+                block.marker
+            ));
+        }
+        Ok(self.get_text_edit(start, start + block.marker.len(), marker))
+    }
+
+    pub(crate) fn get_text_edit(&self, start: usize, end: usize, new_text: String) -> TextEdit {
+        TextEdit {
+            range: BlockRange {
+                start: self.get_position(start),
+                end: self.get_position(end),
+            },
+            new_text,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::editors::structure_editor::MoveTarget;
+    use crate::entities::core::freeout::Freeout;
+    use crate::entities::core::text_edit::TextEdit;
+    use crate::readers::markdown::MarkdownReader;
+
+    const MARKDOWN: &str =
+        "# Book\n\nIntro\n\n## Chapter 1\n\nOne\n\n### Scene\n\nTwo\n\n## Chapter 2\n\nThree";
+
+    fn get_freeout() -> Freeout {
+        let mut freeout = Freeout::new(MARKDOWN.to_string(), None);
+        freeout.outline(&MarkdownReader::default()).unwrap();
+        freeout
+    }
+
+    #[test]
+    fn should_move_subtree_after_sibling() {
+        let freeout = get_freeout();
+        let result = freeout
+            .move_block(&MarkdownReader::default(), 2, MoveTarget::After(4))
+            .unwrap();
+
+        assert_eq!(
+            result.text,
+            "# Book\n\nIntro\n\n## Chapter 2\n\nThree\n\n## Chapter 1\n\nOne\n\n### Scene\n\nTwo\n\n"
+        );
+        assert_eq!(result.edits.len(), 2);
+        assert_eq!(result.edits[0].range.start.line, 4);
+        assert_eq!(result.edits[0].range.end.line, 12);
+        assert_eq!(TextEdit::apply(MARKDOWN, &result.edits), result.text);
+    }
+
+    #[test]
+    fn should_move_subtree_under_new_parent_with_new_markers() {
+        let freeout = get_freeout();
+        let result = freeout
+            .move_block(&MarkdownReader::default(), 4, MoveTarget::Under(3))
+            .unwrap();
+
+        assert_eq!(
+            result.text,
+            "# Book\n\nIntro\n\n## Chapter 1\n\nOne\n\n### Scene\n\nTwo\n\n#### Chapter 2\n\nThree\n"
+        );
+
+        let result = freeout
+            .move_block(&MarkdownReader::default(), 3, MoveTarget::Before(1))
+            .unwrap();
+        assert!(result.text.starts_with("# Scene\n\nTwo\n\n# Book\n"));
+
+        // the last section without a trailing newline
+        let result = freeout
+            .move_block(&MarkdownReader::default(), 4, MoveTarget::Before(2))
+            .unwrap();
+        assert_eq!(
+            result.text,
+            "# Book\n\nIntro\n\n## Chapter 2\n\nThree\n\n## Chapter 1\n\nOne\n\n### Scene\n\nTwo\n\n"
+        );
+    }
+
+    #[test]
+    fn should_promote_and_demote_subtree() {
+        let freeout = get_freeout();
+        let reader = MarkdownReader::default();
+
+        let result = freeout.promote_block(&reader, 2).unwrap();
+        assert_eq!(
+            result.text,
+            "# Book\n\nIntro\n\n# Chapter 1\n\nOne\n\n## Scene\n\nTwo\n\n## Chapter 2\n\nThree"
+        );
+        assert_eq!(result.edits.len(), 2);
+        assert_eq!(result.edits[0].new_text, "#");
+        assert_eq!(result.edits[0].range.start.offset, 15);
+        assert_eq!(result.edits[0].range.end.offset, 17);
+
+        let result = freeout.demote_block(&reader, 1).unwrap();
+        assert!(result.text.contains("\n#### Scene\n"));
+    }
+
+    #[test]
+    fn should_normalize_skipped_heading_levels() {
+        let reader = MarkdownReader::default();
+        let markdown = "## Intro\n\n#### Scene\n\n## Part\n\n### Chapter\n\n###### Note\n";
+        let mut freeout = Freeout::new(markdown.to_string(), None);
+        freeout.outline(&reader).unwrap();
+
+        let result = freeout.normalize_headings(&reader).unwrap();
+        assert_eq!(
+            result.text,
+            "# Intro\n\n## Scene\n\n# Part\n\n## Chapter\n\n### Note\n"
+        );
+        assert_eq!(result.edits.len(), 5);
+        assert_eq!(result.edits[1].range.start.line, 2);
+        assert_eq!(result.edits[1].new_text, "##");
+
+        // already normalized
+        let mut freeout = Freeout::new(result.text, None);
+        freeout.outline(&reader).unwrap();
+        assert!(freeout
+            .normalize_headings(&reader)
+            .unwrap()
+            .edits
+            .is_empty());
+    }
+
+    #[test]
+    fn should_delete_subtree() {
+        let freeout = get_freeout();
+        let result = freeout.delete_block(2).unwrap();
+
+        assert_eq!(result.text, "# Book\n\nIntro\n\n## Chapter 2\n\nThree");
+    }
+
+    #[test]
+    fn should_reject_invalid_edits() {
+        let freeout = get_freeout();
+        let reader = MarkdownReader::default();
+
+        // into its own subtree
+        assert!(freeout
+            .move_block(&reader, 2, MoveTarget::Under(3))
+            .is_err());
+        assert!(freeout
+            .move_block(&reader, 2, MoveTarget::Before(2))
+            .is_err());
+        // above the top level
+        assert!(freeout.promote_block(&reader, 1).is_err());
+        assert!(freeout.delete_block(9).is_err());
+
+        // Markdown has no heading beyond depth 6
+        let mut freeout = Freeout::new("## A\n\n###### B\n".to_string(), None);
+        freeout.outline(&reader).unwrap();
+        assert!(freeout.demote_block(&reader, 1).is_err());
+    }
+}
@@ -1,14 +1,18 @@
 /// # Block
 ///
 /// A block is the building block of a tree.
+use std::borrow::Cow;
 use std::collections::HashMap;
-use std::hash::Hash;
+use std::ops::Range;
+use std::path::PathBuf;
 
+use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 
 use crate::entities::core::block_range::BlockRange;
 use crate::entities::core::words_statistics::WordStatistics;
-use crate::entities::visitor::visitor_trait::Visitor;
+use crate::entities::visitor::visitor_trait::{VisitFlow, Visitor};
+use crate::utils::visit_utils::VisitUtils;
 
 pub type Blocks = HashMap<usize, Block>;
 
@@ -20,12 +24,24 @@ pub struct Block {
     pub id: usize,
 
     pub depth: usize,
+    /// Depth of the block in the tree: 1 for root blocks, and the logical depth of the parent + 1 for the others.
+    ///
+    /// Unlike `depth`, it has no skipped levels, e.g. a `###` heading right under a `#` heading has a logical depth
+    /// of 2. Computed by Freeout.
+    pub logical_depth: usize,
     /// Section marker, e.g. # for markdown, and = for typst.
     pub marker: String,
     /// Section title
     pub title: String,
     /// plain content if the config asked the parser to return plain text of this block
+    ///
+    /// Readers that find the content in the source give its `content_ranges` instead, and `Freeout::to_outline` fills
+    /// it in from them.
     pub content: Option<String>,
+    /// Byte ranges of the plain content of this block in its source, e.g. the text of its heading and paragraphs,
+    /// joined by newlines. Resolved with `Freeout::get_content`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub content_ranges: Vec<Range<usize>>,
     /// Section note
     pub note: Option<String>,
     /// Parend id
@@ -74,26 +90,71 @@ pub struct Block {
     pub aggregate_stats: WordStatistics,
 
     /// Whether this block should be excluded from all calculations and basically ignored. This is needed when, for example, I want to mark a section as a note, but I don't want it to be counted in the word count.
+    ///
+    /// Set by the reader or the `ExclusionRules` of `FreeoutOptions`, and inherited by the whole subtree. Excluded blocks stay in the outline, but do not count toward the `aggregate_stats` or the target distribution of their parents.
     pub exclude: bool,
 
     pub hash: Option<u64>,
 
+    /// The file the block was read from, in a `Project` of many files. `None` for a single document.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_path: Option<PathBuf>,
+
     pub children_ids: Vec<usize>, // Indices of child blocks.
 }
 
 // impl Visitor pattern for Block
 impl Block {
-    pub fn accept(&self, visitor: &mut dyn Visitor, blocks: &HashMap<usize, Block>) {
-        visitor.visit_block(self);
+    /// Visit the block and its subtree, see `VisitUtils::walk_subtree`.
+    pub fn accept(&self, visitor: &mut dyn Visitor, blocks: &HashMap<usize, Block>) -> VisitFlow {
+        VisitUtils::walk_subtree(self, blocks, visitor)
+    }
 
-        for &child_id in &self.children_ids {
-            if let Some(child) = blocks.get(&child_id) {
-                child.accept(visitor, blocks);
-            }
+    /// The plain content of the block: its `content`, or else the text of its `content_ranges` in its source, joined
+    /// by newlines.
+    pub fn get_content<'a>(&'a self, source: &'a str) -> Option<Cow<'a, str>> {
+        if let Some(content) = &self.content {
+            return Some(Cow::Borrowed(content));
         }
+
+        let mut texts = self
+            .content_ranges
+            .iter()
+            .filter_map(|range| source.get(range.clone()));
+        let first_text = texts.next()?;
+        match texts.next() {
+            // a single range is borrowed as is
+            None => Some(Cow::Borrowed(first_text)),
+            Some(second_text) => Some(Cow::Owned(
+                [first_text, second_text]
+                    .into_iter()
+                    .chain(texts)
+                    .join("\n"),
+            )),
+        }
+    }
+
+    /// The `content` of the block without the text of its heading, e.g. to write it under a heading of its own.
+    ///
+    /// The text of the heading is the leading `content_ranges` within the `header_range`, each on a line of `content`.
+    pub fn get_body(&self) -> Option<&str> {
+        let content = self.content.as_deref()?;
+        let heading_lines = self
+            .content_ranges
+            .iter()
+            .take_while(|range| {
+                range.start >= self.header_range.start.offset
+                    && range.end <= self.header_range.end.offset
+            })
+            .count();
+        let body = content
+            .splitn(heading_lines + 1, '\n')
+            .nth(heading_lines)?
+            .trim();
+        (!body.is_empty()).then_some(body)
     }
 
-    pub fn hash(&mut self) -> () {
+    pub fn hash(&mut self) {
         self.hash = Some(compute_hash(&self.title));
     }
 }
@@ -0,0 +1,71 @@
+use std::collections::HashMap;
+
+use crate::entities::core::block::Blocks;
+
+/// The section of a block, as byte offsets.
+#[derive(Clone, Debug, PartialEq)]
+pub struct IndexEntry {
+    pub block_id: usize,
+    /// Offset of the start of the header.
+    pub start: usize,
+    /// Offset of the end of the `block_range`, inclusive.
+    pub end: usize,
+}
+
+/// # Block Index
+///
+/// The sections of the blocks sorted by start offset. Sections are nested intervals: a section either contains
+/// another one or is disjoint from it. So the innermost section at an offset is either the last section starting
+/// at or before it, or one of its ancestors.
+#[derive(Clone, Default, Debug)]
+pub struct BlockIndex {
+    entries: Vec<IndexEntry>,
+    /// Index of each block in `entries`.
+    ranks: HashMap<usize, usize>,
+}
+
+impl BlockIndex {
+    pub fn new(blocks: &Blocks) -> Self {
+        let mut entries: Vec<IndexEntry> = blocks
+            .values()
+            // blocks included from another file have offsets in that file
+            .filter(|block| block.source_path.is_none())
+            .map(|block| IndexEntry {
+                block_id: block.id,
+                start: block.header_range.start.offset,
+                end: block
+                    .block_range
+                    .as_ref()
+                    .map_or(block.header_range.end.offset, |range| range.end.offset),
+            })
+            .collect();
+        entries.sort_by_key(|entry| (entry.start, entry.block_id));
+
+        let ranks = entries
+            .iter()
+            .enumerate()
+            .map(|(rank, entry)| (entry.block_id, rank))
+            .collect();
+        Self { entries, ranks }
+    }
+
+    /// The last section starting at or before an offset.
+    pub fn get_last_started(&self, offset: usize) -> Option<&IndexEntry> {
+        let rank = self.entries.partition_point(|entry| entry.start <= offset);
+        rank.checked_sub(1).map(|rank| &self.entries[rank])
+    }
+
+    pub fn get(&self, block_id: usize) -> Option<&IndexEntry> {
+        self.ranks.get(&block_id).map(|rank| &self.entries[*rank])
+    }
+
+    /// The section after a block, in document order.
+    pub fn get_next(&self, block_id: usize) -> Option<&IndexEntry> {
+        self.entries.get(self.ranks.get(&block_id)? + 1)
+    }
+
+    /// The section before a block, in document order.
+    pub fn get_previous(&self, block_id: usize) -> Option<&IndexEntry> {
+        self.entries.get(self.ranks.get(&block_id)?.checked_sub(1)?)
+    }
+}
@@ -0,0 +1,116 @@
+use crate::entities::core::block::Blocks;
+
+/// Links of a block in a `BlockTree`.
+#[derive(Clone, Copy, Default, Debug, PartialEq)]
+pub struct TreeNode {
+    pub parent: Option<usize>,
+    pub first_child: Option<usize>,
+    pub last_child: Option<usize>,
+    pub next_sibling: Option<usize>,
+    /// The next sibling of the block, or else of its closest ancestor that has one, i.e. the first block after its
+    /// subtree.
+    pub next_sibling_or_uncle: Option<usize>,
+}
+
+/// # Block Tree
+///
+/// An arena of the links between blocks, indexed by block id, so that tree queries do not scan the blocks.
+///
+/// It is built in a single pass in document order, from the `parent_id` of the blocks, which must have continuous
+/// ids from 1 as checked by `Freeout::validate_blocks`.
+#[derive(Clone, Default, Debug)]
+pub struct BlockTree {
+    /// Nodes by block id. The node at index 0 is unused, as ids are 1-indexed.
+    nodes: Vec<TreeNode>,
+}
+
+impl BlockTree {
+    pub fn new(blocks: &Blocks) -> Self {
+        let mut nodes = vec![TreeNode::default(); blocks.len() + 1];
+
+        // a parent comes before its children, so children are linked in document order
+        for block_id in 1..=blocks.len() {
+            let parent = blocks[&block_id].parent_id;
+            nodes[block_id].parent = parent;
+            if let Some(parent) = parent {
+                match nodes[parent].last_child {
+                    Some(last_child) => nodes[last_child].next_sibling = Some(block_id),
+                    None => nodes[parent].first_child = Some(block_id),
+                }
+                nodes[parent].last_child = Some(block_id);
+            }
+        }
+
+        // previous root blocks are linked as siblings as well
+        let mut last_root: Option<usize> = None;
+        for block_id in 1..=blocks.len() {
+            if nodes[block_id].parent.is_none() {
+                if let Some(last_root) = last_root {
+                    nodes[last_root].next_sibling = Some(block_id);
+                }
+                last_root = Some(block_id);
+            }
+        }
+
+        // the parent is settled before its children
+        for block_id in 1..=blocks.len() {
+            let node = nodes[block_id];
+            nodes[block_id].next_sibling_or_uncle = node.next_sibling.or_else(|| {
+                node.parent
+                    .and_then(|parent| nodes[parent].next_sibling_or_uncle)
+            });
+        }
+
+        Self { nodes }
+    }
+
+    pub fn get(&self, block_id: usize) -> Option<&TreeNode> {
+        // the unused node is not a block
+        self.nodes.get(block_id).filter(|_| block_id > 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.len().saturating_sub(1)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The children of a block, in document order.
+    pub fn children(&self, block_id: usize) -> impl Iterator<Item = usize> + '_ {
+        std::iter::successors(
+            self.get(block_id).and_then(|node| node.first_child),
+            |child| self.nodes[*child].next_sibling,
+        )
+    }
+
+    /// The last block of the subtree of a block, which is the block itself for a leaf.
+    pub fn get_last_descendant(&self, block_id: usize) -> Option<usize> {
+        let node = self.get(block_id)?;
+        Some(node.next_sibling_or_uncle.unwrap_or(self.nodes.len()) - 1)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::core::freeout::Freeout;
+    use crate::readers::markdown::MarkdownReader;
+
+    #[test]
+    fn should_link_blocks_in_document_order() {
+        let markdown = "## Intro\n\n# Part\n\n### Scene\n\n## Chapter\n\n### Note\n\n# Appendix\n";
+        let mut freeout = Freeout::new(markdown.to_string(), None);
+        freeout.outline(&MarkdownReader::default()).unwrap();
+
+        let tree = &freeout.tree;
+        assert_eq!(tree.len(), 6);
+        assert_eq!(tree.children(2).collect::<Vec<usize>>(), vec![3, 4]);
+        assert_eq!(tree.get(1).unwrap().next_sibling, Some(2));
+        assert_eq!(tree.get(5).unwrap().next_sibling_or_uncle, Some(6));
+        assert_eq!(tree.get(6).unwrap().next_sibling_or_uncle, None);
+        assert_eq!(tree.get_last_descendant(2), Some(5));
+        assert_eq!(tree.get_last_descendant(6), Some(6));
+        assert!(tree.get(0).is_none());
+    }
+}
@@ -0,0 +1,66 @@
+use regex::Regex;
+
+/// # Exclusion Rules
+///
+/// Rules deciding which blocks are marked as `exclude`, e.g. "Notes", "References" or draft appendices.
+///
+/// An excluded block and its whole subtree stay in the outline with `exclude` set, but they do not count toward the `aggregate_stats` of their parents nor receive a share of their parents' targets.
+///
+/// Blocks can be excluded by:
+/// - A title matching any of `title_patterns`. This works for all readers.
+/// - A title listed under `front_matter_key` in the front matter of the document.
+/// - A heading attribute carrying `attribute_class`, e.g. `# Notes {.exclude}`.
+/// - A `comment_marker` comment placed under the heading, e.g. `<!-- freeout:exclude -->`.
+///
+/// The last three are detected by readers that support them.
+#[derive(Clone)]
+pub struct ExclusionRules {
+    /// Blocks whose titles match any of these patterns are excluded.
+    pub title_patterns: Vec<Regex>,
+    /// Front matter key holding a list of titles to exclude.
+    pub front_matter_key: String,
+    /// Class in a heading attribute block, e.g. `{.exclude}`, that excludes the block.
+    pub attribute_class: String,
+    /// Comment content that excludes the block it is placed under.
+    pub comment_marker: String,
+}
+
+impl Default for ExclusionRules {
+    fn default() -> Self {
+        Self {
+            title_patterns: vec![],
+            front_matter_key: "exclude".to_string(),
+            attribute_class: "exclude".to_string(),
+            comment_marker: "freeout:exclude".to_string(),
+        }
+    }
+}
+
+impl ExclusionRules {
+    /// Add a title pattern.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pattern is not a valid regular expression.
+    pub fn with_title_pattern(mut self, pattern: &str) -> Result<Self, regex::Error> {
+        self.title_patterns.push(Regex::new(pattern)?);
+        Ok(self)
+    }
+
+    /// Whether the title matches any of the title patterns.
+    pub fn matches_title(&self, title: &str) -> bool {
+        self.title_patterns
+            .iter()
+            .any(|pattern| pattern.is_match(title))
+    }
+
+    /// Whether a comment, with or without the `<!--` and `-->` delimiters, is the exclusion marker.
+    pub fn is_comment_marker(&self, comment: &str) -> bool {
+        let comment = comment.trim();
+        let comment = comment
+            .strip_prefix("<!--")
+            .and_then(|c| c.strip_suffix("-->"))
+            .unwrap_or(comment);
+        comment.trim() == self.comment_marker
+    }
+}
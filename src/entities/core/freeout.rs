@@ -1,21 +1,39 @@
 use crate::entities::core::block::{compute_hash, Block};
+use crate::entities::core::block_index::BlockIndex;
 use crate::entities::core::block_range::BlockRange;
-use crate::entities::core::outline::Outline;
-use crate::entities::core::words_statistics::WordCount;
+use crate::entities::core::block_tree::BlockTree;
+use crate::entities::core::exclusion_rules::ExclusionRules;
+use crate::entities::core::include_rules::IncludeRules;
+use crate::entities::core::outline::{Outline, OutlineRef};
+use crate::entities::core::position::Position;
+use crate::entities::core::position_encoding::PositionEncoding;
 use crate::entities::reader::reader_trait::ReaderTrait;
+use crate::entities::visitor::visitor_trait::{VisitContext, VisitFlow, VisitorMut};
+use crate::utils::visit_utils::VisitUtils;
 use eyre::Report;
+use std::borrow::Cow;
 use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use tracing::debug;
 
 #[derive(Clone)]
 pub struct FreeoutOptions {
     pub include_content: bool,
+    /// Rules to mark blocks as excluded from statistics.
+    pub exclusion: ExclusionRules,
+    /// Directives including other files, resolved when the `Freeout` has a path.
+    pub include: IncludeRules,
+    /// Unit of the columns of the positions in blocks.
+    pub position_encoding: PositionEncoding,
 }
 
 impl Default for FreeoutOptions {
     fn default() -> Self {
         Self {
             include_content: true,
+            exclusion: ExclusionRules::default(),
+            include: IncludeRules::default(),
+            position_encoding: PositionEncoding::default(),
         }
     }
 }
@@ -25,6 +43,16 @@ pub struct Freeout {
     pub blocks: HashMap<usize, Block>, // Using HashMap for quick block lookups by ID.
     pub line_starts: Vec<usize>,
     pub(crate) opt: FreeoutOptions,
+    /// Links between the blocks, built once they are validated, for the tree queries of the processes and editors.
+    pub(crate) tree: BlockTree,
+    /// Sections of the blocks by position, built at the end of `outline`.
+    pub(crate) index: BlockIndex,
+    /// The file of the source, against which includes are resolved.
+    pub(crate) path: Option<PathBuf>,
+    /// The files including this one, outermost first, to detect include cycles.
+    pub(crate) include_chain: Vec<PathBuf>,
+    /// Sources of the included files, by the `source_path` of their blocks, to resolve their content.
+    pub(crate) included_texts: HashMap<PathBuf, String>,
 }
 impl Freeout {
     pub fn new(source: String, opt: Option<FreeoutOptions>) -> Freeout {
@@ -38,9 +66,24 @@ impl Freeout {
             text: source,
             blocks: HashMap::new(),
             line_starts,
+            tree: BlockTree::default(),
+            index: BlockIndex::default(),
+            path: None,
+            include_chain: vec![],
+            included_texts: HashMap::new(),
         }
     }
 
+    /// Set the file of the source, so that its include directives are resolved. See `IncludeRules`.
+    pub fn with_path(mut self, path: PathBuf) -> Freeout {
+        self.path = Some(path);
+        self
+    }
+
+    pub fn get_path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
     pub fn get_line(&self, line: usize) -> Option<&str> {
         if line >= self.line_starts.len() {
             return None; // Line number out of range
@@ -60,17 +103,72 @@ impl Freeout {
     pub fn line_count(&self) -> usize {
         self.line_starts.len()
     }
+
+    /// Get the 0-indexed position of a byte offset, with the column in bytes.
+    pub fn get_position(&self, offset: usize) -> Position {
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+        Position {
+            line,
+            column: offset - self.line_starts[line],
+            offset,
+        }
+    }
+
+    /// Get the 0-indexed position of a byte offset, with the column in the `position_encoding` of the options.
+    pub fn get_encoded_position(&self, offset: usize) -> Position {
+        let position = self.get_position(offset);
+        let line_start = offset - position.column;
+        Position {
+            column: self
+                .opt
+                .position_encoding
+                .get_column(&self.text[line_start..offset]),
+            ..position
+        }
+    }
+
+    /// Get the byte offset of a 0-indexed line and a column in the `position_encoding` of the options. Positions
+    /// beyond the end of a line or of the text are clamped.
+    pub fn get_offset(&self, line: usize, column: usize) -> usize {
+        match self.get_line(line) {
+            Some(line_text) => {
+                self.line_starts[line]
+                    + self.opt.position_encoding.get_byte_index(line_text, column)
+            }
+            None => self.text.len(),
+        }
+    }
+
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
 }
 
-fn compute_current_outline(document: &str) -> Vec<Block> {
+fn compute_current_outline(_document: &str) -> Vec<Block> {
     // Implementation of the actual outline computation.
     // This can be the existing logic to generate blocks from a document.
     Vec::new() // Placeholder
 }
 impl Freeout {
-    pub fn outline<R>(&mut self, reader: &R) -> Result<Outline, Report>
+    /// # Outline the source
+    ///
+    /// Processes the blocks of the source, and builds an outline borrowing them. See `to_outline` for a copy, e.g.
+    /// for a writer.
+    pub fn outline<R>(&mut self, reader: &R) -> Result<OutlineRef<'_>, Report>
     where
-        R: ReaderTrait,
+        R: ReaderTrait + ?Sized,
+    {
+        self.process(reader)?;
+        Ok(self.get_outline())
+    }
+
+    /// # Process the blocks of the source
+    ///
+    /// Runs the whole pipeline of `outline`, without building the outline. It can then be borrowed with
+    /// `get_outline`.
+    pub fn process<R>(&mut self, reader: &R) -> Result<(), Report>
+    where
+        R: ReaderTrait + ?Sized,
     {
         debug!("Running reader: {}", reader.name());
         self.blocks = reader.read(&self.text, &self.opt)?;
@@ -78,12 +176,43 @@ impl Freeout {
         // validate blocks
         debug!("Validating blocks");
         self.validate_blocks()?;
+        self.tree = BlockTree::new(&self.blocks);
+
+        debug!("Processing logical depths");
+        self.process_logical_depths();
 
         // process content
         debug!("Processing content");
         self.process_content();
 
-        Outline::build_outline(&self.blocks)
+        // mark excluded subtrees before any statistics are aggregated
+        debug!("Applying exclusion rules");
+        self.apply_exclusion_rules();
+
+        debug!("Processing positions");
+        self.process_positions();
+
+        // included blocks come with their own positions, and count in the statistics
+        debug!("Processing includes");
+        self.process_includes(reader)?;
+
+        debug!("Processing statistics");
+        self.process_stats();
+
+        self.index = BlockIndex::new(&self.blocks);
+        Ok(())
+    }
+
+    /// Get the outline of the processed blocks, borrowing them.
+    pub fn get_outline(&self) -> OutlineRef<'_> {
+        OutlineRef::build_outline(&self.blocks)
+    }
+
+    /// Copy the processed blocks into an `Outline`, with the `content` of each block resolved from the source.
+    pub fn to_outline(&self) -> Outline {
+        let mut outline = self.get_outline().to_outline();
+        VisitUtils::walk_outline_mut(&mut outline, &mut ContentResolver { freeout: self });
+        outline
     }
 
     pub fn get_block_content(&self, block_id: &usize) -> Option<&str> {
@@ -98,6 +227,38 @@ impl Freeout {
         Some(&self.text[range.start.offset..range.end.offset])
     }
 
+    /// Get the plain content of a block, see `Block::get_content`.
+    pub fn get_content(&self, block_id: usize) -> Option<Cow<'_, str>> {
+        let block = self.blocks.get(&block_id)?;
+        block.get_content(self.get_block_source(block).unwrap_or_default())
+    }
+
+    /// Get the own content of a block from its source: the text between its header and its first child, or else the
+    /// end of its section. Unlike `get_content`, it is the raw source, markup included.
+    pub fn get_own_content(&self, block_id: usize) -> Option<&str> {
+        let block = self.blocks.get(&block_id)?;
+        let start = block.header_range.end.offset;
+        // children included from another file have offsets in that file
+        let first_child = block
+            .children_ids
+            .iter()
+            .filter_map(|child_id| self.blocks.get(child_id))
+            .find(|child| child.source_path == block.source_path);
+        let end = match first_child {
+            Some(child) => child.header_range.start.offset,
+            None => block.block_range.as_ref()?.end.offset,
+        };
+        self.get_block_source(block)?.get(start..end.max(start))
+    }
+
+    /// The source of a block, in which its offsets are: the source of its file for blocks included from another one.
+    fn get_block_source(&self, block: &Block) -> Option<&str> {
+        match &block.source_path {
+            Some(path) => self.included_texts.get(path).map(String::as_str),
+            None => Some(&self.text),
+        }
+    }
+
     pub fn compute_block_hash(&mut self, block_id: usize) -> Option<u64> {
         // Fetch the content first
         let content = self.get_block_content(&block_id);
@@ -115,10 +276,27 @@ impl Freeout {
         None
     }
     pub fn generate_incremental_outline(&self, previous_outline: Option<Vec<Block>>) -> Vec<Block> {
-        if let Some(prev) = previous_outline {
+        if let Some(_prev) = previous_outline {
             todo!()
         } else {
             compute_current_outline(&self.text)
         }
     }
 }
+
+/// Fills in the `content` of the blocks of a copied outline from the source of a `Freeout`.
+struct ContentResolver<'a> {
+    freeout: &'a Freeout,
+}
+
+impl VisitorMut for ContentResolver<'_> {
+    fn enter_block(&mut self, block: &mut Block, _context: &VisitContext) -> VisitFlow {
+        if block.content.is_none() {
+            block.content = self
+                .freeout
+                .get_content(block.id)
+                .map(|content| content.into_owned());
+        }
+        VisitFlow::Continue
+    }
+}
@@ -0,0 +1,116 @@
+use std::path::Path;
+
+use regex::Regex;
+
+/// mdBook include, e.g. `{{#include chapter.md}}`. Line ranges and anchors, e.g. `{{#include chapter.md:10:20}}`, are
+/// ignored: the whole file is included.
+pub const MD_BOOK_INCLUDE_PATTERN: &str = r"\{\{#include\s+(?P<path>[^}:\s]+)[^}]*\}\}";
+/// Obsidian embed, e.g. `![[note]]`. Headings and aliases, e.g. `![[note#Heading|alias]]`, are ignored: the whole
+/// note is included.
+pub const OBSIDIAN_EMBED_PATTERN: &str = r"!\[\[(?P<path>[^\]|#]+)[^\]]*\]\]";
+
+/// An include directive found in a source.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Include {
+    /// Byte offset of the directive.
+    pub offset: usize,
+    /// Path of the included file, relative to the including file.
+    pub path: String,
+}
+
+/// # Include Rules
+///
+/// Directives that include another file in a document, e.g. a snippet reused across a handbook.
+///
+/// The headings of an included file are spliced into the outline of the including file, at the place of the
+/// directive. Only files with a reader are included, so that an include of a code listing stays content.
+#[derive(Clone)]
+pub struct IncludeRules {
+    /// Patterns of the directives, with the path of the included file in a `path` group.
+    pub patterns: Vec<Regex>,
+    /// Extension given to paths without one, e.g. `note` in `![[note]]`.
+    pub default_extension: String,
+}
+
+impl Default for IncludeRules {
+    fn default() -> Self {
+        Self {
+            patterns: vec![
+                Regex::new(MD_BOOK_INCLUDE_PATTERN).unwrap(),
+                Regex::new(OBSIDIAN_EMBED_PATTERN).unwrap(),
+            ],
+            default_extension: "md".to_string(),
+        }
+    }
+}
+
+impl IncludeRules {
+    /// Add a pattern.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pattern is not a valid regular expression, or if it has no `path` group.
+    pub fn with_pattern(mut self, pattern: &str) -> Result<Self, regex::Error> {
+        let regex = Regex::new(pattern)?;
+        if !regex.capture_names().any(|name| name == Some("path")) {
+            return Err(regex::Error::Syntax(format!(
+                "Include pattern `{}` has no `path` group",
+                pattern
+            )));
+        }
+        self.patterns.push(regex);
+        Ok(self)
+    }
+
+    /// Find the include directives of a source, in order.
+    pub fn find_includes(&self, text: &str) -> Vec<Include> {
+        let mut includes: Vec<Include> = self
+            .patterns
+            .iter()
+            .flat_map(|pattern| pattern.captures_iter(text))
+            .filter_map(|captures| {
+                let path = captures.name("path")?.as_str().trim();
+                let path = match has_extension(path) {
+                    true => path.to_string(),
+                    false => format!("{}.{}", path, self.default_extension),
+                };
+                Some(Include {
+                    offset: captures.get(0)?.start(),
+                    path,
+                })
+            })
+            .collect();
+        includes.sort_by_key(|include| include.offset);
+        includes
+    }
+}
+
+/// Whether the file name of a path has an extension. A dot in a folder, e.g. `../shared/note`, or followed by a space,
+/// e.g. `Dr. Who`, does not start one.
+fn has_extension(path: &str) -> bool {
+    Path::new(path).extension().is_some_and(|extension| {
+        let extension = extension.to_string_lossy();
+        !extension.is_empty() && !extension.contains(char::is_whitespace)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::core::include_rules::IncludeRules;
+
+    #[test]
+    fn should_add_default_extension_to_paths_without_one() {
+        let includes = IncludeRules::default().find_includes(
+            "![[../shared/note]]\n\n![[Dr. Who]]\n\n![[setup.md#Install]]\n\n{{#include code.rs:1:5}}\n",
+        );
+        let paths: Vec<&str> = includes
+            .iter()
+            .map(|include| include.path.as_str())
+            .collect();
+
+        assert_eq!(
+            paths,
+            vec!["../shared/note.md", "Dr. Who.md", "setup.md", "code.rs"]
+        );
+    }
+}
@@ -1,6 +1,12 @@
 pub mod freeout;
 pub mod outline;
 pub mod block;
+pub mod block_index;
+pub mod block_tree;
 pub mod position;
+pub mod position_encoding;
 pub mod words_statistics;
-pub(crate) mod block_range;
+pub mod exclusion_rules;
+pub mod include_rules;
+pub mod block_range;
+pub mod text_edit;
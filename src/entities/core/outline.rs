@@ -13,10 +13,8 @@ pub struct Outline {
 
 impl Outline {
     pub fn build_outline(blocks: &HashMap<usize, Block>) -> Result<Outline, Report> {
-        let mut root_outline_items: Vec<OutlineItem> = Vec::new();
-
         // collect root blocks whose parents are None
-        root_outline_items = blocks
+        let root_outline_items: Vec<OutlineItem> = blocks
             .values()
             .filter(|block| block.parent_id.is_none())
             .map(|block| OutlineItem::from_block(block, blocks))
@@ -26,6 +24,20 @@ impl Outline {
             items: root_outline_items,
         })
     }
+
+    /// # Get all blocks of the outline in document order
+    pub fn get_blocks(&self) -> Vec<&Block> {
+        fn collect<'a>(items: &'a [OutlineItem], blocks: &mut Vec<&'a Block>) {
+            for item in items {
+                blocks.push(&item.block);
+                collect(&item.subitems, blocks);
+            }
+        }
+
+        let mut blocks = vec![];
+        collect(&self.items, &mut blocks);
+        blocks
+    }
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -54,3 +66,130 @@ impl OutlineItem {
         }
     }
 }
+
+/// # Outline Ref
+///
+/// An outline borrowing the blocks of a `Freeout` instead of copying them, e.g. for large documents. It serializes
+/// like an `Outline`.
+#[derive(Serialize, Clone)]
+pub struct OutlineRef<'a> {
+    pub items: Vec<OutlineItemRef<'a>>,
+}
+
+impl<'a> OutlineRef<'a> {
+    pub fn build_outline(blocks: &'a Blocks) -> OutlineRef<'a> {
+        // block ids follow the document order, and so do the children ids of each block
+        let items = (1..=blocks.len())
+            .filter_map(|block_id| blocks.get(&block_id))
+            .filter(|block| block.parent_id.is_none())
+            .map(|block| OutlineItemRef::from_block(block, blocks))
+            .collect();
+        OutlineRef { items }
+    }
+
+    /// # Get all blocks of the outline in document order
+    pub fn get_blocks(&self) -> Vec<&'a Block> {
+        fn collect<'a>(items: &[OutlineItemRef<'a>], blocks: &mut Vec<&'a Block>) {
+            for item in items {
+                blocks.push(item.block);
+                collect(&item.subitems, blocks);
+            }
+        }
+
+        let mut blocks = vec![];
+        collect(&self.items, &mut blocks);
+        blocks
+    }
+
+    /// Copy the borrowed blocks into an `Outline`, e.g. for a writer.
+    pub fn to_outline(&self) -> Outline {
+        fn to_item(item: &OutlineItemRef) -> OutlineItem {
+            OutlineItem {
+                block: item.block.clone(),
+                subitems: item.subitems.iter().map(to_item).collect(),
+            }
+        }
+
+        Outline {
+            items: self.items.iter().map(to_item).collect(),
+        }
+    }
+}
+
+#[derive(Serialize, Clone)]
+pub struct OutlineItemRef<'a> {
+    pub block: &'a Block,
+    pub subitems: Vec<OutlineItemRef<'a>>,
+}
+
+impl<'a> OutlineItemRef<'a> {
+    fn from_block(block: &'a Block, blocks: &'a Blocks) -> OutlineItemRef<'a> {
+        OutlineItemRef {
+            block,
+            subitems: block
+                .children_ids
+                .iter()
+                .filter_map(|child_id| blocks.get(child_id))
+                .map(|child| OutlineItemRef::from_block(child, blocks))
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::core::freeout::Freeout;
+    use crate::entities::lint::diagnostic::LintRule;
+    use crate::entities::lint::lint_config::LintConfig;
+    use crate::readers::markdown::MarkdownReader;
+    use crate::utils::test_utils::test_utils::md_get_simple_eng;
+
+    #[test]
+    fn should_borrow_the_same_outline() {
+        let text = std::fs::read_to_string(md_get_simple_eng()).unwrap();
+        let mut freeout = Freeout::new(text, None);
+        freeout.process(&MarkdownReader::default()).unwrap();
+
+        let outline_ref = freeout.get_outline();
+        let outline = freeout.to_outline();
+        assert_eq!(outline_ref.get_blocks().len(), outline.get_blocks().len());
+        for (block, copy) in outline_ref
+            .get_blocks()
+            .into_iter()
+            .zip(outline.get_blocks())
+        {
+            // blocks hold no copy of the source, the owned outline resolves their content
+            assert_eq!(block.id, copy.id);
+            assert!(block.content.is_none());
+            assert_eq!(
+                copy.content.as_deref(),
+                freeout.get_content(block.id).as_deref()
+            );
+            assert_eq!(block.hash, copy.hash);
+            assert_eq!(
+                block.aggregate_stats.count.words,
+                copy.aggregate_stats.count.words
+            );
+        }
+
+        let mut freeout = Freeout::new(
+            "# Book\n\nOne two\n\n## Chapter\n\nThree\n\n## Empty\n".to_string(),
+            None,
+        );
+        freeout.process(&MarkdownReader::default()).unwrap();
+        assert_eq!(freeout.get_own_content(1), Some("\n\nOne two\n\n"));
+        assert_eq!(freeout.get_own_content(2), Some("\n\nThree\n"));
+        assert_eq!(freeout.get_content(1).as_deref(), Some("Book\nOne two"));
+        // the lint resolves the content from the source
+        let report = freeout.lint(&LintConfig::default());
+        assert_eq!(
+            report
+                .diagnostics
+                .iter()
+                .filter(|diagnostic| diagnostic.rule == LintRule::EmptySection)
+                .map(|diagnostic| diagnostic.block_id)
+                .collect::<Vec<usize>>(),
+            vec![3]
+        );
+    }
+}
@@ -0,0 +1,86 @@
+use serde::{Deserialize, Serialize};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// # Position Encoding
+///
+/// The unit of the `column` of a `Position`. Lines and offsets do not depend on it: lines are 0-indexed, and offsets
+/// are always in bytes, so that they can slice the source text.
+#[derive(Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Debug)]
+#[serde(rename_all = "kebab-case")]
+pub enum PositionEncoding {
+    /// Bytes of UTF-8, as Rust strings are indexed.
+    #[default]
+    Utf8,
+    /// UTF-16 code units, as in editors such as VS Code and in the Language Server Protocol.
+    Utf16,
+    /// Extended grapheme clusters, i.e. the characters a reader sees, e.g. an emoji with a skin tone is one column.
+    Grapheme,
+}
+
+impl PositionEncoding {
+    /// Get the column at the end of the beginning of a line.
+    pub fn get_column(&self, line_prefix: &str) -> usize {
+        match self {
+            PositionEncoding::Utf8 => line_prefix.len(),
+            PositionEncoding::Utf16 => line_prefix.encode_utf16().count(),
+            PositionEncoding::Grapheme => line_prefix.graphemes(true).count(),
+        }
+    }
+
+    /// Get the byte index of a column in a line. Columns beyond the end of the line, or inside a character, are
+    /// clamped to the end of that character or line.
+    pub fn get_byte_index(&self, line: &str, column: usize) -> usize {
+        match self {
+            PositionEncoding::Utf8 => {
+                let mut index = column.min(line.len());
+                while !line.is_char_boundary(index) {
+                    index += 1;
+                }
+                index
+            }
+            PositionEncoding::Utf16 => {
+                let mut units = 0;
+                for (index, character) in line.char_indices() {
+                    if units >= column {
+                        return index;
+                    }
+                    units += character.len_utf16();
+                }
+                line.len()
+            }
+            PositionEncoding::Grapheme => line
+                .grapheme_indices(true)
+                .nth(column)
+                .map_or(line.len(), |(index, _)| index),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::core::position_encoding::PositionEncoding;
+
+    #[test]
+    fn should_count_columns_in_each_encoding() {
+        // "标" is 3 bytes, "😀" is 2 UTF-16 units, and "👍🏽" is 2 characters in 1 grapheme
+        let line = "标😀👍🏽a";
+        let columns = |encoding: PositionEncoding| {
+            (
+                encoding.get_column(line),
+                encoding.get_byte_index(line, encoding.get_column("标😀")),
+            )
+        };
+
+        assert_eq!(columns(PositionEncoding::Utf8), (16, 7));
+        assert_eq!(columns(PositionEncoding::Utf16), (8, 7));
+        assert_eq!(columns(PositionEncoding::Grapheme), (4, 7));
+
+        // clamped to the end of the line
+        assert_eq!(
+            PositionEncoding::Grapheme.get_byte_index(line, 9),
+            line.len()
+        );
+        // or to the end of a character
+        assert_eq!(PositionEncoding::Utf8.get_byte_index(line, 1), 3);
+    }
+}
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entities::core::block_range::BlockRange;
+
+/// # Text Edit
+///
+/// A replacement of a range of the original source, e.g. to be sent to an editor. An empty range is an insertion,
+/// and an empty `new_text` is a deletion.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct TextEdit {
+    /// Range of the original source, with 0-indexed lines and byte columns.
+    pub range: BlockRange,
+    pub new_text: String,
+}
+
+impl TextEdit {
+    /// Apply non-overlapping edits to the source they were computed from.
+    ///
+    /// Edits are applied from the end of the source, so that their ranges stay valid. At the same offset, a
+    /// replacement is applied before an insertion, so the inserted text is kept.
+    pub fn apply(source: &str, edits: &[TextEdit]) -> String {
+        let mut edits: Vec<&TextEdit> = edits.iter().collect();
+        edits.sort_by_key(|edit| (edit.range.start.offset, edit.range.end.offset));
+
+        let mut text = source.to_string();
+        for edit in edits.into_iter().rev() {
+            text.replace_range(
+                edit.range.start.offset..edit.range.end.offset,
+                &edit.new_text,
+            );
+        }
+        text
+    }
+}
+
+/// # Edit Result
+///
+/// The outcome of an editing operation: the new source, and the edits that turn the original source into it.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct EditResult {
+    pub text: String,
+    /// Edits in document order.
+    pub edits: Vec<TextEdit>,
+}
+
+impl EditResult {
+    /// Build the result of edits on a source.
+    pub fn new(source: &str, mut edits: Vec<TextEdit>) -> Self {
+        edits.sort_by_key(|edit| (edit.range.start.offset, edit.range.end.offset));
+        Self {
+            text: TextEdit::apply(source, &edits),
+            edits,
+        }
+    }
+}
@@ -1,20 +1,19 @@
+use std::ops::AddAssign;
+
 use serde::{Deserialize, Serialize};
-use words_count::{count, WordsCount};
+use words_count::count;
 
 /// # Word Stats
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct WordCount {
     pub words: usize,
     pub characters: usize,
 }
 
-// default
-impl Default for WordCount {
-    fn default() -> Self {
-        Self {
-            words: 0,
-            characters: 0,
-        }
+impl AddAssign<&WordCount> for WordCount {
+    fn add_assign(&mut self, other: &WordCount) {
+        self.words += other.words;
+        self.characters += other.characters;
     }
 }
 
@@ -28,22 +27,15 @@ impl From<words_count::WordsCount> for WordCount {
 }
 
 /// # Word Expectation
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct WordsTarget {
     /// The expected number of words.
     pub words: usize,
     /// Distribution method (uniform, custom, etc.)
     pub distribution: Option<DistributionMethod>,
-}
-
-// default
-impl Default for WordsTarget {
-    fn default() -> Self {
-        Self {
-            words: 0,
-            distribution: None,
-        }
-    }
+    /// Whether the target is a share of the target of the parent, rather than set on the block itself.
+    #[serde(default)]
+    pub distributed: bool,
 }
 
 #[derive(Serialize, Deserialize, Clone)]
@@ -52,11 +44,10 @@ pub enum DistributionMethod {
              // ... any other methods you might want to introduce
 }
 
-
 /// # Word Reality
 ///
 /// Computed entity to represents the overflow or
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct WordsStatus {
     /// The actual balance of words. A words goal of 100 and a words reality of 120 means 20.
     pub balance: isize,
@@ -66,32 +57,43 @@ pub struct WordsStatus {
     pub adjusted_target: Option<usize>,
 }
 
-// default
-impl Default for WordsStatus {
-    fn default() -> Self {
-        Self {
-            balance: 0,
-            adjusted_target: None,
-        }
-    }
-}
-
 // A representation of word statistics.
-#[derive(Serialize, Deserialize, Clone)]
+#[derive(Serialize, Deserialize, Clone, Default)]
 pub struct WordStatistics {
     pub target: Option<WordsTarget>,
     pub status: Option<WordsStatus>,
     pub count: WordCount,
 }
 
-// Default
-impl Default for WordStatistics {
-    fn default() -> Self {
-        Self {
-            target: None,
-            status: None,
-            count: WordCount::default(),
-        }
+/// Tolerance of a target, as a ratio of the target words, within which a block is considered on target.
+pub const TARGET_TOLERANCE: f64 = 0.1;
+
+/// # Target State
+///
+/// How the count of a block compares with its target, within `TARGET_TOLERANCE`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum TargetState {
+    Under,
+    OnTarget,
+    Over,
+}
+
+impl WordStatistics {
+    /// # Get the target state
+    ///
+    /// Returns `None` if there is no target or no status.
+    pub fn get_target_state(&self) -> Option<TargetState> {
+        let target = self.target.as_ref()?;
+        let status = self.status.as_ref()?;
+        let tolerance = (target.words as f64 * TARGET_TOLERANCE) as isize;
+
+        Some(if status.balance < -tolerance {
+            TargetState::Under
+        } else if status.balance > tolerance {
+            TargetState::Over
+        } else {
+            TargetState::OnTarget
+        })
     }
 }
 
@@ -116,7 +118,7 @@ impl WordStatistics {
        todo!()
     }
 
-    pub fn calculate(text: &str) -> Self {
+    pub fn calculate(_text: &str) -> Self {
         todo!()
     }
 }
@@ -0,0 +1,37 @@
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+pub enum LinkKind {
+    /// e.g. `[text](#anchor)`, `[text](chapter.md#scene)` or a reference link.
+    Markdown,
+    /// e.g. `[[Note]]`, `[[Note#Heading]]` or `[[#Heading|text]]`.
+    WikiLink,
+}
+
+/// # Link
+///
+/// A link found in the content of a source.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Link {
+    pub kind: LinkKind,
+    /// The text of the link, or the alias of a wikilink.
+    pub text: String,
+    /// The target as written, e.g. `chapter.md#scene` or `Note#Heading`.
+    pub destination: String,
+    /// Byte offsets of the link in the source.
+    pub offsets: Range<usize>,
+}
+
+impl Link {
+    /// Split the destination into a path, or a note name for a wikilink, and a fragment, e.g. `chapter.md` and
+    /// `scene`. The path is empty for a link within the source.
+    pub fn split_destination(&self) -> (&str, Option<&str>) {
+        match self.destination.split_once('#') {
+            Some((path, fragment)) => (path, Some(fragment)),
+            None => (self.destination.as_str(), None),
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::entities::core::block::Blocks;
+use crate::entities::core::block_range::BlockRange;
+use crate::entities::graph::link::Link;
+
+/// Where a link leads.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "status", rename_all = "kebab-case")]
+pub enum LinkTarget {
+    Block {
+        block_id: usize,
+    },
+    /// Outside of the graph, e.g. a web page, or an existing file that is not outlined.
+    External,
+    /// To a section, note or file that does not exist.
+    Broken,
+}
+
+/// A link of a `LinkGraph`, with the blocks it goes from and to.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct GraphLink {
+    /// The block whose own content holds the link. `None` for a link before the first heading.
+    pub source_block_id: Option<usize>,
+    /// The file holding the link, if known.
+    pub source_path: Option<PathBuf>,
+    /// The range of the link in its file, with the position encoding of the options.
+    pub range: BlockRange,
+    pub link: Link,
+    pub target: LinkTarget,
+}
+
+/// # Link Graph
+///
+/// The links between the blocks of an outline, e.g. of a knowledge base, with their outgoing and incoming links.
+#[derive(Serialize, Clone, Default)]
+pub struct LinkGraph {
+    /// All links, in document order.
+    pub links: Vec<GraphLink>,
+    /// Blocks standing for whole documents, e.g. the entries of a `Project`. They are never orphans.
+    #[serde(skip)]
+    pub(crate) document_block_ids: HashSet<usize>,
+    /// Indices in `links`, by source block.
+    #[serde(skip)]
+    outgoing: HashMap<usize, Vec<usize>>,
+    /// Indices in `links`, by target block.
+    #[serde(skip)]
+    incoming: HashMap<usize, Vec<usize>>,
+}
+
+impl LinkGraph {
+    pub fn new(links: Vec<GraphLink>, document_block_ids: HashSet<usize>) -> Self {
+        let mut outgoing: HashMap<usize, Vec<usize>> = HashMap::new();
+        let mut incoming: HashMap<usize, Vec<usize>> = HashMap::new();
+        for (index, link) in links.iter().enumerate() {
+            if let Some(source_block_id) = link.source_block_id {
+                outgoing.entry(source_block_id).or_default().push(index);
+            }
+            if let LinkTarget::Block { block_id } = link.target {
+                incoming.entry(block_id).or_default().push(index);
+            }
+        }
+
+        Self {
+            links,
+            document_block_ids,
+            outgoing,
+            incoming,
+        }
+    }
+
+    /// Links from the own content of a block.
+    pub fn get_outgoing(&self, block_id: usize) -> Vec<&GraphLink> {
+        self.get_links(self.outgoing.get(&block_id))
+    }
+
+    /// Links to a block.
+    pub fn get_incoming(&self, block_id: usize) -> Vec<&GraphLink> {
+        self.get_links(self.incoming.get(&block_id))
+    }
+
+    pub fn get_broken_links(&self) -> Vec<&GraphLink> {
+        self.links
+            .iter()
+            .filter(|link| link.target == LinkTarget::Broken)
+            .collect()
+    }
+
+    /// # Get the orphan sections
+    ///
+    /// A section is reachable if a link leads to it or to one of its ancestors, other than from itself. Orphans are
+    /// the outermost sections that are not reachable, e.g. the root heading of a note no other note links to, but not
+    /// its subsections. Blocks are expected to be in document order, with continuous ids.
+    pub fn get_orphans(&self, blocks: &Blocks) -> Vec<usize> {
+        let mut is_reachable = vec![false; blocks.len() + 1];
+        let mut orphans = vec![];
+
+        // parents come before their children
+        for block_id in 1..=blocks.len() {
+            let block = &blocks[&block_id];
+            let is_linked = self
+                .get_incoming(block_id)
+                .iter()
+                .any(|link| link.source_block_id != Some(block_id));
+            let parent_id = block.parent_id;
+            is_reachable[block_id] =
+                is_linked || parent_id.is_some_and(|parent_id| is_reachable[parent_id]);
+
+            let is_outermost =
+                parent_id.is_none_or(|parent_id| self.document_block_ids.contains(&parent_id));
+            if !is_reachable[block_id]
+                && is_outermost
+                && !self.document_block_ids.contains(&block_id)
+            {
+                orphans.push(block_id);
+            }
+        }
+        orphans
+    }
+
+    fn get_links(&self, indices: Option<&Vec<usize>>) -> Vec<&GraphLink> {
+        indices
+            .map(|indices| indices.iter().map(|index| &self.links[*index]).collect())
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,107 @@
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::entities::core::block_range::BlockRange;
+
+/// # Lint Rule
+///
+/// The rules of the outline linter. Each rule is serialized in kebab case, e.g. `skipped-heading-level`.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[serde(rename_all = "kebab-case")]
+pub enum LintRule {
+    /// A block is more than one level deeper than its parent, e.g. `###` right under `#`.
+    SkippedHeadingLevel,
+    /// A block has no content, no note and no children.
+    EmptySection,
+    /// Two siblings have the same title, ignoring case.
+    DuplicateSiblingTitle,
+    /// A title ends with a punctuation mark, e.g. `Methods:`.
+    TrailingPunctuation,
+    /// A title is longer than `LintConfig::max_title_length` characters.
+    LongTitle,
+    /// The outline has more than one root block.
+    MultipleTopLevel,
+    /// A block with a target is over or under it, beyond `TARGET_TOLERANCE`.
+    WordTarget,
+    /// A block has a single child, which could be merged into it.
+    SingleChild,
+}
+
+impl LintRule {
+    pub const ALL: [LintRule; 8] = [
+        LintRule::SkippedHeadingLevel,
+        LintRule::EmptySection,
+        LintRule::DuplicateSiblingTitle,
+        LintRule::TrailingPunctuation,
+        LintRule::LongTitle,
+        LintRule::MultipleTopLevel,
+        LintRule::WordTarget,
+        LintRule::SingleChild,
+    ];
+
+    /// Name of the rule, as serialized.
+    pub fn name(&self) -> &'static str {
+        match self {
+            LintRule::SkippedHeadingLevel => "skipped-heading-level",
+            LintRule::EmptySection => "empty-section",
+            LintRule::DuplicateSiblingTitle => "duplicate-sibling-title",
+            LintRule::TrailingPunctuation => "trailing-punctuation",
+            LintRule::LongTitle => "long-title",
+            LintRule::MultipleTopLevel => "multiple-top-level",
+            LintRule::WordTarget => "word-target",
+            LintRule::SingleChild => "single-child",
+        }
+    }
+}
+
+/// Severity of a rule. A rule that is `Off` is not checked.
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Off,
+    Info,
+    #[default]
+    Warning,
+    Error,
+}
+
+/// # Diagnostic
+///
+/// A problem found by a rule on a block.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct Diagnostic {
+    pub rule: LintRule,
+    pub severity: Severity,
+    pub block_id: usize,
+    /// The `header_range` of the block.
+    pub range: BlockRange,
+    /// The file of a block included from another file, see `Block::source_path`. The `range` is in that file.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source_path: Option<PathBuf>,
+    pub message: String,
+}
+
+/// # Lint Report
+///
+/// The diagnostics of an outline, in document order.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Default)]
+pub struct LintReport {
+    pub diagnostics: Vec<Diagnostic>,
+}
+
+impl LintReport {
+    /// Whether any diagnostic is an error, e.g. to fail a docs pipeline.
+    pub fn has_errors(&self) -> bool {
+        self.diagnostics
+            .iter()
+            .any(|diagnostic| diagnostic.severity == Severity::Error)
+    }
+
+    pub fn count(&self, severity: Severity) -> usize {
+        self.diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == severity)
+            .count()
+    }
+}
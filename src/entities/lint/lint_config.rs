@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+use crate::entities::lint::diagnostic::{LintRule, Severity};
+
+/// # Lint Config
+///
+/// The severity of each rule, and the settings of the rules. Missing fields take their default, so a config only
+/// needs the rules it changes, e.g. `{"single_child": "off", "max_title_length": 60}`.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct LintConfig {
+    pub skipped_heading_level: Severity,
+    pub empty_section: Severity,
+    pub duplicate_sibling_title: Severity,
+    pub trailing_punctuation: Severity,
+    pub long_title: Severity,
+    pub multiple_top_level: Severity,
+    pub word_target: Severity,
+    pub single_child: Severity,
+
+    /// Characters that a title should not end with.
+    pub trailing_punctuation_characters: String,
+    /// Maximum number of characters of a title.
+    pub max_title_length: usize,
+}
+
+impl Default for LintConfig {
+    fn default() -> Self {
+        Self {
+            skipped_heading_level: Severity::Warning,
+            empty_section: Severity::Warning,
+            duplicate_sibling_title: Severity::Warning,
+            trailing_punctuation: Severity::Warning,
+            long_title: Severity::Warning,
+            multiple_top_level: Severity::Info,
+            word_target: Severity::Info,
+            single_child: Severity::Info,
+            trailing_punctuation_characters: ".,;:!。，；：！".to_string(),
+            max_title_length: 80,
+        }
+    }
+}
+
+impl LintConfig {
+    pub fn get_severity(&self, rule: LintRule) -> Severity {
+        match rule {
+            LintRule::SkippedHeadingLevel => self.skipped_heading_level,
+            LintRule::EmptySection => self.empty_section,
+            LintRule::DuplicateSiblingTitle => self.duplicate_sibling_title,
+            LintRule::TrailingPunctuation => self.trailing_punctuation,
+            LintRule::LongTitle => self.long_title,
+            LintRule::MultipleTopLevel => self.multiple_top_level,
+            LintRule::WordTarget => self.word_target,
+            LintRule::SingleChild => self.single_child,
+        }
+    }
+}
@@ -1,3 +1,9 @@
 pub mod core;
+pub mod graph;
+pub mod lint;
+pub mod project;
+pub mod query;
+pub mod search;
 pub mod visitor;
 pub mod reader;
+pub mod writer;
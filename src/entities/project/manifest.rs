@@ -0,0 +1,36 @@
+use std::path::PathBuf;
+
+/// # Project Manifest
+///
+/// Where the files of a `Project` are listed, and in which order.
+#[derive(Clone, Debug)]
+pub enum ProjectManifest {
+    /// An mdBook `SUMMARY.md`. Paths are relative to its folder.
+    Summary(PathBuf),
+    /// Files in order, one top-level entry each.
+    Files(Vec<PathBuf>),
+    /// Every file with a reader under a folder, with an entry for each sub-folder.
+    Directory { root: PathBuf, order: WalkOrder },
+}
+
+/// Order of the files and folders of a `ProjectManifest::Directory`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum WalkOrder {
+    /// By name, e.g. `10-end.md` before `2-middle.md`.
+    Name,
+    /// By name, with numbers compared by value, e.g. `2-middle.md` before `10-end.md`.
+    #[default]
+    Natural,
+}
+
+/// # Manifest Entry
+///
+/// An entry of the table of contents of a project, e.g. `- [Chapter 1](chapter_1.md)` in a `SUMMARY.md`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ManifestEntry {
+    pub title: String,
+    /// The file whose blocks are grafted under the entry. `None` for part titles, draft chapters and folders without
+    /// an index file.
+    pub path: Option<PathBuf>,
+    pub children: Vec<ManifestEntry>,
+}
@@ -0,0 +1,223 @@
+use regex::Regex;
+
+/// A number of a block that a selector can compare, e.g. `words` in `[words < 200]`.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Attribute {
+    Id,
+    /// The depth of the marker, e.g. 3 for `###`.
+    Depth,
+    /// The depth in the tree, without skipped levels. Written `level`.
+    LogicalDepth,
+    /// Words of the whole section, i.e. `aggregate_stats`.
+    Words,
+    /// Words of the own content of the block, i.e. `self_stats`. Written `own_words`.
+    OwnWords,
+    /// Characters of the whole section.
+    Characters,
+    /// Number of children.
+    Children,
+}
+
+impl Attribute {
+    pub const ALL: [Attribute; 7] = [
+        Attribute::Id,
+        Attribute::Depth,
+        Attribute::LogicalDepth,
+        Attribute::Words,
+        Attribute::OwnWords,
+        Attribute::Characters,
+        Attribute::Children,
+    ];
+
+    /// The name of the attribute in the selector language.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Attribute::Id => "id",
+            Attribute::Depth => "depth",
+            Attribute::LogicalDepth => "level",
+            Attribute::Words => "words",
+            Attribute::OwnWords => "own_words",
+            Attribute::Characters => "characters",
+            Attribute::Children => "children",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Comparison {
+    Equal,
+    NotEqual,
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+}
+
+impl Comparison {
+    pub fn compare(&self, left: usize, right: usize) -> bool {
+        match self {
+            Comparison::Equal => left == right,
+            Comparison::NotEqual => left != right,
+            Comparison::Less => left < right,
+            Comparison::LessOrEqual => left <= right,
+            Comparison::Greater => left > right,
+            Comparison::GreaterOrEqual => left >= right,
+        }
+    }
+}
+
+/// How a selector matches titles.
+#[derive(Clone, Debug)]
+pub enum TitleMatcher {
+    /// e.g. `"Methods"`
+    Exact(String),
+    /// e.g. `/TODO/` or `/todo/i`
+    Pattern(Regex),
+}
+
+/// A condition on a block, besides its title.
+#[derive(Clone, Debug)]
+pub enum Condition {
+    /// e.g. `[words < 200]`
+    Compare {
+        attribute: Attribute,
+        comparison: Comparison,
+        value: usize,
+    },
+    /// `:excluded`
+    Excluded,
+    /// `:root`, a block without parent.
+    Root,
+    /// `:leaf`, a block without children.
+    Leaf,
+}
+
+/// Conditions on a single block, e.g. `"Methods"[depth = 2]` or `*`.
+#[derive(Clone, Debug, Default)]
+pub struct CompoundSelector {
+    /// `None` matches any title, e.g. `*`.
+    pub title: Option<TitleMatcher>,
+    pub conditions: Vec<Condition>,
+}
+
+/// How a step of a selector relates to the previous one.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Combinator {
+    /// A space, e.g. `"Methods" *`: any block under the previous one.
+    Descendant,
+    /// `>`, e.g. `"Methods" > *`: a child of the previous one.
+    Child,
+}
+
+/// A chain of compound selectors, e.g. `"Methods" > * [words < 200]`. The combinator of the first step is ignored.
+#[derive(Clone, Debug, Default)]
+pub struct ComplexSelector {
+    pub steps: Vec<(Combinator, CompoundSelector)>,
+}
+
+/// # Selector
+///
+/// A query over the block tree, in a small CSS-like language, e.g. `"Methods" > * [words < 200]` for the sections
+/// under "Methods" with less than 200 words, or `/TODO/i` for the sections whose title mentions a TODO.
+///
+/// - `"Title"` matches an exact title, `/pattern/` a title pattern, with the `i` flag to ignore case, and `*` any
+///   block.
+/// - `[attribute op value]` compares a number of the block, with an `Attribute` name, one of `=`, `!=`, `<`, `<=`,
+///   `>` and `>=`, and an integer.
+/// - `:root`, `:leaf` and `:excluded` match blocks without parent, without children, and excluded.
+/// - A space selects descendants, `>` children, and `,` separates alternatives.
+///
+/// Conditions apply to the title or `*` before them, spaces included, so `* [words < 200]` is a single step.
+///
+/// Selectors are parsed with `str::parse`, or built with `Selector::builder`.
+#[derive(Clone, Debug, Default)]
+pub struct Selector {
+    pub alternatives: Vec<ComplexSelector>,
+}
+
+impl Selector {
+    pub fn builder() -> SelectorBuilder {
+        SelectorBuilder::default()
+    }
+}
+
+/// # Selector Builder
+///
+/// Builds a `Selector` step by step, e.g. `"Methods" > * [words < 200]` is
+/// `Selector::builder().title("Methods").children().compare(Attribute::Words, Comparison::Less, 200).build()`.
+#[derive(Default)]
+pub struct SelectorBuilder {
+    alternatives: Vec<ComplexSelector>,
+    current: ComplexSelector,
+}
+
+impl SelectorBuilder {
+    /// Match an exact title in the current step.
+    pub fn title(mut self, title: &str) -> Self {
+        self.get_step().title = Some(TitleMatcher::Exact(title.to_string()));
+        self
+    }
+
+    /// Match a title pattern in the current step.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pattern is not a valid regular expression.
+    pub fn title_pattern(mut self, pattern: &str) -> Result<Self, regex::Error> {
+        self.get_step().title = Some(TitleMatcher::Pattern(Regex::new(pattern)?));
+        Ok(self)
+    }
+
+    pub fn compare(self, attribute: Attribute, comparison: Comparison, value: usize) -> Self {
+        self.condition(Condition::Compare {
+            attribute,
+            comparison,
+            value,
+        })
+    }
+
+    pub fn condition(mut self, condition: Condition) -> Self {
+        self.get_step().conditions.push(condition);
+        self
+    }
+
+    /// Start a step matching the children of the blocks of the current step.
+    pub fn children(self) -> Self {
+        self.step(Combinator::Child)
+    }
+
+    /// Start a step matching the descendants of the blocks of the current step.
+    pub fn descendants(self) -> Self {
+        self.step(Combinator::Descendant)
+    }
+
+    /// Start an alternative, like `,`.
+    pub fn or(mut self) -> Self {
+        self.alternatives.push(std::mem::take(&mut self.current));
+        self
+    }
+
+    pub fn build(mut self) -> Selector {
+        self.alternatives.push(self.current);
+        Selector {
+            alternatives: self.alternatives,
+        }
+    }
+
+    fn step(mut self, combinator: Combinator) -> Self {
+        self.current
+            .steps
+            .push((combinator, CompoundSelector::default()));
+        self
+    }
+
+    /// The current step, started if there is none yet.
+    fn get_step(&mut self) -> &mut CompoundSelector {
+        if self.current.steps.is_empty() {
+            self.current
+                .steps
+                .push((Combinator::Descendant, CompoundSelector::default()));
+        }
+        &mut self.current.steps.last_mut().unwrap().1
+    }
+}
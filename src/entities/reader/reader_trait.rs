@@ -1,7 +1,8 @@
-use crate::entities::core::block::{Block, Blocks};
+use std::ops::Range;
+
+use crate::entities::core::block::Blocks;
 use crate::entities::core::freeout::FreeoutOptions;
 use eyre::Report;
-use std::collections::HashMap;
 
 pub trait ReaderTrait {
     fn read(&self, source: &str, options: &FreeoutOptions) -> Result<Blocks, Report>;
@@ -12,4 +13,20 @@ pub trait ReaderTrait {
     fn name(&self) -> &'static str {
         "Unknown Reader"
     }
+
+    /// # Render the section marker at a depth
+    ///
+    /// Override this function to support structural editing, e.g. `##` at depth 2 for Markdown. Returns `None` if the
+    /// format has no marker at this depth.
+    fn render_marker(&self, _depth: usize) -> Option<String> {
+        None
+    }
+
+    /// # Read the code ranges of a source
+    ///
+    /// Override this function to return the byte ranges of code, e.g. code blocks and inline code in Markdown, where
+    /// include directives are quoted rather than followed.
+    fn read_code_ranges(&self, _source: &str) -> Result<Vec<Range<usize>>, Report> {
+        Ok(vec![])
+    }
 }
@@ -0,0 +1,53 @@
+use std::ops::Range;
+use std::path::PathBuf;
+
+use serde::Serialize;
+
+use crate::entities::core::block_range::BlockRange;
+
+/// A block found by a full-text search, best first.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct SearchHit {
+    pub block_id: usize,
+    /// The file of the block, in a `Project` or for an included block.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_path: Option<PathBuf>,
+    /// BM25 score of the block for the query.
+    pub score: f64,
+    /// An excerpt of the content around the first match, or the title if only the title matches. Line breaks are
+    /// replaced by spaces.
+    pub snippet: String,
+    /// Byte ranges of the matched words in `snippet`.
+    pub highlights: Vec<Range<usize>>,
+    /// Ranges of the matched words of the content in the source, with the position encoding of the options. Empty
+    /// if the content is not read from the source, e.g. for an included block.
+    pub ranges: Vec<BlockRange>,
+}
+
+impl SearchHit {
+    /// The snippet with the matched words wrapped in `before` and `after`, e.g. `**` and `**`.
+    pub fn highlight(&self, before: &str, after: &str) -> String {
+        let mut text = String::new();
+        let mut end = 0;
+        for highlight in &self.highlights {
+            text.push_str(&self.snippet[end..highlight.start]);
+            text.push_str(before);
+            text.push_str(&self.snippet[highlight.clone()]);
+            text.push_str(after);
+            end = highlight.end;
+        }
+        text.push_str(&self.snippet[end..]);
+        text
+    }
+}
+
+/// A block whose title matches a fuzzy query, e.g. for a "jump to section" picker.
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct TitleMatch {
+    pub block_id: usize,
+    pub title: String,
+    /// Higher is better. Consecutive letters and letters starting words score more, gaps less.
+    pub score: i64,
+    /// Indices of the matched characters of the title, e.g. to highlight them.
+    pub indices: Vec<usize>,
+}
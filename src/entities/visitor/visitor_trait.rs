@@ -1,5 +1,59 @@
 use crate::entities::core::block::Block;
 
+/// What a traversal does after entering a block.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum VisitFlow {
+    /// Visit the children of the block.
+    #[default]
+    Continue,
+    /// Skip the children of the block, and go on with its next sibling. The block is still exited.
+    SkipChildren,
+    /// Stop the traversal. No block is exited, the current block and its ancestors included.
+    Stop,
+}
+
+/// Where a block is in a traversal.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct VisitContext {
+    /// Depth of the block in the traversed tree: 1 for root blocks, and the level of the parent + 1 for the others.
+    pub level: usize,
+    /// 0-indexed position of the block among its siblings.
+    pub index: usize,
+    /// Number of children of the block, visited unless they are skipped.
+    pub child_count: usize,
+}
+
+/// # Visitor
+///
+/// Visits the blocks of a tree in document order, e.g. with `VisitUtils::walk_blocks` or
+/// `VisitUtils::walk_outline`: each block is entered, then its children are visited, then it is exited.
+///
+/// Implement `visit_block` for a plain pre-order visit, or `enter_block` and `exit_block` to know when a subtree
+/// ends, skip subtrees or stop early.
 pub trait Visitor {
-    fn visit_block(&mut self, block: &Block);
-}
\ No newline at end of file
+    /// Called before the children of a block. Calls `visit_block` by default.
+    fn enter_block(&mut self, block: &Block, _context: &VisitContext) -> VisitFlow {
+        self.visit_block(block);
+        VisitFlow::Continue
+    }
+
+    /// Called after the children of a block, or after skipping them.
+    fn exit_block(&mut self, _block: &Block, _context: &VisitContext) {}
+
+    /// Called for each block in pre-order by the default `enter_block`.
+    fn visit_block(&mut self, _block: &Block) {}
+}
+
+/// # Visitor Mut
+///
+/// A `Visitor` changing the blocks in place, e.g. with `VisitUtils::walk_blocks_mut`. The children of a block are
+/// read after it is entered, and are all exited before it, so `exit_block` can sum up what was found in them.
+pub trait VisitorMut {
+    /// Called before the children of a block.
+    fn enter_block(&mut self, _block: &mut Block, _context: &VisitContext) -> VisitFlow {
+        VisitFlow::Continue
+    }
+
+    /// Called after the children of a block, or after skipping them.
+    fn exit_block(&mut self, _block: &mut Block, _context: &VisitContext) {}
+}
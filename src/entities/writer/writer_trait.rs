@@ -0,0 +1,13 @@
+use crate::entities::core::outline::Outline;
+use eyre::Report;
+
+pub trait WriterTrait {
+    fn write(&self, outline: &Outline) -> Result<String, Report>;
+
+    /// # Get the name of the writer
+    ///
+    /// Override this function to return the name of the writer for logging purposes.
+    fn name(&self) -> &'static str {
+        "Unknown Writer"
+    }
+}
@@ -1,7 +1,18 @@
 /// # Freeout
 ///
 /// Freeout is an engine for generating outline out of documents across formats.
+pub mod editors;
 pub mod entities;
+pub mod linkers;
+pub mod linters;
+pub mod lsp;
+pub mod navigators;
+pub mod outliners;
 pub mod processes;
+pub mod projects;
+pub mod queries;
 pub mod readers;
-mod utils;
+pub mod searchers;
+pub mod utils;
+pub mod watchers;
+pub mod writers;
@@ -0,0 +1,294 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use eyre::{eyre, Report};
+
+use crate::entities::core::block_range::BlockRange;
+use crate::entities::core::freeout::Freeout;
+use crate::entities::graph::link::{Link, LinkKind};
+use crate::entities::graph::link_graph::{GraphLink, LinkGraph, LinkTarget};
+use crate::projects::project::Project;
+use crate::readers::markdown::MarkdownReader;
+use crate::utils::anchor_utils::AnchorUtils;
+
+/// A document of a link graph.
+struct LinkedDocument<'a> {
+    freeout: &'a Freeout,
+    /// The canonical path of the document, if it has one.
+    path: Option<PathBuf>,
+    /// Id in the graph of the first block of the document.
+    first_block_id: usize,
+    /// The block a link to the whole document leads to, e.g. the entry of a file in a `Project`.
+    document_block_id: Option<usize>,
+    /// Ids in the graph of the blocks, by anchor.
+    anchors: HashMap<String, usize>,
+}
+
+impl<'a> LinkedDocument<'a> {
+    fn new(freeout: &'a Freeout, first_block_id: usize, document_block_id: Option<usize>) -> Self {
+        // included blocks are part of the rendered document, so they have anchors as well
+        let blocks = (1..=freeout.blocks.len()).map(|block_id| &freeout.blocks[&block_id]);
+        let anchors = AnchorUtils::get_anchors(blocks)
+            .into_iter()
+            .map(|(block_id, anchor)| (anchor, first_block_id + block_id - 1))
+            .collect();
+
+        Self {
+            freeout,
+            path: freeout
+                .get_path()
+                .map(|path| path.canonicalize().unwrap_or(path.to_path_buf())),
+            first_block_id,
+            document_block_id: document_block_id
+                .or((!freeout.blocks.is_empty()).then_some(first_block_id)),
+            anchors,
+        }
+    }
+
+    /// Whether the links of the document can be read, i.e. it is Markdown.
+    fn is_markdown(&self) -> bool {
+        self.path
+            .as_deref()
+            .and_then(Path::extension)
+            .is_none_or(|extension| {
+                matches!(
+                    extension.to_string_lossy().to_lowercase().as_str(),
+                    "md" | "markdown" | "mdx"
+                )
+            })
+    }
+
+    fn get_folder(&self) -> &Path {
+        self.path
+            .as_deref()
+            .and_then(Path::parent)
+            .unwrap_or(Path::new(""))
+    }
+
+    /// Whether a wikilink name, e.g. `Note` or `notes/Note`, is the name of the document.
+    fn has_name(&self, name: &str) -> bool {
+        let name = name.rsplit('/').next().unwrap_or(name).to_lowercase();
+        let name = name.strip_suffix(".md").unwrap_or(&name);
+        self.path
+            .as_deref()
+            .and_then(Path::file_stem)
+            .is_some_and(|stem| stem.to_string_lossy().to_lowercase() == name)
+    }
+}
+
+impl Freeout {
+    /// # Build the link graph of the document
+    ///
+    /// Links to other files are `External` if the files exist, and `Broken` otherwise. See `Project::link_graph` to
+    /// resolve links across files.
+    pub fn link_graph(&self) -> Result<LinkGraph, Report> {
+        build_link_graph(&[LinkedDocument::new(self, 1, None)], HashSet::new())
+    }
+}
+
+impl Project {
+    /// # Build the link graph of the project
+    ///
+    /// Links to a file of the project lead to the block of its entry, or to a block of the file with a fragment.
+    /// Entries are never orphans.
+    pub fn link_graph(&self) -> Result<LinkGraph, Report> {
+        let documents: Vec<LinkedDocument> = self
+            .files
+            .iter()
+            .map(|file| {
+                LinkedDocument::new(
+                    &file.freeout,
+                    file.first_block_id,
+                    Some(file.entry_block_id),
+                )
+            })
+            .collect();
+        let entry_block_ids = (1..=self.blocks.len())
+            .filter(|block_id| self.get_file_block(*block_id).is_none())
+            .collect();
+        build_link_graph(&documents, entry_block_ids)
+    }
+}
+
+fn build_link_graph(
+    documents: &[LinkedDocument],
+    document_block_ids: HashSet<usize>,
+) -> Result<LinkGraph, Report> {
+    let reader = MarkdownReader::default();
+    let mut links = vec![];
+
+    for document in documents.iter().filter(|document| document.is_markdown()) {
+        let freeout = document.freeout;
+        let document_links =
+            reader
+                .read_links(freeout.get_text())
+                .map_err(|err| match &document.path {
+                    Some(path) => eyre!("{}: {}", path.display(), err),
+                    None => err,
+                })?;
+
+        for link in document_links {
+            let source_block_id = freeout
+                .index
+                .get_last_started(link.offsets.start)
+                .map(|entry| document.first_block_id + entry.block_id - 1);
+            links.push(GraphLink {
+                source_block_id,
+                source_path: document.path.clone(),
+                range: BlockRange {
+                    start: freeout.get_encoded_position(link.offsets.start),
+                    end: freeout.get_encoded_position(link.offsets.end),
+                },
+                target: resolve_link(documents, document, &link),
+                link,
+            });
+        }
+    }
+
+    Ok(LinkGraph::new(links, document_block_ids))
+}
+
+fn resolve_link(
+    documents: &[LinkedDocument],
+    document: &LinkedDocument,
+    link: &Link,
+) -> LinkTarget {
+    let (path, fragment) = link.split_destination();
+    let path = path.trim();
+
+    let target_document = match link.kind {
+        _ if path.is_empty() => document,
+        LinkKind::Markdown => {
+            if has_scheme(path) {
+                return LinkTarget::External;
+            }
+            let path = path.split('?').next().unwrap_or(path).replace("%20", " ");
+            let target_path = document.get_folder().join(path);
+            let canonical_path = target_path.canonicalize().ok();
+            match documents
+                .iter()
+                .find(|other| other.path.is_some() && other.path == canonical_path)
+            {
+                Some(other) => other,
+                None => return get_unresolved_target(&target_path),
+            }
+        }
+        LinkKind::WikiLink => match documents.iter().find(|other| other.has_name(path)) {
+            Some(other) => other,
+            None => {
+                return get_unresolved_target(&document.get_folder().join(format!("{}.md", path)))
+            }
+        },
+    };
+
+    let Some(fragment) = fragment else {
+        return target_document
+            .document_block_id
+            .map_or(LinkTarget::External, |block_id| LinkTarget::Block {
+                block_id,
+            });
+    };
+    let block_id = match link.kind {
+        LinkKind::Markdown => target_document
+            .anchors
+            .get(&fragment.to_lowercase())
+            .copied(),
+        // a wikilink names the heading, e.g. `[[Note#Heading]]`
+        LinkKind::WikiLink => target_document
+            .anchors
+            .get(&AnchorUtils::slugify(fragment))
+            .copied(),
+    };
+    block_id.map_or(LinkTarget::Broken, |block_id| LinkTarget::Block {
+        block_id,
+    })
+}
+
+/// A target outside of the graph is external if it exists, e.g. an image, and broken otherwise.
+fn get_unresolved_target(path: &Path) -> LinkTarget {
+    match path.exists() {
+        true => LinkTarget::External,
+        false => LinkTarget::Broken,
+    }
+}
+
+/// Whether a destination starts with a URL scheme, e.g. `https:` or `mailto:`.
+fn has_scheme(destination: &str) -> bool {
+    destination.starts_with("//")
+        || destination.split_once(':').is_some_and(|(scheme, _)| {
+            scheme.len() > 1
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "+-.".contains(c))
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::core::freeout::Freeout;
+    use crate::entities::graph::link::LinkKind;
+    use crate::entities::graph::link_graph::LinkTarget;
+    use crate::entities::project::manifest::{ProjectManifest, WalkOrder};
+    use crate::projects::project::Project;
+    use crate::readers::markdown::MarkdownReader;
+    use crate::utils::test_utils::test_utils::get_fixture_folder;
+
+    #[test]
+    fn should_resolve_links_across_files() {
+        let manifest = ProjectManifest::Directory {
+            root: get_fixture_folder().join("knowledge_base"),
+            order: WalkOrder::Natural,
+        };
+        let project = Project::open(&manifest, None).unwrap();
+        let graph = project.link_graph().unwrap();
+
+        // blocks: 1 index, 2 Index, 3 About, 4 notes, 5 cats, 6 Cats, 7 Food, 8 Sleep, 9 dogs, 10 Dogs, 11 Walks,
+        // 12 Tricks, 13 fish, 14 Fish
+        let links: Vec<(Option<usize>, &str, LinkTarget)> = graph
+            .links
+            .iter()
+            .map(|link| {
+                (
+                    link.source_block_id,
+                    link.link.destination.as_str(),
+                    link.target.clone(),
+                )
+            })
+            .collect();
+        let block = |block_id| LinkTarget::Block { block_id };
+        assert_eq!(
+            links,
+            vec![
+                (Some(2), "notes/cats.md", block(5)),
+                (Some(2), "notes/cats.md#food", block(7)),
+                (Some(2), "dogs#Walks", block(11)),
+                (Some(2), "notes/birds.md", LinkTarget::Broken),
+                (Some(2), "https://example.com", LinkTarget::External),
+                (Some(2), "#about", block(3)),
+                (Some(6), "dogs", block(9)),
+                (Some(6), "#sleep", block(8)),
+                (Some(7), "#Naps and Sleep", LinkTarget::Broken),
+                (Some(11), "index", block(1)),
+            ]
+        );
+        assert_eq!(graph.links[2].link.kind, LinkKind::WikiLink);
+        assert_eq!(graph.links[2].link.text, "walks");
+        assert_eq!(graph.links[0].range.start.line, 2);
+
+        assert_eq!(graph.get_outgoing(6).len(), 2);
+        assert_eq!(graph.get_incoming(5).len(), 1);
+        assert_eq!(graph.get_broken_links().len(), 2);
+        // the subsections of linked notes are reachable, unlike a note no other note links to
+        assert_eq!(graph.get_orphans(&project.blocks), vec![14]);
+
+        let mut freeout = Freeout::new(
+            "# Intro\n\nSee [the end](#end).\n\n# End\n\nBack to [[#Nowhere]].\n".to_string(),
+            None,
+        );
+        freeout.outline(&MarkdownReader::default()).unwrap();
+        let graph = freeout.link_graph().unwrap();
+        assert_eq!(graph.get_incoming(2)[0].source_block_id, Some(1));
+        assert_eq!(graph.get_broken_links()[0].source_block_id, Some(2));
+        assert_eq!(graph.get_orphans(&freeout.blocks), vec![1]);
+    }
+}
@@ -0,0 +1,9 @@
+//! # Linkers
+//!
+//! Resolve the links in the content of documents to the blocks they lead to, e.g. to keep a knowledge base
+//! navigable.
+//!
+//! Links are read from the Markdown syntax tree, so links in code are ignored, and resolved through the anchors
+//! generated from the titles, as in the table of contents. Wikilinks, e.g. `[[Note#Heading]]`, are resolved by file
+//! name and heading.
+pub mod link_resolver;
@@ -0,0 +1,6 @@
+//! # Linters
+//!
+//! Checks of the structure of an outlined `Freeout`, reported as `Diagnostic`s at the `header_range` of each block.
+//!
+//! The rules are listed in `LintRule`, and configured with a `LintConfig`.
+pub mod outline_linter;
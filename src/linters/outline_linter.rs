@@ -0,0 +1,244 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::entities::core::block::Block;
+use crate::entities::core::freeout::Freeout;
+use crate::entities::core::words_statistics::TargetState;
+use crate::entities::lint::diagnostic::{Diagnostic, LintReport, LintRule, Severity};
+use crate::entities::lint::lint_config::LintConfig;
+
+impl Freeout {
+    /// # Lint the outline
+    ///
+    /// Checks every block against the rules that are not `Severity::Off`. Expects the blocks to be outlined, as some
+    /// rules rely on statistics.
+    pub fn lint(&self, config: &LintConfig) -> LintReport {
+        // block ids follow the document order
+        let blocks: Vec<&Block> = (1..=self.blocks.len())
+            .map(|block_id| &self.blocks[&block_id])
+            .collect();
+        let duplicate_ids = get_duplicate_sibling_ids(&blocks);
+        let first_root_id = blocks
+            .iter()
+            .find(|block| block.parent_id.is_none())
+            .map(|block| block.id);
+
+        let mut diagnostics = vec![];
+        for block in blocks {
+            for rule in LintRule::ALL {
+                let severity = config.get_severity(rule);
+                if severity == Severity::Off {
+                    continue;
+                }
+
+                let message = match rule {
+                    LintRule::SkippedHeadingLevel => self.check_skipped_heading_level(block),
+                    LintRule::EmptySection => {
+                        check_empty_section(block, &self.get_content(block.id).unwrap_or_default())
+                    }
+                    LintRule::DuplicateSiblingTitle => duplicate_ids
+                        .contains(&block.id)
+                        .then(|| format!("Duplicate title among siblings: `{}`", block.title)),
+                    LintRule::TrailingPunctuation => {
+                        check_trailing_punctuation(block, &config.trailing_punctuation_characters)
+                    }
+                    LintRule::LongTitle => check_long_title(block, config.max_title_length),
+                    LintRule::MultipleTopLevel => (block.parent_id.is_none()
+                        && Some(block.id) != first_root_id)
+                        .then(|| "More than one top-level section".to_string()),
+                    LintRule::WordTarget => check_word_target(block),
+                    LintRule::SingleChild => (block.children_ids.len() == 1)
+                        .then(|| "Single child section, which could be merged".to_string()),
+                };
+
+                if let Some(message) = message {
+                    diagnostics.push(Diagnostic {
+                        rule,
+                        severity,
+                        block_id: block.id,
+                        range: block.header_range.clone(),
+                        source_path: block.source_path.clone(),
+                        message,
+                    });
+                }
+            }
+        }
+
+        LintReport { diagnostics }
+    }
+
+    fn check_skipped_heading_level(&self, block: &Block) -> Option<String> {
+        let parent = self.blocks.get(&block.parent_id?)?;
+        (block.depth > parent.depth + 1).then(|| {
+            format!(
+                "Heading level skipped: depth {} under depth {}",
+                block.depth, parent.depth
+            )
+        })
+    }
+}
+
+/// Ids of the blocks whose title is already used by a previous sibling, ignoring case.
+fn get_duplicate_sibling_ids(blocks: &[&Block]) -> HashSet<usize> {
+    let mut titles: HashMap<Option<usize>, HashSet<String>> = HashMap::new();
+    blocks
+        .iter()
+        .filter(|block| {
+            !titles
+                .entry(block.parent_id)
+                .or_default()
+                .insert(block.title.trim().to_lowercase())
+        })
+        .map(|block| block.id)
+        .collect()
+}
+
+fn check_empty_section(block: &Block, content: &str) -> Option<String> {
+    if !block.children_ids.is_empty() || block.note.is_some() {
+        return None;
+    }
+
+    // the content of a Markdown heading starts with the text of the heading itself
+    let content = content.trim();
+    let body = match content.split_once('\n') {
+        Some((first_line, body)) if first_line.starts_with(&block.title) => body,
+        None if content.starts_with(&block.title) => "",
+        _ => content,
+    };
+
+    body.trim()
+        .is_empty()
+        .then(|| "Empty section without content or subsections".to_string())
+}
+
+fn check_trailing_punctuation(block: &Block, characters: &str) -> Option<String> {
+    let last = block.title.trim_end().chars().last()?;
+    characters
+        .contains(last)
+        .then(|| format!("Title ends with punctuation `{}`", last))
+}
+
+fn check_long_title(block: &Block, max_title_length: usize) -> Option<String> {
+    let length = block.title.chars().count();
+    (length > max_title_length).then(|| {
+        format!(
+            "Title is {} characters long, over {}",
+            length, max_title_length
+        )
+    })
+}
+
+fn check_word_target(block: &Block) -> Option<String> {
+    let stats = &block.aggregate_stats;
+    let target = stats.target.as_ref()?;
+    let state = match stats.get_target_state()? {
+        TargetState::Under => "under",
+        TargetState::Over => "over",
+        TargetState::OnTarget => return None,
+    };
+    Some(format!(
+        "{} words, {} the target of {}",
+        stats.count.words, state, target.words
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::core::freeout::Freeout;
+    use crate::entities::core::words_statistics::{DistributionMethod, WordsTarget};
+    use crate::entities::lint::diagnostic::{Diagnostic, LintReport, LintRule, Severity};
+    use crate::entities::lint::lint_config::LintConfig;
+    use crate::readers::markdown::MarkdownReader;
+
+    const MARKDOWN: &str = "# Report\n\nSummary here\n\n### Methods:\n\nOne two\n\n## Results\n\n## results\n\nThree\n\n# Appendix\n\n## Sources\n\nA very long title\n";
+
+    fn get_rules(report: &LintReport) -> Vec<(usize, LintRule)> {
+        report
+            .diagnostics
+            .iter()
+            .map(|diagnostic| (diagnostic.block_id, diagnostic.rule))
+            .collect()
+    }
+
+    #[test]
+    fn should_report_rule_violations_in_document_order() {
+        let mut freeout = Freeout::new(MARKDOWN.to_string(), None);
+        freeout.outline(&MarkdownReader::default()).unwrap();
+
+        let report = freeout.lint(&LintConfig::default());
+        assert_eq!(
+            get_rules(&report),
+            vec![
+                (2, LintRule::SkippedHeadingLevel),
+                (2, LintRule::TrailingPunctuation),
+                (3, LintRule::EmptySection),
+                (4, LintRule::DuplicateSiblingTitle),
+                (5, LintRule::MultipleTopLevel),
+                (5, LintRule::SingleChild),
+            ]
+        );
+        assert_eq!(report.diagnostics[0].range.start.line, 4);
+        assert!(!report.has_errors());
+        assert_eq!(report.count(Severity::Info), 2);
+    }
+
+    #[test]
+    fn should_apply_config_and_serialize_to_json() {
+        let mut freeout = Freeout::new(MARKDOWN.to_string(), None);
+        freeout.outline(&MarkdownReader::default()).unwrap();
+        freeout.blocks.get_mut(&5).unwrap().aggregate_stats.target = Some(WordsTarget {
+            words: 100,
+            distribution: Some(DistributionMethod::Uniform),
+            distributed: false,
+        });
+        freeout.process_stats();
+
+        let config: LintConfig = serde_json::from_str(
+            r#"{"skipped_heading_level": "error", "trailing_punctuation": "off", "empty_section": "off",
+                "duplicate_sibling_title": "off", "multiple_top_level": "off", "single_child": "off",
+                "max_title_length": 16}"#,
+        )
+        .unwrap();
+        let report = freeout.lint(&config);
+        assert_eq!(
+            get_rules(&report),
+            vec![
+                (2, LintRule::SkippedHeadingLevel),
+                (5, LintRule::WordTarget),
+                (6, LintRule::WordTarget),
+            ]
+        );
+        assert!(report.has_errors());
+
+        let json = serde_json::to_string(&report).unwrap();
+        assert!(json.contains(r#""rule":"skipped-heading-level","severity":"error","block_id":2"#));
+        assert_eq!(serde_json::from_str::<LintReport>(&json).unwrap(), report);
+    }
+
+    #[test]
+    fn should_report_titles_over_max_length() {
+        let mut freeout = Freeout::new(MARKDOWN.to_string(), None);
+        freeout.outline(&MarkdownReader::default()).unwrap();
+
+        let config = LintConfig {
+            max_title_length: 7,
+            ..LintConfig::default()
+        };
+        let report = freeout.lint(&config);
+        let long_titles: Vec<&Diagnostic> = report
+            .diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.rule == LintRule::LongTitle)
+            .collect();
+
+        // `Methods:` and `Appendix` are 8 characters long, `Results` is exactly at the limit
+        assert_eq!(
+            long_titles
+                .iter()
+                .map(|diagnostic| diagnostic.block_id)
+                .collect::<Vec<usize>>(),
+            vec![2, 5]
+        );
+        assert_eq!(long_titles[0].severity, Severity::Warning);
+        assert_eq!(long_titles[0].message, "Title is 8 characters long, over 7");
+    }
+}
@@ -0,0 +1,72 @@
+//! Conversions between the byte offsets of `Freeout` and the positions of LSP, whose columns are in UTF-16 code
+//! units.
+use lsp_types::{Position as LspPosition, Range as LspRange};
+
+use crate::entities::core::block_range::BlockRange;
+use crate::entities::core::freeout::Freeout;
+use crate::entities::core::position_encoding::PositionEncoding;
+
+/// Get the LSP position of a byte offset.
+pub fn to_lsp_position(freeout: &Freeout, offset: usize) -> LspPosition {
+    let position = freeout.get_position(offset);
+    let line_start = offset - position.column;
+    LspPosition {
+        line: position.line as u32,
+        character: freeout.get_text()[line_start..offset]
+            .encode_utf16()
+            .count() as u32,
+    }
+}
+
+/// Get the LSP range of a block range, from the byte offsets of its ends.
+pub fn to_lsp_range(freeout: &Freeout, range: &BlockRange) -> LspRange {
+    LspRange {
+        start: to_lsp_position(freeout, range.start.offset),
+        end: to_lsp_position(freeout, range.end.offset),
+    }
+}
+
+/// Get the byte offset of an LSP position. Positions beyond the end of a line or of the text are clamped.
+pub fn to_offset(freeout: &Freeout, position: &LspPosition) -> usize {
+    let line = position.line as usize;
+    match freeout.get_line(line) {
+        Some(line_text) => {
+            freeout.line_starts[line]
+                + PositionEncoding::Utf16.get_byte_index(line_text, position.character as usize)
+        }
+        None => freeout.get_text().len(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_types::Position as LspPosition;
+
+    use crate::entities::core::freeout::Freeout;
+    use crate::lsp::conversions::{to_lsp_position, to_offset};
+
+    #[test]
+    fn should_convert_byte_offsets_to_utf16() {
+        // "标" is 3 bytes and 1 UTF-16 unit, "😀" is 4 bytes and 2 UTF-16 units
+        let freeout = Freeout::new("# 标题\n\n😀 ok\n".to_string(), None);
+
+        let offset = "# 标题\n\n😀".len();
+        let position = to_lsp_position(&freeout, offset);
+        assert_eq!(position, LspPosition::new(2, 2));
+        assert_eq!(to_offset(&freeout, &position), offset);
+
+        assert_eq!(
+            to_lsp_position(&freeout, "# 标题".len()),
+            LspPosition::new(0, 4)
+        );
+        // clamped to the end of the line and of the text
+        assert_eq!(
+            to_offset(&freeout, &LspPosition::new(0, 99)),
+            "# 标题".len()
+        );
+        assert_eq!(
+            to_offset(&freeout, &LspPosition::new(9, 0)),
+            freeout.get_text().len()
+        );
+    }
+}
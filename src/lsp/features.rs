@@ -0,0 +1,191 @@
+//! The LSP features of an outlined document.
+use lsp_types::{
+    CodeLens, Command, Diagnostic, DiagnosticSeverity, DocumentSymbol, FoldingRange,
+    FoldingRangeKind, Hover, HoverContents, MarkupContent, MarkupKind, NumberOrString,
+    Position as LspPosition, SymbolKind,
+};
+
+use crate::entities::core::block::Block;
+use crate::entities::core::freeout::Freeout;
+use crate::entities::core::outline::{OutlineItemRef, OutlineRef};
+use crate::entities::lint::diagnostic::{LintReport, Severity};
+use crate::lsp::conversions::{to_lsp_range, to_offset};
+
+/// Source of the diagnostics, as shown by editors.
+pub const DIAGNOSTIC_SOURCE: &str = "freeout";
+
+/// Hierarchical symbols, one per block, spanning its `block_range`.
+///
+/// The blocks included from other files are skipped, like in all the features, as their positions are in their own
+/// file. Their words still count in the sections including them.
+pub fn get_document_symbols(freeout: &Freeout, outline: &OutlineRef) -> Vec<DocumentSymbol> {
+    outline
+        .items
+        .iter()
+        .filter(|item| is_in_document(item.block))
+        .map(|item| get_document_symbol(freeout, item))
+        .collect()
+}
+
+#[allow(deprecated)] // `DocumentSymbol::deprecated` has to be set
+fn get_document_symbol(freeout: &Freeout, item: &OutlineItemRef) -> DocumentSymbol {
+    let block = item.block;
+    let selection_range = to_lsp_range(freeout, &block.header_range);
+    DocumentSymbol {
+        name: match block.title.trim() {
+            // editors reject empty symbol names
+            "" => "(untitled)".to_string(),
+            title => title.to_string(),
+        },
+        detail: Some(format!(
+            "{} words",
+            format_number(block.aggregate_stats.count.words)
+        )),
+        kind: SymbolKind::NAMESPACE,
+        tags: None,
+        deprecated: None,
+        range: block
+            .block_range
+            .as_ref()
+            .map_or(selection_range, |range| to_lsp_range(freeout, range)),
+        selection_range,
+        children: Some(
+            item.subitems
+                .iter()
+                .filter(|subitem| is_in_document(subitem.block))
+                .map(|subitem| get_document_symbol(freeout, subitem))
+                .collect(),
+        ),
+    }
+}
+
+/// A folding range per block spanning more than its header line.
+pub fn get_folding_ranges(freeout: &Freeout) -> Vec<FoldingRange> {
+    (1..=freeout.blocks.len())
+        .map(|block_id| &freeout.blocks[&block_id])
+        .filter(|block| is_in_document(block))
+        .filter_map(|block| {
+            let range = to_lsp_range(freeout, block.block_range.as_ref()?);
+            (range.end.line > range.start.line).then_some(FoldingRange {
+                start_line: range.start.line,
+                start_character: None,
+                end_line: range.end.line,
+                end_character: None,
+                kind: Some(FoldingRangeKind::Region),
+                collapsed_text: None,
+            })
+        })
+        .collect()
+}
+
+/// The diagnostics of a lint report, at the headers of their blocks.
+pub fn get_diagnostics(freeout: &Freeout, report: &LintReport) -> Vec<Diagnostic> {
+    report
+        .diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.source_path.is_none())
+        .map(|diagnostic| Diagnostic {
+            range: to_lsp_range(freeout, &diagnostic.range),
+            severity: Some(match diagnostic.severity {
+                Severity::Error => DiagnosticSeverity::ERROR,
+                Severity::Warning => DiagnosticSeverity::WARNING,
+                _ => DiagnosticSeverity::INFORMATION,
+            }),
+            code: Some(NumberOrString::String(diagnostic.rule.name().to_string())),
+            source: Some(DIAGNOSTIC_SOURCE.to_string()),
+            message: diagnostic.message.clone(),
+            ..Default::default()
+        })
+        .collect()
+}
+
+/// The word counts and target of the section at a position.
+pub fn get_hover(freeout: &Freeout, position: &LspPosition) -> Option<Hover> {
+    let block = freeout.block_at(to_offset(freeout, position))?;
+    let stats = &block.aggregate_stats;
+
+    let mut lines = vec![
+        format!("**{}**", block.title),
+        format!(
+            "{} words, {} in its own content",
+            format_number(stats.count.words),
+            format_number(block.self_stats.count.words)
+        ),
+    ];
+    if let Some(target) = &stats.target {
+        let mut line = format!("Target: {} words", format_number(target.words));
+        if let Some(status) = &stats.status {
+            line.push_str(&format!(" ({:+})", status.balance));
+        }
+        lines.push(line);
+    }
+    if block.exclude {
+        lines.push("Excluded from statistics".to_string());
+    }
+
+    Some(Hover {
+        contents: HoverContents::Markup(MarkupContent {
+            kind: MarkupKind::Markdown,
+            value: lines.join("\n\n"),
+        }),
+        range: Some(to_lsp_range(freeout, &block.header_range)),
+    })
+}
+
+/// A code lens above every header, e.g. `1,234 / 2,000 words`.
+pub fn get_code_lenses(freeout: &Freeout) -> Vec<CodeLens> {
+    (1..=freeout.blocks.len())
+        .map(|block_id| &freeout.blocks[&block_id])
+        .filter(|block| is_in_document(block))
+        .map(|block| {
+            let stats = &block.aggregate_stats;
+            let words = format_number(stats.count.words);
+            let title = match &stats.target {
+                Some(target) => format!("{} / {} words", words, format_number(target.words)),
+                None => format!("{} words", words),
+            };
+
+            CodeLens {
+                range: to_lsp_range(freeout, &block.header_range),
+                // a label without an action
+                command: Some(Command {
+                    title,
+                    command: "".to_string(),
+                    arguments: None,
+                }),
+                data: None,
+            }
+        })
+        .collect()
+}
+
+/// Whether a block is in the text of the document, rather than included from another file.
+fn is_in_document(block: &Block) -> bool {
+    block.source_path.is_none()
+}
+
+/// Format a number with thousands separators, e.g. `1,234`.
+fn format_number(number: usize) -> String {
+    let digits = number.to_string();
+    let mut formatted = String::new();
+    for (index, digit) in digits.chars().enumerate() {
+        if index > 0 && (digits.len() - index).is_multiple_of(3) {
+            formatted.push(',');
+        }
+        formatted.push(digit);
+    }
+    formatted
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::lsp::features::format_number;
+
+    #[test]
+    fn test_format_number() {
+        assert_eq!(format_number(0), "0");
+        assert_eq!(format_number(999), "999");
+        assert_eq!(format_number(1234), "1,234");
+        assert_eq!(format_number(1234567), "1,234,567");
+    }
+}
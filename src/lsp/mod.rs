@@ -0,0 +1,10 @@
+//! # Language Server
+//!
+//! Serves the outline of documents to editors over the Language Server Protocol: document symbols, folding ranges,
+//! lint diagnostics, hovers with word counts and code lenses with targets.
+//!
+//! LSP positions count columns in UTF-16 code units, while `Freeout` ranges are byte offsets, so every position goes
+//! through `conversions`.
+pub mod conversions;
+pub mod features;
+pub mod server;
@@ -0,0 +1,511 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use eyre::Report;
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, Response};
+use lsp_types::notification::{
+    DidChangeTextDocument, DidCloseTextDocument, DidOpenTextDocument,
+    Notification as NotificationTrait, PublishDiagnostics,
+};
+use lsp_types::request::{
+    CodeLensRequest, DocumentSymbolRequest, FoldingRangeRequest, HoverRequest,
+    Request as RequestTrait,
+};
+use lsp_types::{
+    CodeLensOptions, Diagnostic, DiagnosticSeverity, DocumentSymbolResponse,
+    FoldingRangeProviderCapability, HoverProviderCapability, InitializeParams, OneOf,
+    PublishDiagnosticsParams, ServerCapabilities, TextDocumentSyncCapability, TextDocumentSyncKind,
+    Url,
+};
+use tracing::{debug, warn};
+
+use crate::entities::core::freeout::Freeout;
+use crate::entities::lint::lint_config::LintConfig;
+use crate::lsp::features::{
+    get_code_lenses, get_diagnostics, get_document_symbols, get_folding_ranges, get_hover,
+    DIAGNOSTIC_SOURCE,
+};
+use crate::readers::get_reader_by_extension;
+use crate::readers::markdown::MarkdownReader;
+
+/// An open document, outlined on every change.
+struct Document {
+    freeout: Freeout,
+    /// Whether the document was outlined, or the error of the reader.
+    outline: Result<(), String>,
+}
+
+impl Document {
+    fn new(uri: &Url, text: String) -> Self {
+        let mut freeout = Freeout::new(text, None);
+        // includes are resolved from the folder of the document
+        if let Ok(path) = uri.to_file_path() {
+            freeout = freeout.with_path(path);
+        }
+        let outline = match Path::new(uri.path())
+            .extension()
+            .and_then(|extension| get_reader_by_extension(&extension.to_string_lossy()))
+        {
+            Some(reader) => freeout.process(reader.as_ref()),
+            None => freeout.process(&MarkdownReader::default()),
+        };
+
+        Self {
+            freeout,
+            outline: outline.map_err(|err| err.to_string()),
+        }
+    }
+}
+
+/// # LSP Server
+///
+/// Serves the outline of the open documents, with full document sync. The `initializationOptions` of the client
+/// are read as a `LintConfig`.
+pub struct LspServer {
+    documents: HashMap<Url, Document>,
+    lint_config: LintConfig,
+}
+
+/// Run the server over the standard input and output, until the client exits.
+pub fn run_stdio() -> Result<(), Report> {
+    let (connection, io_threads) = Connection::stdio();
+    run(&connection)?;
+    // the IO threads end once the connection is dropped
+    drop(connection);
+    io_threads.join()?;
+    Ok(())
+}
+
+/// Run the server over a connection, from the initialization until the client exits.
+pub fn run(connection: &Connection) -> Result<(), Report> {
+    let params = connection.initialize(serde_json::to_value(get_capabilities())?)?;
+    let params: InitializeParams = serde_json::from_value(params)?;
+    let lint_config = match params.initialization_options {
+        Some(options) => serde_json::from_value(options)?,
+        None => LintConfig::default(),
+    };
+
+    let mut server = LspServer {
+        documents: HashMap::new(),
+        lint_config,
+    };
+    for message in &connection.receiver {
+        match message {
+            Message::Request(request) => {
+                if connection.handle_shutdown(&request)? {
+                    return Ok(());
+                }
+                connection
+                    .sender
+                    .send(Message::Response(server.handle_request(request)))?;
+            }
+            Message::Notification(notification) => {
+                let method = notification.method.clone();
+                match server.handle_notification(notification) {
+                    Ok(Some(uri)) => connection.sender.send(Message::Notification(
+                        server.get_diagnostics_notification(uri),
+                    ))?,
+                    Ok(None) => {}
+                    // a notification has no response, so the client cannot be told
+                    Err(err) => warn!("Failed to handle notification {}: {}", method, err),
+                }
+            }
+            Message::Response(_) => {}
+        }
+    }
+    Ok(())
+}
+
+fn get_capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        document_symbol_provider: Some(OneOf::Left(true)),
+        folding_range_provider: Some(FoldingRangeProviderCapability::Simple(true)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        code_lens_provider: Some(CodeLensOptions {
+            resolve_provider: Some(false),
+        }),
+        ..Default::default()
+    }
+}
+
+impl LspServer {
+    fn handle_request(&self, request: Request) -> Response {
+        debug!("Handling request: {}", request.method);
+        match request.method.as_str() {
+            DocumentSymbolRequest::METHOD => {
+                self.respond::<DocumentSymbolRequest>(request, |document, _| {
+                    document.outline.as_ref().ok()?;
+                    Some(DocumentSymbolResponse::Nested(get_document_symbols(
+                        &document.freeout,
+                        &document.freeout.get_outline(),
+                    )))
+                })
+            }
+            FoldingRangeRequest::METHOD => self
+                .respond::<FoldingRangeRequest>(request, |document, _| {
+                    Some(get_folding_ranges(&document.freeout))
+                }),
+            HoverRequest::METHOD => self.respond::<HoverRequest>(request, |document, params| {
+                get_hover(
+                    &document.freeout,
+                    &params.text_document_position_params.position,
+                )
+            }),
+            CodeLensRequest::METHOD => self.respond::<CodeLensRequest>(request, |document, _| {
+                Some(get_code_lenses(&document.freeout))
+            }),
+            method => Response::new_err(
+                request.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("Unsupported method: {}", method),
+            ),
+        }
+    }
+
+    /// Respond to a request on a document. The result of a closed document is empty.
+    fn respond<R>(
+        &self,
+        request: Request,
+        handler: impl Fn(&Document, R::Params) -> R::Result,
+    ) -> Response
+    where
+        R: RequestTrait,
+        R::Params: HasUri,
+    {
+        let params: R::Params = match serde_json::from_value(request.params) {
+            Ok(params) => params,
+            Err(err) => {
+                return Response::new_err(
+                    request.id,
+                    ErrorCode::InvalidParams as i32,
+                    err.to_string(),
+                )
+            }
+        };
+
+        match self.documents.get(params.get_uri()) {
+            Some(document) => Response::new_ok(request.id, handler(document, params)),
+            None => Response::new_ok(request.id, serde_json::Value::Null),
+        }
+    }
+
+    /// Update the documents, and get the document whose diagnostics changed.
+    ///
+    /// Fails on invalid parameters, which the main loop logs and skips.
+    fn handle_notification(&mut self, notification: Notification) -> Result<Option<Url>, Report> {
+        debug!("Handling notification: {}", notification.method);
+        match notification.method.as_str() {
+            DidOpenTextDocument::METHOD => {
+                let params: <DidOpenTextDocument as NotificationTrait>::Params =
+                    serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+                self.documents
+                    .insert(uri.clone(), Document::new(&uri, params.text_document.text));
+                Ok(Some(uri))
+            }
+            DidChangeTextDocument::METHOD => {
+                let params: <DidChangeTextDocument as NotificationTrait>::Params =
+                    serde_json::from_value(notification.params)?;
+                let uri = params.text_document.uri;
+                // with full sync, the last change is the whole text
+                let Some(change) = params.content_changes.into_iter().last() else {
+                    return Ok(None);
+                };
+                self.documents
+                    .insert(uri.clone(), Document::new(&uri, change.text));
+                Ok(Some(uri))
+            }
+            DidCloseTextDocument::METHOD => {
+                let params: <DidCloseTextDocument as NotificationTrait>::Params =
+                    serde_json::from_value(notification.params)?;
+                self.documents.remove(&params.text_document.uri);
+                Ok(Some(params.text_document.uri))
+            }
+            _ => Ok(None),
+        }
+    }
+
+    /// The diagnostics of a document: its lint report, or the error of its reader. Empty once it is closed.
+    fn get_diagnostics_notification(&self, uri: Url) -> Notification {
+        let diagnostics = match self.documents.get(&uri) {
+            None => vec![],
+            Some(Document {
+                freeout,
+                outline: Ok(_),
+            }) => get_diagnostics(freeout, &freeout.lint(&self.lint_config)),
+            Some(Document {
+                outline: Err(error),
+                ..
+            }) => vec![Diagnostic {
+                severity: Some(DiagnosticSeverity::ERROR),
+                source: Some(DIAGNOSTIC_SOURCE.to_string()),
+                message: error.clone(),
+                ..Default::default()
+            }],
+        };
+
+        Notification::new(
+            PublishDiagnostics::METHOD.to_string(),
+            PublishDiagnosticsParams {
+                uri,
+                diagnostics,
+                version: None,
+            },
+        )
+    }
+}
+
+/// Request parameters on a document.
+trait HasUri {
+    fn get_uri(&self) -> &Url;
+}
+
+impl HasUri for lsp_types::DocumentSymbolParams {
+    fn get_uri(&self) -> &Url {
+        &self.text_document.uri
+    }
+}
+
+impl HasUri for lsp_types::FoldingRangeParams {
+    fn get_uri(&self) -> &Url {
+        &self.text_document.uri
+    }
+}
+
+impl HasUri for lsp_types::HoverParams {
+    fn get_uri(&self) -> &Url {
+        &self.text_document_position_params.text_document.uri
+    }
+}
+
+impl HasUri for lsp_types::CodeLensParams {
+    fn get_uri(&self) -> &Url {
+        &self.text_document.uri
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use lsp_server::{Connection, Message, Notification, Request, RequestId};
+    use lsp_types::notification::{
+        DidOpenTextDocument, Exit, Initialized, Notification as NotificationTrait,
+        PublishDiagnostics,
+    };
+    use lsp_types::request::{
+        CodeLensRequest, DocumentSymbolRequest, FoldingRangeRequest, HoverRequest, Initialize,
+        Request as RequestTrait, Shutdown,
+    };
+    use lsp_types::{
+        CodeLens, DidOpenTextDocumentParams, DocumentSymbolResponse, FoldingRange, Hover,
+        HoverContents, InitializeParams, Position, PublishDiagnosticsParams,
+        TextDocumentIdentifier, TextDocumentItem, Url,
+    };
+    use serde_json::{json, Value};
+
+    use crate::lsp::server::run;
+    use crate::utils::test_utils::test_utils::get_fixture_folder;
+
+    /// A client over an in-memory connection.
+    struct Client {
+        connection: Connection,
+        next_id: i32,
+    }
+
+    impl Client {
+        fn request<R: RequestTrait>(&mut self, params: R::Params) -> Value {
+            self.next_id += 1;
+            let id = RequestId::from(self.next_id);
+            self.connection
+                .sender
+                .send(Message::Request(Request::new(
+                    id.clone(),
+                    R::METHOD.to_string(),
+                    params,
+                )))
+                .unwrap();
+
+            match self.connection.receiver.recv().unwrap() {
+                Message::Response(response) if response.id == id => response.result.unwrap(),
+                message => panic!("Unexpected message: {:?}", message),
+            }
+        }
+
+        fn notify<N: NotificationTrait>(&self, params: N::Params) {
+            self.connection
+                .sender
+                .send(Message::Notification(Notification::new(
+                    N::METHOD.to_string(),
+                    params,
+                )))
+                .unwrap();
+        }
+    }
+
+    #[test]
+    fn should_serve_outline_over_lsp() {
+        let (server_connection, client_connection) = Connection::memory();
+        let server = std::thread::spawn(move || run(&server_connection));
+        let mut client = Client {
+            connection: client_connection,
+            next_id: 0,
+        };
+
+        let initialize_params: InitializeParams = serde_json::from_value(
+            json!({"capabilities": {}, "initializationOptions": {"single_child": "error"}}),
+        )
+        .unwrap();
+        let result = client.request::<Initialize>(initialize_params);
+        assert_eq!(result["capabilities"]["documentSymbolProvider"], true);
+        client.notify::<Initialized>(lsp_types::InitializedParams {});
+
+        // invalid parameters are skipped, and the server goes on
+        client
+            .connection
+            .sender
+            .send(Message::Notification(Notification::new(
+                DidOpenTextDocument::METHOD.to_string(),
+                json!({"textDocument": 1}),
+            )))
+            .unwrap();
+
+        let uri = Url::parse("file:///book/draft.md").unwrap();
+        client.notify::<DidOpenTextDocument>(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "markdown".to_string(),
+                version: 1,
+                text: "# 书 Book\n\nOne two\n\n## Chapter\n\nThree four five\n".to_string(),
+            },
+        });
+
+        // the diagnostics of the opened document, with the severity of the config
+        let Message::Notification(notification) = client.connection.receiver.recv().unwrap() else {
+            panic!("Expected diagnostics");
+        };
+        assert_eq!(notification.method, PublishDiagnostics::METHOD);
+        let diagnostics: PublishDiagnosticsParams =
+            serde_json::from_value(notification.params).unwrap();
+        assert_eq!(diagnostics.diagnostics.len(), 1);
+        assert_eq!(
+            diagnostics.diagnostics[0].severity,
+            Some(lsp_types::DiagnosticSeverity::ERROR)
+        );
+        // "书" is a single UTF-16 unit
+        assert_eq!(diagnostics.diagnostics[0].range.end, Position::new(0, 8));
+
+        let document = TextDocumentIdentifier { uri: uri.clone() };
+        let symbols: DocumentSymbolResponse =
+            serde_json::from_value(client.request::<DocumentSymbolRequest>(
+                serde_json::from_value(json!({"textDocument": document})).unwrap(),
+            ))
+            .unwrap();
+        let DocumentSymbolResponse::Nested(symbols) = symbols else {
+            panic!("Expected nested symbols");
+        };
+        assert_eq!(symbols[0].name, "书 Book");
+        assert_eq!(symbols[0].kind, lsp_types::SymbolKind::NAMESPACE);
+        assert_eq!(symbols[0].children.as_ref().unwrap()[0].name, "Chapter");
+        assert_eq!(
+            symbols[0].children.as_ref().unwrap()[0].range.start,
+            Position::new(4, 0)
+        );
+
+        let folding_ranges: Vec<FoldingRange> =
+            serde_json::from_value(client.request::<FoldingRangeRequest>(
+                serde_json::from_value(json!({"textDocument": document})).unwrap(),
+            ))
+            .unwrap();
+        assert_eq!(
+            folding_ranges
+                .iter()
+                .map(|range| (range.start_line, range.end_line))
+                .collect::<Vec<(u32, u32)>>(),
+            vec![(0, 7), (4, 7)]
+        );
+
+        let hover: Hover = serde_json::from_value(
+            client.request::<HoverRequest>(
+                serde_json::from_value(
+                    json!({"textDocument": document, "position": {"line": 6, "character": 2}}),
+                )
+                .unwrap(),
+            ),
+        )
+        .unwrap();
+        let HoverContents::Markup(contents) = hover.contents else {
+            panic!("Expected markup");
+        };
+        assert!(contents.value.starts_with("**Chapter**\n\n4 words"));
+
+        let code_lenses: Vec<CodeLens> = serde_json::from_value(client.request::<CodeLensRequest>(
+            serde_json::from_value(json!({"textDocument": document})).unwrap(),
+        ))
+        .unwrap();
+        assert_eq!(code_lenses.len(), 2);
+        assert_eq!(code_lenses[0].command.as_ref().unwrap().title, "8 words");
+
+        client.request::<Shutdown>(());
+        client.notify::<Exit>(());
+        server.join().unwrap().unwrap();
+    }
+
+    #[test]
+    fn should_count_includes_without_locating_them() {
+        let (server_connection, client_connection) = Connection::memory();
+        let server = std::thread::spawn(move || run(&server_connection));
+        let mut client = Client {
+            connection: client_connection,
+            next_id: 0,
+        };
+        client.request::<Initialize>(serde_json::from_value(json!({"capabilities": {}})).unwrap());
+        client.notify::<Initialized>(lsp_types::InitializedParams {});
+
+        let path = get_fixture_folder().join("includes").join("handbook.md");
+        let uri = Url::from_file_path(&path).unwrap();
+        client.notify::<DidOpenTextDocument>(DidOpenTextDocumentParams {
+            text_document: TextDocumentItem {
+                uri: uri.clone(),
+                language_id: "markdown".to_string(),
+                version: 1,
+                text: std::fs::read_to_string(&path).unwrap(),
+            },
+        });
+        client.connection.receiver.recv().unwrap();
+
+        // the included sections are in other files, but their words count in their sections
+        let document = TextDocumentIdentifier { uri };
+        let symbols: DocumentSymbolResponse =
+            serde_json::from_value(client.request::<DocumentSymbolRequest>(
+                serde_json::from_value(json!({"textDocument": document})).unwrap(),
+            ))
+            .unwrap();
+        let DocumentSymbolResponse::Nested(symbols) = symbols else {
+            panic!("Expected nested symbols");
+        };
+        let chapters = symbols[0].children.as_ref().unwrap();
+        assert_eq!(
+            chapters
+                .iter()
+                .map(|symbol| (symbol.name.as_str(), symbol.detail.as_deref()))
+                .collect::<Vec<(&str, Option<&str>)>>(),
+            vec![
+                ("Onboarding", Some("11 words")),
+                ("Support", Some("10 words"))
+            ]
+        );
+        assert!(chapters
+            .iter()
+            .all(|symbol| symbol.children.as_ref().unwrap().is_empty()));
+
+        let code_lenses: Vec<CodeLens> = serde_json::from_value(client.request::<CodeLensRequest>(
+            serde_json::from_value(json!({"textDocument": document})).unwrap(),
+        ))
+        .unwrap();
+        assert_eq!(code_lenses.len(), 3);
+
+        client.request::<Shutdown>(());
+        client.notify::<Exit>(());
+        server.join().unwrap().unwrap();
+    }
+}
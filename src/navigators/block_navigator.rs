@@ -0,0 +1,101 @@
+use crate::entities::core::block::Block;
+use crate::entities::core::freeout::Freeout;
+
+/// A position in the source text.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Cursor {
+    /// Byte offset.
+    Offset(usize),
+    /// 0-indexed line, and column in the `position_encoding` of the options.
+    LineColumn(usize, usize),
+}
+
+impl From<usize> for Cursor {
+    fn from(offset: usize) -> Self {
+        Cursor::Offset(offset)
+    }
+}
+
+impl Freeout {
+    /// Get the innermost block whose `block_range` contains a position. Text before the first header has no block.
+    pub fn block_at(&self, cursor: impl Into<Cursor>) -> Option<&Block> {
+        let offset = match cursor.into() {
+            Cursor::Offset(offset) => offset,
+            Cursor::LineColumn(line, column) => self.get_offset(line, column),
+        };
+
+        let entry = self.index.get_last_started(offset)?;
+        let mut block = self.blocks.get(&entry.block_id)?;
+        // the last section started is either the innermost one, or it ended before the offset, and one of its
+        // ancestors contains it
+        while self.index.get(block.id)?.end < offset {
+            block = self.blocks.get(&block.parent_id?)?;
+        }
+        Some(block)
+    }
+
+    /// Get the ancestors of a block and the block itself, from the root, e.g. `Chapter 3 › Methods › Sampling`.
+    pub fn breadcrumbs(&self, block_id: usize) -> Vec<&Block> {
+        let mut breadcrumbs = vec![];
+        let mut next_id = Some(block_id);
+        while let Some(block) = next_id.and_then(|id| self.blocks.get(&id)) {
+            breadcrumbs.push(block);
+            next_id = block.parent_id;
+        }
+        breadcrumbs.reverse();
+        breadcrumbs
+    }
+
+    /// Get the block after a block, in document order.
+    pub fn next_block(&self, block_id: usize) -> Option<&Block> {
+        self.blocks.get(&self.index.get_next(block_id)?.block_id)
+    }
+
+    /// Get the block before a block, in document order.
+    pub fn previous_block(&self, block_id: usize) -> Option<&Block> {
+        self.blocks
+            .get(&self.index.get_previous(block_id)?.block_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::core::freeout::Freeout;
+    use crate::navigators::block_navigator::Cursor;
+    use crate::readers::markdown::MarkdownReader;
+
+    const MARKDOWN: &str =
+        "Preface\n\n# Chapter 3\n\n## Methods\n\n### Sampling\n\nOne two\n\n## Results\n\nThree\n";
+
+    #[test]
+    fn should_find_blocks_at_positions() {
+        let mut freeout = Freeout::new(MARKDOWN.to_string(), None);
+        freeout.outline(&MarkdownReader::default()).unwrap();
+        let title_at = |cursor: Cursor| freeout.block_at(cursor).map(|block| block.title.as_str());
+
+        assert_eq!(title_at(Cursor::Offset(0)), None);
+        assert_eq!(
+            title_at(MARKDOWN.find("One").unwrap().into()),
+            Some("Sampling")
+        );
+        assert_eq!(title_at(Cursor::LineColumn(4, 3)), Some("Methods"));
+        assert_eq!(title_at(Cursor::LineColumn(12, 0)), Some("Results"));
+        assert_eq!(title_at(MARKDOWN.len().into()), Some("Results"));
+
+        let sampling = freeout.block_at(Cursor::LineColumn(8, 0)).unwrap();
+        let breadcrumbs: Vec<&str> = freeout
+            .breadcrumbs(sampling.id)
+            .iter()
+            .map(|block| block.title.as_str())
+            .collect();
+        assert_eq!(breadcrumbs.join(" › "), "Chapter 3 › Methods › Sampling");
+
+        assert_eq!(freeout.next_block(sampling.id).unwrap().title, "Results");
+        assert_eq!(
+            freeout.previous_block(sampling.id).unwrap().title,
+            "Methods"
+        );
+        assert!(freeout.previous_block(1).is_none());
+        assert!(freeout.next_block(4).is_none());
+    }
+}
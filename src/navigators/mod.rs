@@ -0,0 +1,7 @@
+//! # Navigators
+//!
+//! Lookups of the blocks of an outlined `Freeout` by position, e.g. to show where the cursor of an editor is, or to
+//! jump between sections.
+//!
+//! Lookups go through the `BlockIndex` built at the end of `Freeout::outline`, so they do not scan the blocks.
+pub mod block_navigator;
@@ -0,0 +1,128 @@
+use std::path::{Path, PathBuf};
+
+use eyre::{eyre, Report};
+use rayon::prelude::*;
+
+use crate::entities::core::freeout::{Freeout, FreeoutOptions};
+use crate::entities::reader::reader_trait::ReaderTrait;
+use crate::readers::get_reader_by_extension;
+
+/// The outline of a file of a batch, or the error that prevented it.
+pub struct FileOutline {
+    pub path: PathBuf,
+    pub result: Result<Freeout, Report>,
+}
+
+/// # Outline sources in parallel
+///
+/// Every source is read with the same reader. The results are in the order of the sources, and the outline of each
+/// is borrowed with `Freeout::get_outline`.
+pub fn outline_sources<R>(
+    sources: Vec<String>,
+    reader: &R,
+    options: &FreeoutOptions,
+) -> Vec<Result<Freeout, Report>>
+where
+    R: ReaderTrait + Sync + ?Sized,
+{
+    sources
+        .into_par_iter()
+        .map(|source| {
+            let mut freeout = Freeout::new(source, Some(options.clone()));
+            freeout.process(reader)?;
+            Ok(freeout)
+        })
+        .collect()
+}
+
+/// # Outline files in parallel
+///
+/// Every file is read with the reader of its extension. A file that cannot be read or outlined gets an error, and
+/// the others are still outlined. The results are in the order of the paths.
+pub fn outline_files(paths: &[PathBuf], options: &FreeoutOptions) -> Vec<FileOutline> {
+    paths
+        .par_iter()
+        .map(|path| FileOutline {
+            path: path.clone(),
+            result: process_file(path, options),
+        })
+        .collect()
+}
+
+/// Process the blocks of a file with the reader of its extension.
+pub fn process_file(path: &Path, options: &FreeoutOptions) -> Result<Freeout, Report> {
+    let reader = path
+        .extension()
+        .and_then(|extension| get_reader_by_extension(&extension.to_string_lossy()))
+        .ok_or_else(|| eyre!("No reader for {}", path.display()))?;
+    process_file_with_reader(path, reader.as_ref(), options)
+}
+
+/// Process the blocks of a file with the given reader, whatever its extension.
+pub fn process_file_with_reader<R>(
+    path: &Path,
+    reader: &R,
+    options: &FreeoutOptions,
+) -> Result<Freeout, Report>
+where
+    R: ReaderTrait + ?Sized,
+{
+    let source = std::fs::read_to_string(path)
+        .map_err(|err| eyre!("Failed to read {}: {}", path.display(), err))?;
+
+    let mut freeout = Freeout::new(source, Some(options.clone())).with_path(path.to_path_buf());
+    freeout
+        .process(reader)
+        .map_err(|err| eyre!("{}: {}", path.display(), err))?;
+    Ok(freeout)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::core::freeout::FreeoutOptions;
+    use crate::outliners::batch_outliner::{outline_files, outline_sources};
+    use crate::readers::markdown::MarkdownReader;
+    use crate::utils::test_utils::test_utils::{get_fixture_folder, md_get_long_chinese};
+
+    #[test]
+    fn should_outline_batches_in_order() {
+        let sources: Vec<String> = (1..=50)
+            .map(|i| format!("# Document {}\n\n{}", i, "word ".repeat(i)))
+            .collect();
+        let outlines = outline_sources(
+            sources,
+            &MarkdownReader::default(),
+            &FreeoutOptions::default(),
+        );
+
+        assert_eq!(outlines.len(), 50);
+        for (i, result) in (1..).zip(&outlines) {
+            let freeout = result.as_ref().unwrap();
+            assert_eq!(
+                freeout.get_outline().items[0].block.title,
+                format!("Document {}", i)
+            );
+            assert_eq!(freeout.blocks.len(), 1);
+        }
+
+        let paths = vec![
+            md_get_long_chinese(),
+            get_fixture_folder().join("opml").join("book_plan.opml"),
+            get_fixture_folder().join("notes.txt"),
+        ];
+        let outlines = outline_files(&paths, &FreeoutOptions::default());
+        assert_eq!(
+            outlines
+                .iter()
+                .map(|outline| &outline.path)
+                .collect::<Vec<_>>(),
+            paths.iter().collect::<Vec<_>>()
+        );
+        assert!(outlines[0].result.is_ok());
+        assert!(outlines[1].result.is_ok());
+        assert!(outlines[2]
+            .result
+            .as_ref()
+            .is_err_and(|err| err.to_string().starts_with("No reader for")));
+    }
+}
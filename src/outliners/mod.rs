@@ -0,0 +1,7 @@
+//! # Outliners
+//!
+//! Outline many documents at once, e.g. every page of a documentation repository in CI.
+//!
+//! Documents are outlined in parallel on the global `rayon` thread pool, and the results keep the order of the
+//! inputs, so the output does not depend on the scheduling.
+pub mod batch_outliner;
@@ -1,8 +1,16 @@
-use crate::entities::core::block::Blocks;
+use rayon::prelude::*;
+
+use words_count::count;
+
+use crate::entities::core::block::Block;
 use crate::entities::core::freeout::Freeout;
-use crate::entities::core::words_statistics::{WordsStatus, WordsTarget};
+use crate::entities::core::words_statistics::WordCount;
 use crate::utils::block_utils::BlockUtils;
 
+/// Number of blocks from which their content is processed in parallel. Below it, spawning tasks costs more than
+/// hashing and counting the words of the blocks.
+const PARALLEL_BLOCK_COUNT: usize = 1000;
+
 impl Freeout {
     /// # Process Content of the block tree
     ///
@@ -14,23 +22,36 @@ impl Freeout {
     /// - Word count of each block
     ///
     /// For any of these fields that have been set by the Reader, the function will not overwrite them.
+    ///
+    /// The content is resolved from the `content_ranges` of the blocks as it is counted, without being kept.
     pub(crate) fn process_content(&mut self) {
         // populate range
-        BlockUtils::populate_block_ranges(&mut self.blocks, &self.text);
+        BlockUtils::populate_block_ranges_in_tree(&mut self.blocks, &self.tree, &self.text);
         // after all blocks are generated, traverse again to hash all content and count words
-        for (_, block) in self.blocks.iter_mut() {
-            if block.content.is_none() {
-                continue;
-            }
-            if block.hash.is_none() && self.opt.include_content {
-                block.hash();
-            }
-            if block.self_stats.count.words == 0 {
-                block.self_stats.count(block.content.as_ref().unwrap());
+        let include_content = self.opt.include_content;
+        let source = &self.text;
+        // blocks are processed independently, so the result does not depend on the order
+        if self.blocks.len() >= PARALLEL_BLOCK_COUNT {
+            self.blocks
+                .par_iter_mut()
+                .for_each(|(_, block)| process_block_content(block, source, include_content));
+        } else {
+            for (_, block) in self.blocks.iter_mut() {
+                process_block_content(block, source, include_content);
             }
         }
-
-
     }
 }
 
+fn process_block_content(block: &mut Block, source: &str, include_content: bool) {
+    if block.content.is_none() && block.content_ranges.is_empty() {
+        return;
+    }
+    if block.hash.is_none() && include_content {
+        block.hash();
+    }
+    if block.self_stats.count.words == 0 {
+        let word_count = count(block.get_content(source).unwrap_or_default());
+        block.self_stats.count = WordCount::from(word_count);
+    }
+}
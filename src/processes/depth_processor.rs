@@ -0,0 +1,48 @@
+use crate::entities::core::freeout::Freeout;
+
+impl Freeout {
+    /// # Process logical depths
+    ///
+    /// Sets the `logical_depth` of every block: 1 for root blocks, and the logical depth of the parent + 1 for the
+    /// others, whatever the depth of their markers.
+    pub(crate) fn process_logical_depths(&mut self) {
+        // settled by id over the tree, and then set in a single pass over the blocks
+        let mut logical_depths = vec![0; self.tree.len() + 1];
+        // Block ids follow the document order, so a parent is always settled before its children.
+        for block_id in 1..=self.tree.len() {
+            let parent_depth = self
+                .tree
+                .get(block_id)
+                .unwrap()
+                .parent
+                .map_or(0, |parent_id| logical_depths[parent_id]);
+            logical_depths[block_id] = parent_depth + 1;
+        }
+
+        for block in self.blocks.values_mut() {
+            block.logical_depth = logical_depths[block.id];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::core::freeout::Freeout;
+    use crate::readers::markdown::MarkdownReader;
+
+    #[test]
+    fn should_compute_logical_depths() {
+        let markdown =
+            "## Intro\n\n#### Scene\n\n## Part\n\n### Chapter\n\n###### Note\n\n# Appendix\n";
+        let mut freeout = Freeout::new(markdown.to_string(), None);
+        freeout.outline(&MarkdownReader::default()).unwrap();
+
+        let logical_depths: Vec<(usize, usize)> = (1..=freeout.blocks.len())
+            .map(|id| (freeout.blocks[&id].depth, freeout.blocks[&id].logical_depth))
+            .collect();
+        assert_eq!(
+            logical_depths,
+            vec![(2, 1), (4, 2), (2, 1), (3, 2), (6, 3), (1, 1)]
+        );
+    }
+}
@@ -0,0 +1,28 @@
+use crate::entities::core::freeout::Freeout;
+
+impl Freeout {
+    /// # Apply exclusion rules
+    ///
+    /// Marks the blocks whose titles match the title patterns of `FreeoutOptions::exclusion` as excluded, and then
+    /// propagates `exclude` from every excluded block to its whole subtree.
+    ///
+    /// Blocks already excluded by the reader, e.g. through a heading attribute, stay excluded.
+    pub(crate) fn apply_exclusion_rules(&mut self) {
+        // settled by id over the tree, and then set in a single pass over the blocks
+        let mut excluded = vec![false; self.tree.len() + 1];
+        for block in self.blocks.values() {
+            excluded[block.id] = block.exclude || self.opt.exclusion.matches_title(&block.title);
+        }
+
+        // Block ids follow the document order, so a parent is always settled before its children.
+        for block_id in 1..=self.tree.len() {
+            if let Some(parent_id) = self.tree.get(block_id).unwrap().parent {
+                excluded[block_id] |= excluded[parent_id];
+            }
+        }
+
+        for block in self.blocks.values_mut() {
+            block.exclude = excluded[block.id];
+        }
+    }
+}
@@ -0,0 +1,251 @@
+use std::path::{Path, PathBuf};
+
+use eyre::{eyre, Report};
+use itertools::Itertools;
+use tracing::warn;
+
+use crate::entities::core::block::{Block, Blocks};
+use crate::entities::core::block_tree::BlockTree;
+use crate::entities::core::freeout::Freeout;
+use crate::entities::reader::reader_trait::ReaderTrait;
+use crate::readers::get_reader_by_extension;
+use crate::utils::block_utils::BlockUtils;
+
+/// The processed blocks of an included file, and where they are included.
+struct IncludedFile {
+    offset: usize,
+    path: PathBuf,
+    blocks: Blocks,
+}
+
+impl Freeout {
+    /// # Process includes
+    ///
+    /// Splices the blocks of the files included by the `IncludeRules` of `FreeoutOptions` at the place of their
+    /// directives. Needs the path of the source, see `Freeout::with_path`.
+    ///
+    /// Included files are processed as a whole, their own includes included. Their blocks are placed under the
+    /// section of the directive, with their `depth` shifted by the depth of that section, and keep the positions of
+    /// their file in `header_range`, `block_range` and `content_ranges`, with the file in `source_path`. The text of
+    /// each included file is kept to resolve their content.
+    ///
+    /// Directives in the code ranges of the reader, e.g. a directive quoted in a code block, and directives of
+    /// missing files stay content.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an included file cannot be read or processed, or if a file includes itself, directly or
+    /// not.
+    pub(crate) fn process_includes<R>(&mut self, reader: &R) -> Result<(), Report>
+    where
+        R: ReaderTrait + ?Sized,
+    {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let mut includes = self.opt.include.find_includes(&self.text);
+        if includes.is_empty() {
+            return Ok(());
+        }
+        let code_ranges = reader.read_code_ranges(&self.text)?;
+        includes.retain(|include| {
+            !code_ranges
+                .iter()
+                .any(|code_range| code_range.contains(&include.offset))
+        });
+
+        let folder = path.parent().unwrap_or(Path::new(""));
+        let mut include_chain = self.include_chain.clone();
+        include_chain.push(get_canonical_path(path)?);
+
+        let mut included_files = vec![];
+        for include in includes {
+            let include_path = folder.join(&include.path);
+            // e.g. a code listing, which stays content
+            let Some(reader) = include_path
+                .extension()
+                .and_then(|extension| get_reader_by_extension(&extension.to_string_lossy()))
+            else {
+                continue;
+            };
+
+            if !include_path.is_file() {
+                warn!(
+                    "Skipping the include of a missing file: {}",
+                    include_path.display()
+                );
+                continue;
+            }
+
+            let canonical_path = get_canonical_path(&include_path)?;
+            if include_chain.contains(&canonical_path) {
+                return Err(eyre!(
+                    "Include cycle: {} -> {}",
+                    include_chain.iter().map(|path| path.display()).join(" -> "),
+                    canonical_path.display()
+                ));
+            }
+
+            let source = std::fs::read_to_string(&include_path)
+                .map_err(|err| eyre!("Failed to read {}: {}", include_path.display(), err))?;
+            let mut freeout =
+                Freeout::new(source, Some(self.opt.clone())).with_path(include_path.clone());
+            freeout.include_chain = include_chain.clone();
+            freeout
+                .process(reader.as_ref())
+                .map_err(|err| eyre!("{}: {}", include_path.display(), err))?;
+
+            // the content ranges of the included blocks are in the text of their own file
+            self.included_texts.extend(freeout.included_texts);
+            self.included_texts
+                .insert(include_path.clone(), freeout.text);
+            included_files.push(IncludedFile {
+                offset: include.offset,
+                path: include_path,
+                blocks: freeout.blocks,
+            });
+        }
+
+        self.splice_included_files(included_files);
+        self.tree = BlockTree::new(&self.blocks);
+        self.process_logical_depths();
+        // included blocks inherit the exclusion of their section
+        self.apply_exclusion_rules();
+        Ok(())
+    }
+
+    /// Rebuild the blocks in document order, with the blocks of each included file after the last block starting
+    /// before its directive.
+    fn splice_included_files(&mut self, included_files: Vec<IncludedFile>) {
+        let own_blocks: Vec<Block> = (1..=self.blocks.len())
+            .map(|block_id| self.blocks.remove(&block_id).unwrap())
+            .collect();
+        // ids of the own blocks in the rebuilt blocks, by their former id
+        let mut new_ids = vec![0; own_blocks.len() + 1];
+        let mut included_files = included_files.into_iter().peekable();
+        // the last block starting before the next directive, with its depth
+        let mut section: Option<(usize, usize)> = None;
+
+        for mut block in own_blocks {
+            while let Some(included_file) = included_files
+                .next_if(|included_file| included_file.offset < block.header_range.start.offset)
+            {
+                self.append_included_file(included_file, section);
+            }
+
+            let former_id = block.id;
+            let parent_id = block.parent_id.map(|parent_id| new_ids[parent_id]);
+            block.children_ids.clear();
+            new_ids[former_id] = BlockUtils::append_block(&mut self.blocks, block, parent_id);
+            section = Some((new_ids[former_id], self.blocks[&new_ids[former_id]].depth));
+        }
+        for included_file in included_files {
+            self.append_included_file(included_file, section);
+        }
+    }
+
+    fn append_included_file(
+        &mut self,
+        included_file: IncludedFile,
+        section: Option<(usize, usize)>,
+    ) {
+        let (section_id, section_depth) = section.unzip();
+        let first_block_id = self.blocks.len() + 1;
+
+        for block_id in 1..=included_file.blocks.len() {
+            let block = &included_file.blocks[&block_id];
+            let parent_id = block
+                .parent_id
+                .map(|parent_id| first_block_id + parent_id - 1)
+                .or(section_id);
+            let block = Block {
+                depth: block.depth + section_depth.unwrap_or(0),
+                // blocks included by the included file keep their own file
+                source_path: block
+                    .source_path
+                    .clone()
+                    .or(Some(included_file.path.clone())),
+                children_ids: vec![],
+                ..block.clone()
+            };
+            BlockUtils::append_block(&mut self.blocks, block, parent_id);
+        }
+    }
+}
+
+fn get_canonical_path(path: &Path) -> Result<PathBuf, Report> {
+    path.canonicalize()
+        .map_err(|err| eyre!("Failed to read {}: {}", path.display(), err))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::core::freeout::FreeoutOptions;
+    use crate::outliners::batch_outliner::process_file;
+    use crate::utils::test_utils::test_utils::get_fixture_folder;
+
+    #[test]
+    fn should_splice_included_files() {
+        let folder = get_fixture_folder().join("includes");
+        let freeout =
+            process_file(&folder.join("handbook.md"), &FreeoutOptions::default()).unwrap();
+
+        let blocks: Vec<(&str, usize, Option<usize>, usize)> = (1..=freeout.blocks.len())
+            .map(|id| &freeout.blocks[&id])
+            .map(|block| {
+                (
+                    block.title.as_str(),
+                    block.depth,
+                    block.parent_id,
+                    block.aggregate_stats.count.words,
+                )
+            })
+            .collect();
+        // the included code listing stays content
+        assert_eq!(
+            blocks,
+            vec![
+                ("Handbook", 1, None, 22),
+                ("Onboarding", 2, Some(1), 11),
+                ("Setup", 3, Some(2), 8),
+                ("Accounts", 4, Some(3), 4),
+                ("Support", 2, Some(1), 10),
+                ("FAQ", 3, Some(5), 3),
+            ]
+        );
+
+        let accounts = &freeout.blocks[&4];
+        assert_eq!(
+            accounts.source_path,
+            Some(folder.join("snippets").join("setup.md"))
+        );
+        assert_eq!(accounts.header_range.start.line, 4);
+        assert_eq!(accounts.logical_depth, 4);
+        // their content is resolved from their own file
+        assert_eq!(
+            freeout.get_content(4).as_deref(),
+            Some("Accounts\nRequest your accounts.")
+        );
+        assert_eq!(
+            freeout.get_own_content(3),
+            Some("\n\nInstall the tools.\n\n")
+        );
+
+        // quoted directives and missing files stay content
+        let freeout = process_file(&folder.join("quoted.md"), &FreeoutOptions::default()).unwrap();
+        let titles: Vec<&str> = (1..=freeout.blocks.len())
+            .map(|id| freeout.blocks[&id].title.as_str())
+            .collect();
+        assert_eq!(titles, vec!["Quoted", "Missing"]);
+
+        let err = process_file(&folder.join("cycle_a.md"), &FreeoutOptions::default())
+            .err()
+            .unwrap();
+        assert!(err.to_string().ends_with(&format!(
+            "Include cycle: {} -> {} -> {}",
+            folder.join("cycle_a.md").display(),
+            folder.join("cycle_b.md").display(),
+            folder.join("cycle_a.md").display()
+        )));
+    }
+}
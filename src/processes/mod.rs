@@ -28,9 +28,10 @@
 //!
 //! ## 4. Target Distribution (if applicable)
 //! * **Input**: Blocks with `self_stats` and `aggregate_stats`, and potentially some targets specified.
-//! * **Output**: `self_stats.target` filled out for all blocks.
+//! * **Output**: `aggregate_stats.target` filled out for the descendants of blocks with a target.
 //! * **Description**: Distribute the root's target (if given) among its children based on a specified logic
-//!   (e.g., proportionally or equally). This is propagated down the hierarchy to all children.
+//!   (e.g., proportionally or equally). This is propagated down the hierarchy to all children. A target in
+//!   `aggregate_stats` is the target of the whole section, while one in `self_stats` covers only its own content.
 //!
 //! ## 5. Status Calculation
 //! * **Input**: Blocks with populated `self_stats` and `aggregate_stats`.
@@ -44,6 +45,34 @@
 //! * **Description**: Perform any additional operations, such as filtering out excluded blocks,
 //!   re-indexing, or other necessary transformations.
 //!
+//! ## Exclusion
+//! Before step 3, blocks matching the `ExclusionRules` of `FreeoutOptions` are marked as `exclude`, together with
+//! their whole subtree. Excluded blocks stay in the outline, but they are skipped in steps 3 and 4.
+//!
+//! ## Block Tree
+//! Right after the blocks are validated, the links between them are built once into a `BlockTree`, which the
+//! following steps and the editors use for tree queries. It is built again when included blocks are spliced in.
+//!
+//! ## Logical Depth
+//! Right after the blocks are validated, each block gets a `logical_depth` from its parent, so that documents with
+//! skipped heading levels, e.g. `#` followed by `###`, still have a regular tree.
+//!
+//! ## Positions
+//! Readers report positions in their own units, e.g. the Markdown reader reports 1-indexed lines and columns. As a
+//! last step, every position in the blocks is computed again from its byte offset, with a 0-indexed line and a
+//! column in the `PositionEncoding` of `FreeoutOptions`. Lines and columns were reported as the reader gave them
+//! before, so the lines of Markdown blocks are now one less.
+//!
+//! ## Includes
+//! When the source has a path, the files of its include directives, e.g. `{{#include snippet.md}}`, are processed on
+//! their own, and their blocks are spliced under the section of each directive, right after the positions and before
+//! the statistics. See `IncludeRules`.
+//!
 //! These steps are modular and encapsulated, allowing each to be understood, tested, and potentially
 //! parallelized independently.
 pub mod content_processor;
+pub mod depth_processor;
+pub mod exclusion_processor;
+pub mod include_processor;
+pub mod position_processor;
+pub mod stats_processor;
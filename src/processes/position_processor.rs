@@ -0,0 +1,109 @@
+use crate::entities::core::block_range::BlockRange;
+use crate::entities::core::freeout::Freeout;
+
+impl Freeout {
+    /// # Process positions
+    ///
+    /// Computes the `line` and `column` of every position in the blocks again from its offset, so that they are all
+    /// 0-indexed and in the `position_encoding` of the options, whatever the reader reported.
+    pub(crate) fn process_positions(&mut self) {
+        // the positions only depend on the text, so the blocks are taken out to be updated in place
+        let mut blocks = std::mem::take(&mut self.blocks);
+        for block in blocks.values_mut() {
+            block.header_range = self.get_encoded_range(&block.header_range);
+            block.block_range = block
+                .block_range
+                .as_ref()
+                .map(|range| self.get_encoded_range(range));
+        }
+        self.blocks = blocks;
+    }
+
+    fn get_encoded_range(&self, range: &BlockRange) -> BlockRange {
+        BlockRange {
+            start: self.get_encoded_position(range.start.offset),
+            end: self.get_encoded_position(range.end.offset),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::core::freeout::{Freeout, FreeoutOptions};
+    use crate::entities::core::position::Position;
+    use crate::entities::core::position_encoding::PositionEncoding;
+    use crate::readers::markdown::MarkdownReader;
+    use crate::utils::test_utils::test_utils::md_get_long_chinese;
+
+    fn outline(text: &str, position_encoding: PositionEncoding) -> Freeout {
+        let mut freeout = Freeout::new(
+            text.to_string(),
+            Some(FreeoutOptions {
+                position_encoding,
+                ..Default::default()
+            }),
+        );
+        freeout.outline(&MarkdownReader::default()).unwrap();
+        freeout
+    }
+
+    #[test]
+    fn should_encode_positions_of_chinese_document() {
+        let text = std::fs::read_to_string(md_get_long_chinese()).unwrap();
+        let title = "# 大标题";
+        let line = text.lines().position(|line| line == title).unwrap();
+
+        for (encoding, column) in [
+            (PositionEncoding::Utf8, title.len()),
+            (PositionEncoding::Utf16, 5),
+            (PositionEncoding::Grapheme, 5),
+        ] {
+            let freeout = outline(&text, encoding);
+            let block = &freeout.blocks[&1];
+            assert_eq!(block.title, "大标题");
+            assert_eq!(
+                block.header_range.end,
+                Position {
+                    line,
+                    column,
+                    offset: text.find(title).unwrap() + title.len(),
+                }
+            );
+
+            // every position converts back to its offset
+            for block in freeout.blocks.values() {
+                let range = block.block_range.as_ref().unwrap();
+                for position in [
+                    &block.header_range.start,
+                    &block.header_range.end,
+                    &range.end,
+                ] {
+                    assert_eq!(
+                        freeout.get_offset(position.line, position.column),
+                        position.offset
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn should_encode_positions_after_emoji() {
+        // "😀" is 4 bytes and 2 UTF-16 units, "👍🏽" is 8 bytes, 4 UTF-16 units and 1 grapheme
+        let text = "# 😀 Intro\n\n## 👍🏽 Done\n\nok\n";
+
+        let ends: Vec<(usize, usize)> = [
+            PositionEncoding::Utf8,
+            PositionEncoding::Utf16,
+            PositionEncoding::Grapheme,
+        ]
+        .into_iter()
+        .map(|encoding| {
+            let end = &outline(text, encoding).blocks[&2].header_range.end;
+            (end.line, end.column)
+        })
+        .collect();
+
+        assert_eq!(ends, vec![(2, 16), (2, 12), (2, 9)]);
+    }
+}
@@ -0,0 +1,322 @@
+use std::collections::BTreeSet;
+
+use crate::entities::core::block::Block;
+use crate::entities::core::freeout::Freeout;
+use crate::entities::core::words_statistics::{
+    DistributionMethod, WordCount, WordsStatus, WordsTarget,
+};
+use crate::entities::visitor::visitor_trait::{VisitContext, VisitFlow, VisitorMut};
+use crate::utils::visit_utils::VisitUtils;
+
+impl Freeout {
+    /// # Process statistics of the block tree
+    ///
+    /// This function is called after `process_content` and the exclusion rules have been applied.
+    ///
+    /// It generates the following information:
+    /// - `aggregate_stats.count` of each block
+    /// - `aggregate_stats.target` of the descendants of blocks with a distributable target
+    /// - `status` of each block with a target
+    ///
+    /// Excluded blocks do not count toward the `aggregate_stats` of their non-excluded parents, and do not receive a share of their targets.
+    pub(crate) fn process_stats(&mut self) {
+        self.aggregate_stats();
+        self.distribute_targets();
+        self.compute_status();
+    }
+
+    /// Whether the child takes part in the statistics of the parent.
+    ///
+    /// An excluded child is only counted if the parent is excluded as well, so that excluded subtrees still have their own aggregate statistics.
+    fn is_counted_in_parent(parent_exclude: bool, child: &Block) -> bool {
+        !child.exclude || parent_exclude
+    }
+
+    /// Sum up the `self_stats` of each block and the `aggregate_stats` of its children.
+    fn aggregate_stats(&mut self) {
+        VisitUtils::walk_blocks_mut(&mut self.blocks, &mut StatsAggregator::default());
+    }
+
+    /// Distribute the `aggregate_stats.target` of each block among its children without a target of their own.
+    ///
+    /// Targets distributed by a previous run are distributed again, e.g. after the target of their parent changed.
+    fn distribute_targets(&mut self) {
+        for block in self.blocks.values_mut() {
+            if block
+                .aggregate_stats
+                .target
+                .as_ref()
+                .is_some_and(|target| target.distributed)
+            {
+                block.aggregate_stats.target = None;
+            }
+        }
+
+        // Only the blocks with a distributable target are visited, parents before children, so distributed targets
+        // are distributed further down.
+        let mut block_ids: BTreeSet<usize> = self
+            .blocks
+            .values()
+            .filter(|block| is_distributable(&block.aggregate_stats.target))
+            .map(|block| block.id)
+            .collect();
+        while let Some(block_id) = block_ids.pop_first() {
+            let block = &self.blocks[&block_id];
+            let target = block.aggregate_stats.target.clone().unwrap();
+
+            let mut targeted_words = 0;
+            let mut untargeted_children_ids = vec![];
+            for child_id in &block.children_ids {
+                let child = &self.blocks[child_id];
+                if !Self::is_counted_in_parent(block.exclude, child) {
+                    continue;
+                }
+                match &child.aggregate_stats.target {
+                    Some(child_target) => targeted_words += child_target.words,
+                    None => untargeted_children_ids.push(*child_id),
+                }
+            }
+
+            if untargeted_children_ids.is_empty() {
+                continue;
+            }
+
+            let remaining_words = target.words.saturating_sub(targeted_words);
+            let share = remaining_words / untargeted_children_ids.len();
+            let remainder = remaining_words % untargeted_children_ids.len();
+
+            for (index, child_id) in untargeted_children_ids.iter().enumerate() {
+                let words = if index < remainder { share + 1 } else { share };
+                let child = self.blocks.get_mut(child_id).unwrap();
+                child.aggregate_stats.target = Some(WordsTarget {
+                    words,
+                    distribution: target.distribution.clone(),
+                    distributed: true,
+                });
+                block_ids.insert(*child_id);
+            }
+        }
+    }
+
+    /// Compute the status of each block with a target.
+    ///
+    /// The `adjusted_target` of a block is the target of its parent, minus the words already used by its preceding siblings, divided among it and its following siblings.
+    fn compute_status(&mut self) {
+        // statuses are computed before any block is updated, as they read the statistics of the siblings
+        let statuses: Vec<(usize, Option<WordsStatus>, Option<WordsStatus>)> = self
+            .blocks
+            .values()
+            .filter(|block| {
+                block.self_stats.target.is_some() || block.aggregate_stats.target.is_some()
+            })
+            .map(|block| {
+                let self_stats = &block.self_stats;
+                let self_status = self_stats.target.as_ref().map(|target| WordsStatus {
+                    balance: get_balance(self_stats.count.words, target.words),
+                    adjusted_target: None,
+                });
+
+                let aggregate_stats = &block.aggregate_stats;
+                let aggregate_status = aggregate_stats.target.as_ref().map(|target| WordsStatus {
+                    balance: get_balance(aggregate_stats.count.words, target.words),
+                    adjusted_target: self.get_adjusted_target(block),
+                });
+
+                (block.id, self_status, aggregate_status)
+            })
+            .collect();
+
+        // blocks without a target have no status
+        for block in self.blocks.values_mut() {
+            block.self_stats.status = None;
+            block.aggregate_stats.status = None;
+        }
+        for (block_id, self_status, aggregate_status) in statuses {
+            let block = self.blocks.get_mut(&block_id).unwrap();
+            block.self_stats.status = self_status;
+            block.aggregate_stats.status = aggregate_status;
+        }
+    }
+
+    fn get_adjusted_target(&self, block: &Block) -> Option<usize> {
+        let parent = self.blocks.get(&block.parent_id?)?;
+        let parent_target = parent.aggregate_stats.target.as_ref()?;
+
+        if !Self::is_counted_in_parent(parent.exclude, block) {
+            return None;
+        }
+
+        let siblings = parent
+            .children_ids
+            .iter()
+            .map(|sibling_id| &self.blocks[sibling_id])
+            .filter(|sibling| Self::is_counted_in_parent(parent.exclude, sibling));
+
+        let mut used_words = 0;
+        let mut remaining_siblings = 0;
+        for sibling in siblings {
+            if sibling.id < block.id {
+                used_words += sibling.aggregate_stats.count.words;
+            } else {
+                remaining_siblings += 1;
+            }
+        }
+
+        Some(parent_target.words.saturating_sub(used_words) / remaining_siblings)
+    }
+}
+
+/// Sums up the counts of the blocks on the way back up the tree: children are exited before their parent.
+#[derive(Default)]
+struct StatsAggregator {
+    /// Whether each entered block is excluded, with the sum of its own count and the counts of its children so far.
+    stack: Vec<(bool, WordCount)>,
+}
+
+impl VisitorMut for StatsAggregator {
+    fn enter_block(&mut self, block: &mut Block, _context: &VisitContext) -> VisitFlow {
+        self.stack
+            .push((block.exclude, block.self_stats.count.clone()));
+        VisitFlow::Continue
+    }
+
+    fn exit_block(&mut self, block: &mut Block, _context: &VisitContext) {
+        let (_, count) = self.stack.pop().unwrap();
+        if let Some((parent_exclude, parent_count)) = self.stack.last_mut() {
+            if Freeout::is_counted_in_parent(*parent_exclude, block) {
+                *parent_count += &count;
+            }
+        }
+        block.aggregate_stats.count = count;
+    }
+}
+
+fn is_distributable(target: &Option<WordsTarget>) -> bool {
+    target
+        .as_ref()
+        .is_some_and(|target| matches!(target.distribution, Some(DistributionMethod::Uniform)))
+}
+
+fn get_balance(words: usize, target_words: usize) -> isize {
+    words as isize - target_words as isize
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::core::block::Block;
+    use crate::entities::core::block_tree::BlockTree;
+    use crate::entities::core::freeout::Freeout;
+    use crate::entities::core::words_statistics::{DistributionMethod, WordCount, WordsTarget};
+
+    /// 1 Book
+    ///   2 Chapter 1
+    ///   3 Chapter 2
+    ///     4 Section
+    ///   5 Notes (excluded)
+    ///     6 Draft
+    fn get_test_freeout() -> Freeout {
+        let mut freeout = Freeout::new("fake".to_string(), None);
+
+        let blocks = vec![
+            (1, 1, None, vec![2, 3, 5], 10, false),
+            (2, 2, Some(1), vec![], 100, false),
+            (3, 2, Some(1), vec![4], 20, false),
+            (4, 3, Some(3), vec![], 30, false),
+            (5, 2, Some(1), vec![6], 1000, true),
+            (6, 3, Some(5), vec![], 500, false),
+        ];
+
+        for (id, depth, parent_id, children_ids, words, exclude) in blocks {
+            let mut block = Block {
+                id,
+                depth,
+                parent_id,
+                children_ids,
+                exclude,
+                ..Default::default()
+            };
+            block.self_stats.count = WordCount {
+                words,
+                characters: words * 5,
+            };
+            freeout.blocks.insert(id, block);
+        }
+        freeout.tree = BlockTree::new(&freeout.blocks);
+
+        freeout
+    }
+
+    #[test]
+    fn should_excluded_blocks_not_count_toward_parents() {
+        let mut freeout = get_test_freeout();
+
+        freeout.apply_exclusion_rules();
+        freeout.process_stats();
+
+        // the draft under the notes is excluded with its parent
+        assert!(freeout.blocks[&6].exclude);
+
+        assert_eq!(freeout.blocks[&3].aggregate_stats.count.words, 50);
+        assert_eq!(freeout.blocks[&1].aggregate_stats.count.words, 160);
+        assert_eq!(freeout.blocks[&1].aggregate_stats.count.characters, 800);
+
+        // excluded subtrees still have their own aggregate statistics
+        assert_eq!(freeout.blocks[&5].aggregate_stats.count.words, 1500);
+    }
+
+    #[test]
+    fn should_distribute_targets_to_non_excluded_children() {
+        let mut freeout = get_test_freeout();
+
+        freeout.blocks.get_mut(&1).unwrap().aggregate_stats.target = Some(WordsTarget {
+            words: 301,
+            distribution: Some(DistributionMethod::Uniform),
+            distributed: false,
+        });
+
+        freeout.apply_exclusion_rules();
+        freeout.process_stats();
+
+        let chapter_1 = &freeout.blocks[&2];
+        let chapter_2 = &freeout.blocks[&3];
+
+        assert_eq!(
+            chapter_1.aggregate_stats.target.as_ref().unwrap().words,
+            151
+        );
+        assert_eq!(
+            chapter_2.aggregate_stats.target.as_ref().unwrap().words,
+            150
+        );
+        // distributed further down
+        assert_eq!(
+            freeout.blocks[&4]
+                .aggregate_stats
+                .target
+                .as_ref()
+                .unwrap()
+                .words,
+            150
+        );
+        assert!(freeout.blocks[&5].aggregate_stats.target.is_none());
+
+        let chapter_1_status = chapter_1.aggregate_stats.status.as_ref().unwrap();
+        assert_eq!(chapter_1_status.balance, -51);
+        assert_eq!(chapter_1_status.adjusted_target, Some(150));
+
+        // chapter 1 used 100 words, leaving 201 words for chapter 2 alone
+        let chapter_2_status = chapter_2.aggregate_stats.status.as_ref().unwrap();
+        assert_eq!(chapter_2_status.balance, -100);
+        assert_eq!(chapter_2_status.adjusted_target, Some(201));
+
+        assert_eq!(
+            freeout.blocks[&1]
+                .aggregate_stats
+                .status
+                .as_ref()
+                .unwrap()
+                .balance,
+            160 - 301
+        );
+    }
+}
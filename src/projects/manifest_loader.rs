@@ -0,0 +1,343 @@
+use std::cmp::Ordering;
+use std::path::{Path, PathBuf};
+
+use eyre::{eyre, Report};
+
+use crate::entities::project::manifest::{ManifestEntry, ProjectManifest, WalkOrder};
+use crate::readers::get_reader_by_extension;
+
+/// Names of the files standing for their folder in a directory walk, e.g. `chapter_1/README.md`.
+const INDEX_FILE_STEMS: [&str; 2] = ["readme", "index"];
+
+impl ProjectManifest {
+    /// # Load the entries of the manifest
+    ///
+    /// The paths of the entries are resolved against the folder of the manifest.
+    pub fn load(&self) -> Result<Vec<ManifestEntry>, Report> {
+        match self {
+            ProjectManifest::Summary(path) => {
+                let summary = std::fs::read_to_string(path)
+                    .map_err(|err| eyre!("Failed to read {}: {}", path.display(), err))?;
+                Ok(parse_summary(
+                    &summary,
+                    path.parent().unwrap_or(Path::new("")),
+                ))
+            }
+            ProjectManifest::Files(paths) => Ok(paths
+                .iter()
+                .map(|path| ManifestEntry {
+                    title: get_file_title(path),
+                    path: Some(path.clone()),
+                    children: vec![],
+                })
+                .collect()),
+            ProjectManifest::Directory { root, order } => {
+                let mut entries = walk_directory(root, *order)?;
+                // the index of the root folder is its first file
+                if let Some(index_path) = find_index_file(root, *order)? {
+                    entries.insert(
+                        0,
+                        ManifestEntry {
+                            title: get_file_title(&index_path),
+                            path: Some(index_path),
+                            children: vec![],
+                        },
+                    );
+                }
+                Ok(entries)
+            }
+        }
+    }
+}
+
+/// # Parse an mdBook `SUMMARY.md`
+///
+/// - The first heading is the title of the summary, and is skipped.
+/// - The next headings are part titles, grouping the numbered chapters that follow them.
+/// - Links outside of a list are prefix or suffix chapters, at the top level.
+/// - List items are numbered chapters, nested by indentation. A link without a target is a draft chapter.
+/// - Separators, i.e. `---`, are skipped.
+pub fn parse_summary(summary: &str, folder: &Path) -> Vec<ManifestEntry> {
+    // entries with their nesting level, parts and top-level links being at level 0
+    let mut items = vec![];
+    let mut has_title = false;
+    let mut is_in_part = false;
+
+    for line in summary.lines() {
+        let content = line.trim_start();
+        let indent: usize = line[..line.len() - content.len()]
+            .chars()
+            .map(|c| if c == '\t' { 4 } else { 1 })
+            .sum();
+
+        if let Some(title) = content.strip_prefix('#') {
+            let title = title.trim_start_matches('#').trim();
+            if has_title || !items.is_empty() {
+                items.push((0, get_entry(title, None, folder)));
+                is_in_part = true;
+            }
+            has_title = true;
+        } else if let Some(item) = content
+            .strip_prefix("- ")
+            .or_else(|| content.strip_prefix("* "))
+        {
+            if let Some((title, target)) = parse_link(item) {
+                let level = indent + usize::from(is_in_part) + 1;
+                items.push((level, get_entry(title, Some(target), folder)));
+            }
+        } else if let Some((title, target)) = parse_link(content) {
+            items.push((0, get_entry(title, Some(target), folder)));
+            is_in_part = false;
+        }
+    }
+
+    nest_entries(items)
+}
+
+fn get_entry(title: &str, target: Option<&str>, folder: &Path) -> ManifestEntry {
+    ManifestEntry {
+        title: title.to_string(),
+        path: target
+            .filter(|target| !target.is_empty())
+            .map(|target| folder.join(target.replace("%20", " "))),
+        children: vec![],
+    }
+}
+
+/// Parse a Markdown link, e.g. `[Title](path.md)`, into its text and its target.
+fn parse_link(text: &str) -> Option<(&str, &str)> {
+    let rest = text.trim().strip_prefix('[')?;
+    let (title, rest) = rest.split_once("](")?;
+    let (target, _) = rest.split_once(')')?;
+    Some((title.trim(), target.trim()))
+}
+
+/// Nest the entries under the closest previous entry of a lower level.
+fn nest_entries(items: Vec<(usize, ManifestEntry)>) -> Vec<ManifestEntry> {
+    fn close_last(open_entries: &mut Vec<(usize, ManifestEntry)>, roots: &mut Vec<ManifestEntry>) {
+        let (_, entry) = open_entries.pop().unwrap();
+        match open_entries.last_mut() {
+            Some((_, parent)) => parent.children.push(entry),
+            None => roots.push(entry),
+        }
+    }
+
+    let mut roots = vec![];
+    let mut open_entries: Vec<(usize, ManifestEntry)> = vec![];
+    for (level, entry) in items {
+        while open_entries
+            .last()
+            .is_some_and(|(open_level, _)| *open_level >= level)
+        {
+            close_last(&mut open_entries, &mut roots);
+        }
+        open_entries.push((level, entry));
+    }
+    while !open_entries.is_empty() {
+        close_last(&mut open_entries, &mut roots);
+    }
+    roots
+}
+
+/// Get the entries of the files and sub-folders of a folder. A sub-folder without any file to outline is skipped.
+///
+/// Symlinked sub-folders are skipped too, as they may point back up the tree.
+fn walk_directory(folder: &Path, order: WalkOrder) -> Result<Vec<ManifestEntry>, Report> {
+    let mut entries = vec![];
+    for path in read_sorted_directory(folder, order)? {
+        if path.is_symlink() && path.is_dir() {
+            continue;
+        }
+        if path.is_dir() {
+            let children = walk_directory(&path, order)?;
+            let index_path = find_index_file(&path, order)?;
+            if children.is_empty() && index_path.is_none() {
+                continue;
+            }
+            entries.push(ManifestEntry {
+                title: get_file_title(&path),
+                path: index_path,
+                children,
+            });
+        } else if is_outlinable(&path) && !is_index_file(&path) {
+            entries.push(ManifestEntry {
+                title: get_file_title(&path),
+                path: Some(path),
+                children: vec![],
+            });
+        }
+    }
+    Ok(entries)
+}
+
+/// Get the paths of a folder in order, without hidden files.
+fn read_sorted_directory(folder: &Path, order: WalkOrder) -> Result<Vec<PathBuf>, Report> {
+    let read_dir = std::fs::read_dir(folder)
+        .map_err(|err| eyre!("Failed to read {}: {}", folder.display(), err))?;
+    let mut paths = vec![];
+    for entry in read_dir {
+        let path = entry
+            .map_err(|err| eyre!("Failed to read {}: {}", folder.display(), err))?
+            .path();
+        if !get_file_name(&path).starts_with('.') {
+            paths.push(path);
+        }
+    }
+
+    paths.sort_by(|a, b| {
+        let (a, b) = (get_file_name(a), get_file_name(b));
+        match order {
+            WalkOrder::Name => a.cmp(&b),
+            WalkOrder::Natural => compare_natural(&a, &b),
+        }
+    });
+    Ok(paths)
+}
+
+fn find_index_file(folder: &Path, order: WalkOrder) -> Result<Option<PathBuf>, Report> {
+    Ok(read_sorted_directory(folder, order)?
+        .into_iter()
+        .find(|path| is_outlinable(path) && is_index_file(path)))
+}
+
+fn is_outlinable(path: &Path) -> bool {
+    path.is_file()
+        && path.extension().is_some_and(|extension| {
+            get_reader_by_extension(&extension.to_string_lossy()).is_some()
+        })
+}
+
+fn is_index_file(path: &Path) -> bool {
+    path.file_stem().is_some_and(|stem| {
+        INDEX_FILE_STEMS.contains(&stem.to_string_lossy().to_lowercase().as_str())
+    })
+}
+
+fn get_file_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+fn get_file_title(path: &Path) -> String {
+    path.file_stem()
+        .map(|stem| stem.to_string_lossy().to_string())
+        .unwrap_or_default()
+}
+
+/// Compare names ignoring case, with runs of digits compared by value, e.g. `chapter 2` before `Chapter 10`.
+fn compare_natural(a: &str, b: &str) -> Ordering {
+    fn split_runs(text: &str) -> Vec<&str> {
+        let mut runs = vec![];
+        let mut start = 0;
+        for (index, c) in text.char_indices().skip(1) {
+            let previous = text[..index].chars().last().unwrap();
+            if c.is_ascii_digit() != previous.is_ascii_digit() {
+                runs.push(&text[start..index]);
+                start = index;
+            }
+        }
+        if start < text.len() {
+            runs.push(&text[start..]);
+        }
+        runs
+    }
+
+    let (a_runs, b_runs) = (split_runs(a), split_runs(b));
+    for (a_run, b_run) in a_runs.iter().zip(&b_runs) {
+        let ordering = if a_run.starts_with(|c: char| c.is_ascii_digit())
+            && b_run.starts_with(|c: char| c.is_ascii_digit())
+        {
+            let (a_number, b_number) =
+                (a_run.trim_start_matches('0'), b_run.trim_start_matches('0'));
+            a_number
+                .len()
+                .cmp(&b_number.len())
+                .then_with(|| a_number.cmp(b_number))
+        } else {
+            a_run.to_lowercase().cmp(&b_run.to_lowercase())
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+    a_runs.len().cmp(&b_runs.len()).then_with(|| a.cmp(b))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use crate::entities::project::manifest::{ManifestEntry, ProjectManifest, WalkOrder};
+    use crate::projects::manifest_loader::parse_summary;
+    use crate::utils::test_utils::test_utils::get_temp_folder;
+
+    fn get_titles(entries: &[ManifestEntry]) -> Vec<String> {
+        entries
+            .iter()
+            .map(|entry| match entry.children.is_empty() {
+                true => entry.title.clone(),
+                false => format!("{} {:?}", entry.title, get_titles(&entry.children)),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_load_summaries_and_directories() {
+        let summary = "# Summary\n\n[Preface](preface.md)\n\n# Part I\n\n- [Chapter 1](part_1/chapter%201.md)\n    - [Section](part_1/section.md)\n- [Chapter 2]()\n\n---\n\n# Part II\n\n* [Chapter 3](chapter_3.md)\n\n[Afterword](afterword.md)\n";
+        let entries = parse_summary(summary, Path::new("book"));
+        assert_eq!(
+            get_titles(&entries),
+            vec![
+                "Preface",
+                r#"Part I ["Chapter 1 [\"Section\"]", "Chapter 2"]"#,
+                r#"Part II ["Chapter 3"]"#,
+                "Afterword",
+            ]
+        );
+        assert_eq!(
+            entries[1].children[0].path,
+            Some(PathBuf::from("book/part_1/chapter 1.md"))
+        );
+        assert_eq!(entries[1].children[1].path, None);
+
+        let root = get_temp_folder().join("walked_book");
+        let _ = std::fs::remove_dir_all(&root);
+        for file in [
+            "README.md",
+            "10-end.md",
+            "2-middle.md",
+            "notes.txt",
+            "1-start/index.md",
+            "1-start/scene.opml",
+            "empty/notes.txt",
+        ] {
+            let path = root.join(file);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, "# Title\n").unwrap();
+        }
+        // a loop back up the tree
+        #[cfg(unix)]
+        std::os::unix::fs::symlink(&root, root.join("1-start/loop")).unwrap();
+
+        let manifest = ProjectManifest::Directory {
+            root: root.clone(),
+            order: WalkOrder::Natural,
+        };
+        let entries = manifest.load().unwrap();
+        assert_eq!(
+            get_titles(&entries),
+            vec!["README", r#"1-start ["scene"]"#, "2-middle", "10-end"]
+        );
+        assert_eq!(entries[1].path, Some(root.join("1-start/index.md")));
+
+        let manifest = ProjectManifest::Directory {
+            root,
+            order: WalkOrder::Name,
+        };
+        assert_eq!(
+            get_titles(&manifest.load().unwrap()),
+            vec!["README", r#"1-start ["scene"]"#, "10-end", "2-middle"]
+        );
+    }
+}
@@ -0,0 +1,9 @@
+//! # Projects
+//!
+//! Outline a document written as many files, e.g. a book, from a manifest listing its files: an mdBook
+//! `SUMMARY.md`, a list of files, or a folder.
+//!
+//! The blocks of every file are grafted under the entries of the manifest, so that statistics, targets, writers and
+//! the lint work on the whole book as on a single document.
+pub mod manifest_loader;
+pub mod project;
@@ -0,0 +1,300 @@
+use std::borrow::Cow;
+use std::path::{Path, PathBuf};
+
+use eyre::Report;
+use rayon::prelude::*;
+
+use crate::entities::core::block::{Block, Blocks};
+use crate::entities::core::block_tree::BlockTree;
+use crate::entities::core::freeout::{Freeout, FreeoutOptions};
+use crate::entities::core::outline::{Outline, OutlineRef};
+use crate::entities::project::manifest::{ManifestEntry, ProjectManifest};
+use crate::entities::visitor::visitor_trait::{VisitContext, VisitFlow, VisitorMut};
+use crate::outliners::batch_outliner::process_file;
+use crate::utils::block_utils::BlockUtils;
+use crate::utils::visit_utils::VisitUtils;
+
+/// Marker of the blocks of the manifest entries, as in the list of a `SUMMARY.md`.
+pub const ENTRY_MARKER: &str = "-";
+
+/// A file of a project, with its own blocks.
+pub struct ProjectFile {
+    pub path: PathBuf,
+    pub freeout: Freeout,
+    /// Id in the project of the first block of the file. The blocks of a file keep their order, so a block of id
+    /// `id` in the file has the id `first_block_id + id - 1` in the project.
+    pub first_block_id: usize,
+    /// Id in the project of the block of the manifest entry of the file.
+    pub entry_block_id: usize,
+}
+
+/// # Project
+///
+/// A document written as many files, e.g. a book with a file per chapter, outlined as a single tree.
+///
+/// Every entry of the manifest becomes a block, with the `ENTRY_MARKER` and a depth from its nesting. The blocks of its
+/// file are grafted under it, and its sub-entries follow. Blocks get new ids in the order of the whole project, and
+/// remember the file they were read from in `source_path`. Their positions stay relative to their own file.
+///
+/// Statistics and targets are processed again over the whole tree, so the `aggregate_stats` of an entry cover all
+/// its files, and a target on an entry is distributed across them.
+pub struct Project {
+    pub blocks: Blocks,
+    pub files: Vec<ProjectFile>,
+}
+
+impl Project {
+    /// # Open a project
+    ///
+    /// Loads the entries of the manifest, and outlines their files in parallel. Fails if any file cannot be read or
+    /// outlined.
+    pub fn open(
+        manifest: &ProjectManifest,
+        opt: Option<FreeoutOptions>,
+    ) -> Result<Project, Report> {
+        Self::from_entries(&manifest.load()?, opt)
+    }
+
+    /// Outline the files of the entries, and graft their blocks under the entries.
+    pub fn from_entries(
+        entries: &[ManifestEntry],
+        opt: Option<FreeoutOptions>,
+    ) -> Result<Project, Report> {
+        let opt = opt.unwrap_or_default();
+
+        let mut paths = vec![];
+        collect_paths(entries, &mut paths);
+        let freeouts = paths
+            .par_iter()
+            .map(|path| process_file(path, &opt))
+            .collect::<Result<Vec<Freeout>, Report>>()?;
+
+        let mut project = Project {
+            blocks: Blocks::new(),
+            files: paths
+                .into_iter()
+                .zip(freeouts)
+                .map(|(path, freeout)| ProjectFile {
+                    path: path.to_path_buf(),
+                    freeout,
+                    first_block_id: 0,
+                    entry_block_id: 0,
+                })
+                .collect(),
+        };
+        let mut next_file_index = 0;
+        project.graft_entries(entries, None, 1, &mut next_file_index);
+        project.process_stats();
+        Ok(project)
+    }
+
+    /// Add a block for each entry, with the blocks of its file and its sub-entries under it.
+    fn graft_entries(
+        &mut self,
+        entries: &[ManifestEntry],
+        parent_id: Option<usize>,
+        depth: usize,
+        next_file_index: &mut usize,
+    ) {
+        for entry in entries {
+            let entry_id = BlockUtils::append_block(
+                &mut self.blocks,
+                Block {
+                    depth,
+                    marker: ENTRY_MARKER.to_string(),
+                    title: entry.title.clone(),
+                    source_path: entry.path.clone(),
+                    ..Default::default()
+                },
+                parent_id,
+            );
+
+            if entry.path.is_some() {
+                self.graft_file(*next_file_index, entry_id);
+                *next_file_index += 1;
+            }
+            self.graft_entries(&entry.children, Some(entry_id), depth + 1, next_file_index);
+        }
+    }
+
+    /// Add the blocks of a file, with their root blocks under the block of its entry.
+    fn graft_file(&mut self, file_index: usize, entry_id: usize) {
+        let first_block_id = self.blocks.len() + 1;
+        let file = &mut self.files[file_index];
+        file.first_block_id = first_block_id;
+        file.entry_block_id = entry_id;
+        let to_project_id = |block_id: usize| first_block_id + block_id - 1;
+
+        let blocks: Vec<Block> = (1..=file.freeout.blocks.len())
+            .map(|block_id| {
+                let block = &file.freeout.blocks[&block_id];
+                Block {
+                    // blocks included from another file keep it
+                    source_path: block.source_path.clone().or(Some(file.path.clone())),
+                    // children are added again in order, with their new ids
+                    children_ids: vec![],
+                    ..block.clone()
+                }
+            })
+            .collect();
+        for block in blocks {
+            let parent_id = block.parent_id.map(to_project_id).or(Some(entry_id));
+            BlockUtils::append_block(&mut self.blocks, block, parent_id);
+        }
+    }
+
+    /// # Process the statistics of the whole project
+    ///
+    /// Runs the statistics of `Freeout` over the blocks of all files, e.g. after a target is set on an entry.
+    pub fn process_stats(&mut self) {
+        let mut freeout = Freeout::new(String::new(), None);
+        freeout.blocks = std::mem::take(&mut self.blocks);
+        freeout.tree = BlockTree::new(&freeout.blocks);
+        freeout.process_logical_depths();
+        freeout.process_stats();
+        self.blocks = freeout.blocks;
+    }
+
+    /// Get the outline of the project, borrowing its blocks.
+    pub fn get_outline(&self) -> OutlineRef<'_> {
+        OutlineRef::build_outline(&self.blocks)
+    }
+
+    /// Copy the blocks of the project into an `Outline`, e.g. for a writer, with the `content` of each block resolved
+    /// from the source of its file.
+    pub fn outline(&self) -> Result<Outline, Report> {
+        let mut outline = Outline::build_outline(&self.blocks)?;
+        VisitUtils::walk_outline_mut(&mut outline, &mut ContentResolver { project: self });
+        Ok(outline)
+    }
+
+    /// Get the plain content of a block from its file, see `Freeout::get_content`.
+    pub fn get_content(&self, block_id: usize) -> Option<Cow<'_, str>> {
+        let (file, file_block_id) = self.get_file_block(block_id)?;
+        file.freeout.get_content(file_block_id)
+    }
+
+    /// Get the file of a block of the project, and the id of the block in that file. `None` for the blocks of the
+    /// entries.
+    pub fn get_file_block(&self, block_id: usize) -> Option<(&ProjectFile, usize)> {
+        // files are grafted in order
+        let file_index = self
+            .files
+            .partition_point(|file| file.first_block_id <= block_id)
+            .checked_sub(1)?;
+        let file = &self.files[file_index];
+        let file_block_id = block_id - file.first_block_id + 1;
+        (file_block_id <= file.freeout.blocks.len()).then_some((file, file_block_id))
+    }
+}
+
+/// Fills in the `content` of the blocks of a copied outline from the files of a `Project`.
+struct ContentResolver<'a> {
+    project: &'a Project,
+}
+
+impl VisitorMut for ContentResolver<'_> {
+    fn enter_block(&mut self, block: &mut Block, _context: &VisitContext) -> VisitFlow {
+        if block.content.is_none() {
+            block.content = self
+                .project
+                .get_content(block.id)
+                .map(|content| content.into_owned());
+        }
+        VisitFlow::Continue
+    }
+}
+
+fn collect_paths<'a>(entries: &'a [ManifestEntry], paths: &mut Vec<&'a Path>) {
+    for entry in entries {
+        paths.extend(entry.path.as_deref());
+        collect_paths(&entry.children, paths);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::core::words_statistics::{DistributionMethod, WordsTarget};
+    use crate::entities::project::manifest::ProjectManifest;
+    use crate::projects::project::Project;
+    use crate::utils::test_utils::test_utils::get_fixture_folder;
+
+    #[test]
+    fn should_graft_files_under_their_entries() {
+        let book_folder = get_fixture_folder().join("book");
+        let manifest = ProjectManifest::Summary(book_folder.join("SUMMARY.md"));
+        let mut project = Project::open(&manifest, None).unwrap();
+
+        let outline: Vec<(usize, &str, Option<usize>, usize)> = (1..=project.blocks.len())
+            .map(|id| &project.blocks[&id])
+            .map(|block| {
+                (
+                    block.logical_depth,
+                    block.title.as_str(),
+                    block.parent_id,
+                    block.aggregate_stats.count.words,
+                )
+            })
+            .collect();
+        // the words of a Markdown section include its heading, and the excluded notes do not count in the chapter
+        assert_eq!(
+            outline,
+            vec![
+                (1, "Preface", None, 6),
+                (2, "Preface", Some(1), 6),
+                (1, "Part I", None, 16),
+                (2, "The Beginning", Some(3), 9),
+                (3, "Chapter 1", Some(4), 9),
+                (4, "Scene 1", Some(5), 4),
+                (2, "The Middle", Some(3), 7),
+                (3, "Chapter 2", Some(7), 7),
+                (4, "Notes", Some(8), 6),
+                (2, "The End", Some(3), 0),
+            ]
+        );
+        assert_eq!(
+            project.blocks[&6].source_path,
+            Some(book_folder.join("part_1").join("chapter_1.md"))
+        );
+        assert_eq!(project.blocks[&3].source_path, None);
+
+        let (file, file_block_id) = project.get_file_block(6).unwrap();
+        assert_eq!(file.path, book_folder.join("part_1").join("chapter_1.md"));
+        assert_eq!(file.freeout.blocks[&file_block_id].title, "Scene 1");
+        assert!(project.get_file_block(3).is_none());
+        assert!(project.get_file_block(10).is_none());
+        // the content of a block is resolved from its own file
+        assert_eq!(
+            project.get_content(6).as_deref(),
+            Some("Scene 1\nFour five.")
+        );
+        assert!(project.get_content(3).is_none());
+        assert_eq!(
+            project.outline().unwrap().get_blocks()[5]
+                .content
+                .as_deref(),
+            Some("Scene 1\nFour five.")
+        );
+
+        // a target on a part is distributed across its chapters and their files
+        project.blocks.get_mut(&3).unwrap().aggregate_stats.target = Some(WordsTarget {
+            words: 100,
+            distribution: Some(DistributionMethod::Uniform),
+            distributed: false,
+        });
+        project.process_stats();
+        let targets: Vec<usize> = [4, 5, 7, 10]
+            .iter()
+            .map(|id| {
+                project.blocks[id]
+                    .aggregate_stats
+                    .target
+                    .as_ref()
+                    .unwrap()
+                    .words
+            })
+            .collect();
+        assert_eq!(targets, vec![34, 34, 33, 33]);
+        assert_eq!(project.get_outline().items.len(), 2);
+    }
+}
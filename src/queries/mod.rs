@@ -0,0 +1,6 @@
+//! # Queries
+//!
+//! Select blocks of an outline with a `Selector`, e.g. `"Methods" > * [words < 200]` or `/TODO/i`, written in a
+//! small CSS-like language or built with `Selector::builder`.
+pub mod selector_evaluator;
+pub mod selector_parser;
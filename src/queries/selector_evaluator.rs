@@ -0,0 +1,229 @@
+use std::collections::HashMap;
+
+use eyre::Report;
+
+use crate::entities::core::block::{Block, Blocks};
+use crate::entities::core::freeout::Freeout;
+use crate::entities::core::outline::{Outline, OutlineRef};
+use crate::entities::query::selector::{
+    Attribute, Combinator, ComplexSelector, CompoundSelector, Condition, Selector, TitleMatcher,
+};
+
+impl Selector {
+    /// Ids of the blocks matching the selector, in document order.
+    pub fn select(&self, blocks: &Blocks) -> Vec<usize> {
+        let mut block_ids: Vec<usize> = blocks
+            .values()
+            .filter(|block| self.matches(block, &|block_id| blocks.get(&block_id)))
+            .map(|block| block.id)
+            .collect();
+        block_ids.sort_unstable();
+        block_ids
+    }
+
+    /// Ids of the blocks of an outline matching the selector, in document order.
+    pub fn select_outline(&self, outline: &Outline) -> Vec<usize> {
+        self.select_in_order(outline.get_blocks())
+    }
+
+    /// Like `select_outline`, over an outline borrowing its blocks, e.g. `Freeout::get_outline`.
+    pub fn select_outline_ref(&self, outline: &OutlineRef) -> Vec<usize> {
+        self.select_in_order(outline.get_blocks())
+    }
+
+    /// Ids of the blocks matching the selector, among blocks in document order.
+    fn select_in_order(&self, blocks: Vec<&Block>) -> Vec<usize> {
+        let blocks_by_id: HashMap<usize, &Block> =
+            blocks.iter().map(|block| (block.id, *block)).collect();
+        blocks
+            .into_iter()
+            .filter(|block| self.matches(block, &|block_id| blocks_by_id.get(&block_id).copied()))
+            .map(|block| block.id)
+            .collect()
+    }
+
+    /// Whether a block matches any alternative of the selector. Ancestors are looked up with `get_block`.
+    pub fn matches<'a>(
+        &self,
+        block: &'a Block,
+        get_block: &dyn Fn(usize) -> Option<&'a Block>,
+    ) -> bool {
+        self.alternatives
+            .iter()
+            .any(|alternative| alternative.matches(block, get_block))
+    }
+}
+
+impl ComplexSelector {
+    /// Whether a block matches the last step, and its ancestors the previous ones. A selector without steps
+    /// matches any block.
+    fn matches<'a>(
+        &self,
+        block: &'a Block,
+        get_block: &dyn Fn(usize) -> Option<&'a Block>,
+    ) -> bool {
+        self.steps.is_empty() || self.matches_step(self.steps.len() - 1, block, get_block)
+    }
+
+    fn matches_step<'a>(
+        &self,
+        step: usize,
+        block: &'a Block,
+        get_block: &dyn Fn(usize) -> Option<&'a Block>,
+    ) -> bool {
+        let (combinator, compound) = &self.steps[step];
+        if !compound.matches(block) {
+            return false;
+        }
+        if step == 0 {
+            return true;
+        }
+
+        let mut parent = block.parent_id.and_then(get_block);
+        match combinator {
+            Combinator::Child => {
+                parent.is_some_and(|parent| self.matches_step(step - 1, parent, get_block))
+            }
+            Combinator::Descendant => {
+                while let Some(ancestor) = parent {
+                    if self.matches_step(step - 1, ancestor, get_block) {
+                        return true;
+                    }
+                    parent = ancestor.parent_id.and_then(get_block);
+                }
+                false
+            }
+        }
+    }
+}
+
+impl CompoundSelector {
+    fn matches(&self, block: &Block) -> bool {
+        let matches_title = match &self.title {
+            None => true,
+            Some(TitleMatcher::Exact(title)) => block.title == *title,
+            Some(TitleMatcher::Pattern(pattern)) => pattern.is_match(&block.title),
+        };
+        matches_title
+            && self
+                .conditions
+                .iter()
+                .all(|condition| condition.matches(block))
+    }
+}
+
+impl Condition {
+    fn matches(&self, block: &Block) -> bool {
+        match self {
+            Condition::Compare {
+                attribute,
+                comparison,
+                value,
+            } => comparison.compare(get_attribute(block, *attribute), *value),
+            Condition::Excluded => block.exclude,
+            Condition::Root => block.parent_id.is_none(),
+            Condition::Leaf => block.children_ids.is_empty(),
+        }
+    }
+}
+
+fn get_attribute(block: &Block, attribute: Attribute) -> usize {
+    match attribute {
+        Attribute::Id => block.id,
+        Attribute::Depth => block.depth,
+        Attribute::LogicalDepth => block.logical_depth,
+        Attribute::Words => block.aggregate_stats.count.words,
+        Attribute::OwnWords => block.self_stats.count.words,
+        Attribute::Characters => block.aggregate_stats.count.characters,
+        Attribute::Children => block.children_ids.len(),
+    }
+}
+
+impl Freeout {
+    /// # Select blocks
+    ///
+    /// Ids of the blocks matching a selector, e.g. `"Methods" > * [words < 200]`, in document order. See `Selector`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the selector cannot be parsed.
+    pub fn select(&self, selector: &str) -> Result<Vec<usize>, Report> {
+        Ok(selector.parse::<Selector>()?.select(&self.blocks))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::core::freeout::Freeout;
+    use crate::entities::query::selector::{Attribute, Comparison, Condition, Selector};
+    use crate::readers::markdown::MarkdownReader;
+
+    #[test]
+    fn should_select_blocks() {
+        let text = [
+            "# Paper",
+            "## Methods",
+            "### Sampling",
+            "A few words.",
+            "### TODO Analysis",
+            "Some more words to count here.",
+            "#### Tools",
+            "## Results",
+            "### Sampling",
+            "## Todo",
+        ]
+        .join("\n\n");
+        let mut freeout = Freeout::new(text, None);
+        freeout.process(&MarkdownReader::default()).unwrap();
+
+        // blocks: 1 Paper, 2 Methods, 3 Sampling, 4 TODO Analysis, 5 Tools, 6 Results, 7 Sampling, 8 Todo
+        assert_eq!(
+            freeout.select("\"Methods\" > * [words < 5]").unwrap(),
+            vec![3]
+        );
+        assert_eq!(freeout.select("\"Methods\" *").unwrap(), vec![3, 4, 5]);
+        assert_eq!(freeout.select("/todo/i").unwrap(), vec![4, 8]);
+        assert_eq!(freeout.select("/TODO/ :leaf, :root").unwrap(), vec![1]);
+        assert_eq!(
+            freeout.select("\"Sampling\", [level = 2]:leaf").unwrap(),
+            vec![3, 7, 8]
+        );
+
+        let selector = Selector::builder()
+            .title("Methods")
+            .children()
+            .compare(Attribute::Words, Comparison::Less, 5)
+            .or()
+            .title_pattern("^Too")
+            .unwrap()
+            .condition(Condition::Leaf)
+            .build();
+        assert_eq!(selector.select(&freeout.blocks), vec![3, 5]);
+        assert_eq!(selector.select_outline(&freeout.to_outline()), vec![3, 5]);
+        assert_eq!(
+            selector.select_outline_ref(&freeout.get_outline()),
+            vec![3, 5]
+        );
+
+        let errors = [
+            "\"Methods",
+            "* [size < 2]",
+            "* [words < many]",
+            "* :odd",
+            "/(/",
+            "a",
+        ]
+        .map(|selector| selector.parse::<Selector>().err().unwrap().to_string());
+        assert_eq!(
+            errors,
+            [
+                "Unclosed `\"` at 0 in selector",
+                "Unknown attribute `size` at 3, expected one of: id, depth, level, words, own_words, characters, children",
+                "Expected a number at 11 in selector",
+                "Unknown pseudo-class `:odd` at 3, expected one of: root, leaf, excluded",
+                "Invalid title pattern at 0: regex parse error:\n    (\n    ^\nerror: unclosed group",
+                "Unexpected `a` at 0 in selector",
+            ]
+        );
+    }
+}
@@ -0,0 +1,234 @@
+use std::str::FromStr;
+
+use eyre::{eyre, Report};
+use regex::RegexBuilder;
+
+use crate::entities::query::selector::{
+    Attribute, Combinator, Comparison, ComplexSelector, CompoundSelector, Condition, Selector,
+    TitleMatcher,
+};
+
+impl FromStr for Selector {
+    type Err = Report;
+
+    /// Parse a selector, e.g. `"Methods" > * [words < 200]`. See `Selector` for the syntax.
+    fn from_str(text: &str) -> Result<Self, Self::Err> {
+        let mut parser = SelectorParser { text, offset: 0 };
+        let mut alternatives = vec![parser.parse_complex()?];
+        while parser.eat(',') {
+            alternatives.push(parser.parse_complex()?);
+        }
+        parser.skip_spaces();
+        match parser.peek() {
+            None => Ok(Selector { alternatives }),
+            Some(_) => Err(parser.unexpected()),
+        }
+    }
+}
+
+struct SelectorParser<'a> {
+    text: &'a str,
+    offset: usize,
+}
+
+impl<'a> SelectorParser<'a> {
+    fn parse_complex(&mut self) -> Result<ComplexSelector, Report> {
+        let mut steps = vec![(Combinator::Descendant, self.parse_compound()?)];
+        loop {
+            let has_space = self.skip_spaces();
+            let combinator = match self.peek() {
+                Some('>') => {
+                    self.offset += 1;
+                    Combinator::Child
+                }
+                Some(',') | None => break,
+                Some(_) if has_space => Combinator::Descendant,
+                Some(_) => return Err(self.unexpected()),
+            };
+            steps.push((combinator, self.parse_compound()?));
+        }
+        Ok(ComplexSelector { steps })
+    }
+
+    fn parse_compound(&mut self) -> Result<CompoundSelector, Report> {
+        self.skip_spaces();
+        let title = match self.peek() {
+            Some('*') => {
+                self.offset += 1;
+                None
+            }
+            Some('"') => Some(TitleMatcher::Exact(self.parse_delimited('"')?)),
+            Some('/') => Some(self.parse_pattern()?),
+            Some('[') | Some(':') => None,
+            _ => return Err(self.unexpected()),
+        };
+
+        let mut conditions = vec![];
+        loop {
+            // conditions apply to the step before them, even after a space
+            let offset = self.offset;
+            self.skip_spaces();
+            match self.peek() {
+                Some('[') => conditions.push(self.parse_comparison()?),
+                Some(':') => conditions.push(self.parse_pseudo_class()?),
+                _ => {
+                    self.offset = offset;
+                    break;
+                }
+            }
+        }
+        Ok(CompoundSelector { title, conditions })
+    }
+
+    /// Parse a title pattern, e.g. `/todo/i`.
+    fn parse_pattern(&mut self) -> Result<TitleMatcher, Report> {
+        let start = self.offset;
+        let pattern = self.parse_delimited('/')?;
+        let ignore_case = self.eat_word("i");
+        let regex = RegexBuilder::new(&pattern)
+            .case_insensitive(ignore_case)
+            .build()
+            .map_err(|err| eyre!("Invalid title pattern at {}: {}", start, err))?;
+        Ok(TitleMatcher::Pattern(regex))
+    }
+
+    /// Parse text between delimiters, where `\` escapes the delimiter.
+    fn parse_delimited(&mut self, delimiter: char) -> Result<String, Report> {
+        let start = self.offset;
+        self.offset += delimiter.len_utf8();
+        let mut value = String::new();
+        let mut chars = self.text[self.offset..].char_indices();
+        while let Some((index, c)) = chars.next() {
+            match c {
+                '\\' => match chars.next() {
+                    Some((_, escaped)) if escaped == delimiter => value.push(escaped),
+                    Some((_, escaped)) => {
+                        value.push('\\');
+                        value.push(escaped);
+                    }
+                    None => break,
+                },
+                c if c == delimiter => {
+                    self.offset += index + c.len_utf8();
+                    return Ok(value);
+                }
+                c => value.push(c),
+            }
+        }
+        Err(eyre!("Unclosed `{}` at {} in selector", delimiter, start))
+    }
+
+    /// Parse a comparison, e.g. `[words < 200]`.
+    fn parse_comparison(&mut self) -> Result<Condition, Report> {
+        self.offset += 1;
+        self.skip_spaces();
+        let name = self.parse_word();
+        let attribute = Attribute::ALL
+            .into_iter()
+            .find(|attribute| attribute.name() == name)
+            .ok_or_else(|| {
+                eyre!(
+                    "Unknown attribute `{}` at {}, expected one of: {}",
+                    name,
+                    self.offset - name.len(),
+                    Attribute::ALL.map(|attribute| attribute.name()).join(", ")
+                )
+            })?;
+
+        self.skip_spaces();
+        let comparison = [
+            ("!=", Comparison::NotEqual),
+            ("<=", Comparison::LessOrEqual),
+            (">=", Comparison::GreaterOrEqual),
+            ("=", Comparison::Equal),
+            ("<", Comparison::Less),
+            (">", Comparison::Greater),
+        ]
+        .into_iter()
+        .find(|(operator, _)| self.text[self.offset..].starts_with(operator))
+        .map(|(operator, comparison)| {
+            self.offset += operator.len();
+            comparison
+        })
+        .ok_or_else(|| self.unexpected())?;
+
+        self.skip_spaces();
+        let start = self.offset;
+        let value = self
+            .parse_word()
+            .parse::<usize>()
+            .map_err(|_| eyre!("Expected a number at {} in selector", start))?;
+
+        if !self.eat(']') {
+            return Err(self.unexpected());
+        }
+        Ok(Condition::Compare {
+            attribute,
+            comparison,
+            value,
+        })
+    }
+
+    /// Parse a pseudo-class, e.g. `:leaf`.
+    fn parse_pseudo_class(&mut self) -> Result<Condition, Report> {
+        self.offset += 1;
+        let start = self.offset;
+        match self.parse_word() {
+            "root" => Ok(Condition::Root),
+            "leaf" => Ok(Condition::Leaf),
+            "excluded" => Ok(Condition::Excluded),
+            name => Err(eyre!(
+                "Unknown pseudo-class `:{}` at {}, expected one of: root, leaf, excluded",
+                name,
+                start
+            )),
+        }
+    }
+
+    /// Parse a run of letters, digits and underscores.
+    fn parse_word(&mut self) -> &'a str {
+        let rest: &'a str = &self.text[self.offset..];
+        let length = rest
+            .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+            .unwrap_or(rest.len());
+        self.offset += length;
+        &rest[..length]
+    }
+
+    fn eat_word(&mut self, word: &str) -> bool {
+        let offset = self.offset;
+        if self.parse_word() == word {
+            return true;
+        }
+        self.offset = offset;
+        false
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_spaces();
+        if self.peek() == Some(c) {
+            self.offset += c.len_utf8();
+            return true;
+        }
+        false
+    }
+
+    /// Skip spaces, and tell whether there were any.
+    fn skip_spaces(&mut self) -> bool {
+        let rest = &self.text[self.offset..];
+        let length = rest.len() - rest.trim_start().len();
+        self.offset += length;
+        length > 0
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.text[self.offset..].chars().next()
+    }
+
+    fn unexpected(&self) -> Report {
+        match self.peek() {
+            Some(c) => eyre!("Unexpected `{}` at {} in selector", c, self.offset),
+            None => eyre!("Unexpected end of selector"),
+        }
+    }
+}
@@ -1,25 +1,28 @@
-use eyre::{eyre, Report, WrapErr};
+use std::ops::Range;
+
+use eyre::{eyre, Report};
 use markdown::mdast::Node;
-use markdown::{to_mdast, ParseOptions};
-use tracing::debug;
+use markdown::{to_mdast, Constructs, ParseOptions};
 
 use crate::entities::core::block::{Block, Blocks};
 use crate::entities::core::block_range::BlockRange;
 use crate::entities::core::freeout::FreeoutOptions;
 use crate::entities::core::position::Position;
-use crate::entities::core::words_statistics::WordStatistics;
+use crate::entities::core::words_statistics::{DistributionMethod, WordStatistics, WordsTarget};
 use crate::entities::reader::reader_trait::ReaderTrait;
 
-pub struct MarkdownReader {}
+/// Deepest heading level of Markdown, i.e. `######`.
+pub(crate) const MAX_HEADING_DEPTH: usize = 6;
 
-impl Default for MarkdownReader {
-    fn default() -> Self {
-        Self {}
-    }
-}
+#[derive(Default)]
+pub struct MarkdownReader {}
 
+#[derive(Default)]
 pub struct PastBlocks {
     pub past_block_ids_and_depths: Vec<(usize, usize)>,
+    /// The last block and its ancestors, by increasing depth, so that parents are found without going through
+    /// all the past blocks.
+    open_block_ids_and_depths: Vec<(usize, usize)>,
 }
 
 // method to take in the current block's depth and return its parent id;
@@ -32,12 +35,21 @@ impl PastBlocks {
     // Add a new block id and its depth to the tracker
     pub fn add_block(&mut self, id: usize, depth: usize) {
         self.past_block_ids_and_depths.push((id, depth));
+        // blocks at the same depth or deeper can no longer be parents
+        while self
+            .open_block_ids_and_depths
+            .last()
+            .is_some_and(|&(_, d)| d >= depth)
+        {
+            self.open_block_ids_and_depths.pop();
+        }
+        self.open_block_ids_and_depths.push((id, depth));
     }
 
     // Determine the parent id for a given block's depth
     pub fn get_parent_id_for_depth(&self, depth: usize) -> Option<usize> {
-        // Traverse the list in reverse
-        for &(id, d) in self.past_block_ids_and_depths.iter().rev() {
+        // The last past block with a lesser depth is always open, as no later block was shallow enough to close it
+        for &(id, d) in self.open_block_ids_and_depths.iter().rev() {
             if d < depth {
                 // Found a block with lesser depth, return its id
                 return Some(id);
@@ -58,21 +70,24 @@ impl ReaderTrait for MarkdownReader {
 
         let mut blocks = Blocks::new();
 
-        fn attach_content_to_parent_block(parent_id: usize, content: String, blocks: &mut Blocks) {
+        fn attach_content_to_parent_block(
+            parent_id: usize,
+            content_ranges: Vec<Range<usize>>,
+            blocks: &mut Blocks,
+        ) {
             if let Some(parent) = blocks.get_mut(&parent_id) {
-                // check if the parent has content, if so append the content to the parent's content joined by a space
-                if let Some(parent_content) = &mut parent.content {
-                    parent_content.push_str(&format!("\n{}", content.trim()));
+                // the ranges are joined by newlines when the content is resolved
+                if parent.content_ranges.is_empty() {
+                    parent.content_ranges = content_ranges;
                 } else {
-                    // if the parent doesn't have content, set the content to the parent's content
-                    parent.content = Some(content.to_string());
+                    parent.content_ranges.extend(content_ranges);
                 }
             }
         }
 
         fn process_node(
-            nodes: &[Node],
             source: &str,
+            nodes: &[Node],
             blocks: &mut Blocks,
             opt: &FreeoutOptions,
             past_block_ids_and_depths: &mut PastBlocks,
@@ -98,6 +113,21 @@ impl ReaderTrait for MarkdownReader {
                             "".to_string()
                         };
 
+                        // e.g. `# Notes {.exclude}` or `# Chapter 1 {target=3000}`
+                        let (title, attributes) = split_heading_attributes(&title);
+                        let exclude = attributes.iter().any(|attribute| {
+                            attribute.strip_prefix('.')
+                                == Some(opt.exclusion.attribute_class.as_str())
+                        });
+                        let target = attributes.iter().find_map(|attribute| {
+                            let words = attribute.strip_prefix("target=")?.parse().ok()?;
+                            Some(WordsTarget {
+                                words,
+                                distribution: Some(DistributionMethod::Uniform),
+                                distributed: false,
+                            })
+                        });
+
                         let depth = heading.depth as usize;
 
                         // because Markdown-rs AST is already an ordered tree, we can just use the id as the order
@@ -116,16 +146,22 @@ impl ReaderTrait for MarkdownReader {
                         let block = Block {
                             id,
                             depth,
+                            logical_depth: 0, // computed by Freeout
                             marker: "#".repeat(heading.depth as usize),
                             title: title.clone(),
-                            content: None, // Do not attach any content to heading itself. Instead, its children's text or code content will be attached to its parent at a later stage, if `include_content` option is enabled.
+                            content: None, // Do not attach any content to heading itself. Instead, the ranges of its children's text or code content will be attached to its parent at a later stage, if `include_content` option is enabled.
+                            content_ranges: vec![],
                             note: None,
                             parent_id,
                             header_range: BlockRange { start, end },
                             self_stats: WordStatistics::default(),
-                            aggregate_stats: WordStatistics::default(),
-                            exclude: false,
+                            aggregate_stats: WordStatistics {
+                                target,
+                                ..WordStatistics::default()
+                            },
+                            exclude,
                             hash: None,
+                            source_path: None,
                             children_ids: vec![],
                             block_range: None,
                         };
@@ -135,13 +171,21 @@ impl ReaderTrait for MarkdownReader {
                         past_block_ids_and_depths.add_block(id, depth);
 
                         process_node(
-                            &heading.children,
                             source,
+                            &heading.children,
                             blocks,
                             opt,
                             past_block_ids_and_depths,
                         );
                     }
+                    // e.g. `<!-- freeout:exclude -->` under a heading
+                    Node::Html(html) if opt.exclusion.is_comment_marker(&html.value) => {
+                        if let Some((last_block_id, _)) = past_block_ids_and_depths.last() {
+                            if let Some(last_block) = blocks.get_mut(last_block_id) {
+                                last_block.exclude = true;
+                            }
+                        }
+                    }
                     // Handle other node types as necessary
                     _ => {
                         // add children content
@@ -155,31 +199,26 @@ impl ReaderTrait for MarkdownReader {
                             .map(|(_, depth)| depth + 1)
                             .unwrap_or(0);
 
-                        let mut content: String = "".to_string();
-
-                        match node {
+                        // the content stays in the source, only its ranges are kept
+                        let content_ranges: Vec<Range<usize>> = match node {
                             Node::Text(text) => {
-                                content = text.value.clone();
+                                get_text_range(source, &text.position).into_iter().collect()
                             }
-                            Node::Paragraph(paragraph) => {
-                                for child in &paragraph.children {
-                                    match child {
-                                        Node::Text(text) => {
-                                            content.push_str(&text.value);
-                                        }
-                                        _ => {}
-                                    }
-                                }
-                            }
-                            Node::Code(code) => {
-                                content = code.value.clone();
-                            }
-                            _ => {}
-                        }
+                            Node::Paragraph(paragraph) => paragraph
+                                .children
+                                .iter()
+                                .filter_map(|child| match child {
+                                    Node::Text(text) => get_text_range(source, &text.position),
+                                    _ => None,
+                                })
+                                .collect(),
+                            Node::Code(code) => get_code_range(source, code).into_iter().collect(),
+                            _ => vec![],
+                        };
 
                         let parent_id = past_block_ids_and_depths.get_parent_id_for_depth(depth);
                         if let Some(parent_id) = parent_id {
-                            attach_content_to_parent_block(parent_id, content, blocks);
+                            attach_content_to_parent_block(parent_id, content_ranges, blocks);
                         }
                     }
                 }
@@ -188,32 +227,144 @@ impl ReaderTrait for MarkdownReader {
 
         if let Node::Root(root) = &ast {
             process_node(
-                &root.children,
                 source,
+                &root.children,
                 &mut blocks,
                 opt,
-                &mut PastBlocks {
-                    past_block_ids_and_depths: vec![],
-                },
+                &mut PastBlocks::default(),
             );
+
+            // exclude the titles listed in the front matter
+            if let Some(Node::Yaml(yaml)) = root.children.first() {
+                let excluded_titles =
+                    get_front_matter_list(&yaml.value, &opt.exclusion.front_matter_key)?;
+                for block in blocks.values_mut() {
+                    if excluded_titles.contains(&block.title) {
+                        block.exclude = true;
+                    }
+                }
+            }
         }
 
         Ok(blocks)
     }
+
+    /// ATX heading markers, e.g. `##` at depth 2. Markdown has no heading beyond depth 6.
+    fn render_marker(&self, depth: usize) -> Option<String> {
+        (1..=MAX_HEADING_DEPTH)
+            .contains(&depth)
+            .then(|| "#".repeat(depth))
+    }
+
+    fn read_code_ranges(&self, source: &str) -> Result<Vec<Range<usize>>, Report> {
+        fn collect_code_ranges(node: &Node, code_ranges: &mut Vec<Range<usize>>) {
+            if let (Node::Code(_) | Node::InlineCode(_), Some(position)) = (node, node.position()) {
+                code_ranges.push(position.start.offset..position.end.offset);
+            }
+            for child in node.children().into_iter().flatten() {
+                collect_code_ranges(child, code_ranges);
+            }
+        }
+
+        let mut code_ranges = vec![];
+        collect_code_ranges(&markdown_to_ast(source)?, &mut code_ranges);
+        Ok(code_ranges)
+    }
+}
+
+/// The range of the trimmed text of a node in the source, if any.
+fn get_text_range(
+    source: &str,
+    position: &Option<markdown::unist::Position>,
+) -> Option<Range<usize>> {
+    let position = position.as_ref()?;
+    let text = &source[position.start.offset..position.end.offset];
+    let start = position.start.offset + (text.len() - text.trim_start().len());
+    let end = position.start.offset + text.trim_end().len();
+    (start < end).then_some(start..end)
 }
 
-fn markdown_to_ast(text: &str) -> Result<Node, Report> {
-    let markdown_ask = to_mdast(text, &ParseOptions::default())
-        .map_err(|err| eyre!("Failed to parse markdown: {}", err))?;
+/// The range of the code of a code block in the source, without its fences. The whole block is kept if its code is
+/// not found as is, e.g. in an indented list item.
+fn get_code_range(source: &str, code: &markdown::mdast::Code) -> Option<Range<usize>> {
+    let position = code.position.as_ref()?;
+    let code_start = source[position.start.offset..position.end.offset]
+        .find(&code.value)
+        .filter(|_| !code.value.is_empty());
+    match code_start {
+        Some(code_start) => {
+            let start = position.start.offset + code_start;
+            Some(start..start + code.value.len())
+        }
+        None => get_text_range(source, &code.position),
+    }
+}
+
+pub(crate) fn markdown_to_ast(text: &str) -> Result<Node, Report> {
+    let parse_options = ParseOptions {
+        constructs: Constructs {
+            frontmatter: true,
+            ..Constructs::default()
+        },
+        ..ParseOptions::default()
+    };
+    let markdown_ask =
+        to_mdast(text, &parse_options).map_err(|err| eyre!("Failed to parse markdown: {}", err))?;
     Ok(markdown_ask)
 }
 
+/// Split a trailing attribute block, e.g. `{#id .class key=value}`, from a heading title.
+///
+/// Returns the trimmed title and the attributes. A title without an attribute block is returned as is.
+pub(crate) fn split_heading_attributes(title: &str) -> (String, Vec<String>) {
+    let trimmed_title = title.trim_end();
+    if let Some(attribute_start) = trimmed_title.rfind('{') {
+        if let Some(attributes) = trimmed_title[attribute_start..]
+            .strip_prefix('{')
+            .and_then(|attributes| attributes.strip_suffix('}'))
+        {
+            let is_attribute_block = !attributes.trim().is_empty()
+                && attributes
+                    .split_whitespace()
+                    .all(|attribute| attribute.starts_with(['#', '.']) || attribute.contains('='));
+            if is_attribute_block {
+                return (
+                    trimmed_title[..attribute_start].trim_end().to_string(),
+                    attributes.split_whitespace().map(String::from).collect(),
+                );
+            }
+        }
+    }
+    (title.to_string(), vec![])
+}
+
+/// Get the list of strings under a key of the YAML front matter.
+///
+/// A missing key or a non-list value is treated as an empty list.
+fn get_front_matter_list(front_matter: &str, key: &str) -> Result<Vec<String>, Report> {
+    let front_matter: serde_yaml::Value = serde_yaml::from_str(front_matter)
+        .map_err(|err| eyre!("Failed to parse front matter: {}", err))?;
+    let list = front_matter
+        .get(key)
+        .and_then(|value| value.as_sequence())
+        .map(|values| {
+            values
+                .iter()
+                .filter_map(|value| value.as_str().map(String::from))
+                .collect()
+        })
+        .unwrap_or_default();
+    Ok(list)
+}
+
 #[cfg(test)]
 mod tests {
+    use itertools::Itertools;
     use tracing::info;
     use tracing_test::traced_test;
     use words_count::count;
 
+    use crate::entities::core::exclusion_rules::ExclusionRules;
     use crate::entities::core::freeout::Freeout;
     use crate::entities::core::outline::Outline;
     use crate::utils::block_range_utils::PositionUtils;
@@ -241,10 +392,11 @@ mod tests {
         // markdown file
         let markdown_file = md_get_long_chinese();
         // generate blocks
-        let mut reader = MarkdownReader::default();
+        let reader = MarkdownReader::default();
 
         let options = FreeoutOptions {
             include_content: true,
+            ..Default::default()
         };
 
         let blocks = reader
@@ -266,10 +418,11 @@ mod tests {
         // markdown file
         let markdown_file = md_get_long_chinese();
         // generate blocks
-        let mut reader = MarkdownReader::default();
+        let reader = MarkdownReader::default();
 
         let options = FreeoutOptions {
             include_content: true,
+            ..Default::default()
         };
 
         let blocks = reader
@@ -290,10 +443,11 @@ mod tests {
         // markdown file
         let markdown_file = md_get_long_chinese();
         // generate blocks
-        let mut reader = MarkdownReader::default();
+        let reader = MarkdownReader::default();
 
         let options = FreeoutOptions {
             include_content: true,
+            ..Default::default()
         };
 
         let mut freeout = Freeout::new(
@@ -322,15 +476,17 @@ mod tests {
         // use raw string
         let markdown = format!("# Title 1\n{}\n## Title 2", markdown_content.trim());
 
-        let mut reader = MarkdownReader::default();
+        let reader = MarkdownReader::default();
 
-        let outline = Freeout::new(markdown.clone(), None).outline(&reader).unwrap();
+        let mut freeout = Freeout::new(markdown.clone(), None);
+        freeout.process(&reader).unwrap();
+        let outline = freeout.to_outline();
 
         let root_blocks = outline.items;
 
         assert_eq!(root_blocks.len(), 1);
 
-        let first_block = root_blocks.get(0).unwrap().block.clone();
+        let first_block = root_blocks.first().unwrap().block.clone();
 
         let first_block_content = first_block.content.unwrap().clone();
 
@@ -342,13 +498,95 @@ mod tests {
         assert!(first_block.hash.is_some());
         assert!(first_block.self_stats.count.words > 0);
 
-        // the first block's range should also be able to retrieve the same content.
-        // the block_range should be some
+        // the block_range should be some, and cover the whole section: the heading, the content and the children
         assert!(first_block.block_range.is_some());
         let first_block_range = first_block.block_range.clone().unwrap();
-        let extractd_text_by_range = PositionUtils::get_text_by_range(&markdown, &first_block_range);
+        let extractd_text_by_range =
+            PositionUtils::get_text_by_range(&markdown, &first_block_range);
+
+        assert_eq!(extractd_text_by_range, markdown);
+
+        // the own content of the block is the text between its heading and its first child
+        assert_eq!(
+            freeout.get_own_content(first_block.id).unwrap().trim(),
+            markdown_content.trim()
+        );
+    }
+
+    #[test]
+    fn should_exclude_blocks_by_rules() {
+        let markdown = r#"---
+exclude:
+  - References
+---
+
+# Book
+
+One two three
+
+## Chapter
+
+Four five
+
+## Notes {.exclude}
 
-        assert_eq!(extractd_text_by_range, markdown_content.trim());
+Six seven eight
+
+## Draft
+
+<!-- freeout:exclude -->
+
+Nine ten
+
+### Draft Section
+
+Eleven
+
+## References
+
+Twelve
+
+## TODO Appendix
+
+Thirteen
+"#;
+
+        let options = FreeoutOptions {
+            exclusion: ExclusionRules::default()
+                .with_title_pattern("^TODO")
+                .unwrap(),
+            ..Default::default()
+        };
+
+        let mut freeout = Freeout::new(markdown.to_string(), Some(options));
+        freeout.outline(&MarkdownReader::default()).unwrap();
+
+        let excluded_titles: Vec<&str> = freeout
+            .blocks
+            .values()
+            .filter(|block| block.exclude)
+            .map(|block| block.title.as_str())
+            .sorted()
+            .collect();
+
+        assert_eq!(
+            excluded_titles,
+            vec![
+                "Draft",
+                "Draft Section",
+                "Notes",
+                "References",
+                "TODO Appendix"
+            ]
+        );
+
+        // only the title and content of the book and the chapter are counted
+        let book = &freeout.blocks[&1];
+        let chapter = &freeout.blocks[&2];
+        assert_eq!(
+            book.aggregate_stats.count.words,
+            book.self_stats.count.words + chapter.self_stats.count.words
+        );
     }
 
     // #[traced_test]
@@ -373,12 +611,15 @@ mod tests {
         // measure the time it takes to generate the outline
 
         let start = std::time::Instant::now();
-        let outline = Freeout::new(markdown_with_thousands_of_depths, Some(FreeoutOptions {
-            include_content: true,
-            ..Default::default()
-        }))
-            .outline(&reader)
-            .unwrap();
+        let mut freeout = Freeout::new(
+            markdown_with_thousands_of_depths,
+            Some(FreeoutOptions {
+                include_content: true,
+                ..Default::default()
+            }),
+        );
+        freeout.process(&reader).unwrap();
+        let outline = freeout.to_outline();
 
         let end = std::time::Instant::now();
         info!(
@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+use eyre::Report;
+use markdown::mdast::Node;
+use regex::Regex;
+
+use crate::entities::graph::link::{Link, LinkKind};
+use crate::readers::markdown::{markdown_to_ast, MarkdownReader};
+
+/// e.g. `[[Note#Heading|text]]`. Embeds, e.g. `![[Note]]`, are matched too, to be skipped as includes: excluding
+/// their `!` with a preceding character would consume the end of an adjacent wikilink.
+static WIKILINK_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"!?\[\[([^\[\]|]+)(?:\|([^\[\]]*))?\]\]").unwrap());
+
+impl MarkdownReader {
+    /// # Read the links of a document
+    ///
+    /// Reads inline links, reference links with their definitions, and wikilinks, in document order. Links in code
+    /// are not read.
+    pub fn read_links(&self, source: &str) -> Result<Vec<Link>, Report> {
+        let ast = markdown_to_ast(source)?;
+        let mut definitions = HashMap::new();
+        collect_definitions(&ast, &mut definitions);
+
+        let mut links = vec![];
+        collect_links(&ast, source, &definitions, &mut links);
+        links.sort_by_key(|link| link.offsets.start);
+        Ok(links)
+    }
+}
+
+fn collect_definitions(node: &Node, definitions: &mut HashMap<String, String>) {
+    if let Node::Definition(definition) = node {
+        definitions.insert(definition.identifier.clone(), definition.url.clone());
+    }
+    for child in node.children().into_iter().flatten() {
+        collect_definitions(child, definitions);
+    }
+}
+
+fn collect_links(
+    node: &Node,
+    source: &str,
+    definitions: &HashMap<String, String>,
+    links: &mut Vec<Link>,
+) {
+    let offsets = node
+        .position()
+        .map_or(0..0, |position| position.start.offset..position.end.offset);
+    match node {
+        Node::Link(link) => links.push(Link {
+            kind: LinkKind::Markdown,
+            text: node.to_string(),
+            destination: link.url.clone(),
+            offsets,
+        }),
+        Node::LinkReference(reference) => {
+            if let Some(url) = definitions.get(&reference.identifier) {
+                links.push(Link {
+                    kind: LinkKind::Markdown,
+                    text: node.to_string(),
+                    destination: url.clone(),
+                    offsets,
+                });
+            }
+        }
+        // the source is read rather than the value, so that offsets stay right with escapes
+        Node::Text(_) => {
+            for captures in WIKILINK_REGEX.captures_iter(&source[offsets.clone()]) {
+                let wikilink = captures.get(0).unwrap();
+                if wikilink.as_str().starts_with('!') {
+                    continue;
+                }
+                let destination = captures[1].trim().to_string();
+                links.push(Link {
+                    kind: LinkKind::WikiLink,
+                    text: captures
+                        .get(2)
+                        .map_or(destination.clone(), |text| text.as_str().trim().to_string()),
+                    destination,
+                    offsets: offsets.start + wikilink.start()..offsets.start + wikilink.end(),
+                });
+            }
+        }
+        _ => {}
+    }
+
+    for child in node.children().into_iter().flatten() {
+        collect_links(child, source, definitions, links);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::graph::link::LinkKind;
+    use crate::readers::markdown::MarkdownReader;
+
+    #[test]
+    fn should_read_adjacent_wikilinks() {
+        let source = "See [[Alpha]][[Beta|the second]] and ![[Gamma]]![[Delta]][[Epsilon]].";
+        let links = MarkdownReader::default().read_links(source).unwrap();
+
+        assert!(links.iter().all(|link| link.kind == LinkKind::WikiLink));
+        assert_eq!(
+            links
+                .iter()
+                .map(|link| (
+                    link.destination.as_str(),
+                    link.text.as_str(),
+                    &source[link.offsets.clone()]
+                ))
+                .collect::<Vec<(&str, &str, &str)>>(),
+            vec![
+                ("Alpha", "Alpha", "[[Alpha]]"),
+                ("Beta", "the second", "[[Beta|the second]]"),
+                ("Epsilon", "Epsilon", "[[Epsilon]]"),
+            ]
+        );
+    }
+}
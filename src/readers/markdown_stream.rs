@@ -0,0 +1,319 @@
+use std::io::BufRead;
+
+use eyre::{eyre, Report};
+
+use crate::entities::core::block::Block;
+use crate::entities::core::block_range::BlockRange;
+use crate::entities::core::freeout::FreeoutOptions;
+use crate::entities::core::position::Position;
+use crate::entities::core::words_statistics::WordCount;
+use crate::readers::markdown::{split_heading_attributes, MarkdownReader, MAX_HEADING_DEPTH};
+
+/// A block whose own content is still being read.
+struct OpenBlock {
+    id: usize,
+    depth: usize,
+    logical_depth: usize,
+    exclude: bool,
+}
+
+/// An open code fence, e.g. ```` ``` ```` or `~~~~`.
+struct Fence {
+    character: char,
+    length: usize,
+}
+
+/// # Markdown Stream
+///
+/// Reads the blocks of a Markdown document line by line, for documents too large to be held in memory, e.g. wiki
+/// dumps of hundreds of megabytes.
+///
+/// Blocks are yielded in document order, as soon as their own content ends, i.e. at the next heading or at the end of
+/// the document. Their content is counted in `self_stats`, but not stored, so memory stays bounded by the longest
+/// line and the depth of the headings.
+///
+/// Unlike `MarkdownReader`, the stream does not build a syntax tree:
+/// - Only ATX headings, e.g. `## Title`, are detected. Headings in fenced code blocks are content.
+/// - Content is counted as written, markup included, e.g. the URL of a link.
+/// - Lines are 0-indexed and columns are in bytes. `block_range`, `aggregate_stats` and `children_ids` are left to
+///   the consumer, since a section only ends with the next sibling or uncle.
+/// - A front matter is skipped, but its exclusion list is not read.
+pub struct MarkdownStream<R: BufRead> {
+    reader: R,
+    options: FreeoutOptions,
+    /// Buffer of the current line, reused from line to line.
+    line: String,
+    line_index: usize,
+    offset: usize,
+    /// The block being read and its ancestors.
+    open_blocks: Vec<OpenBlock>,
+    /// The block being read, until its content ends.
+    current_block: Option<Block>,
+    fence: Option<Fence>,
+    is_in_front_matter: bool,
+    next_id: usize,
+}
+
+impl MarkdownReader {
+    /// Read the blocks of a document from a stream. See `MarkdownStream`.
+    pub fn stream<R: BufRead>(&self, reader: R, options: &FreeoutOptions) -> MarkdownStream<R> {
+        MarkdownStream {
+            reader,
+            options: options.clone(),
+            line: String::new(),
+            line_index: 0,
+            offset: 0,
+            open_blocks: vec![],
+            current_block: None,
+            fence: None,
+            is_in_front_matter: false,
+            next_id: 1,
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for MarkdownStream<R> {
+    type Item = Result<Block, Report>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            self.line.clear();
+            let line_length = match self.reader.read_line(&mut self.line) {
+                Ok(line_length) => line_length,
+                Err(err) => {
+                    return Some(Err(eyre!(
+                        "Failed to read line {}: {}",
+                        self.line_index + 1,
+                        err
+                    )))
+                }
+            };
+            // the end of the document ends the content of the last block
+            if line_length == 0 {
+                return self.current_block.take().map(Ok);
+            }
+
+            // the buffer is given back after the line is read, to be reused
+            let line = std::mem::take(&mut self.line);
+            let finished_block = self.read_line(&line);
+            self.line = line;
+            self.offset += line_length;
+            self.line_index += 1;
+            if let Some(block) = finished_block {
+                return Some(Ok(block));
+            }
+        }
+    }
+}
+
+impl<R: BufRead> MarkdownStream<R> {
+    /// Read the current line, and get the previous block if the line is a heading.
+    fn read_line(&mut self, line: &str) -> Option<Block> {
+        let line = line.trim_end_matches(['\n', '\r']);
+
+        if self.line_index == 0 && line == "---" {
+            self.is_in_front_matter = true;
+            return None;
+        }
+        if self.is_in_front_matter {
+            self.is_in_front_matter = line != "---";
+            return None;
+        }
+
+        if let Some(fence) = &self.fence {
+            if is_closing_fence(line, fence) {
+                self.fence = None;
+            }
+        } else if let Some(fence) = get_opening_fence(line) {
+            self.fence = Some(fence);
+        } else if let Some((depth, title)) = get_atx_heading(line) {
+            let header_start = line.len() - line.trim_start().len();
+            let header_range = BlockRange {
+                start: self.get_position(header_start),
+                end: self.get_position(line.trim_end().len()),
+            };
+            let block = self.open_block(depth, title, header_range);
+            return self.current_block.replace(block);
+        }
+
+        let line = line.trim();
+        if let Some(block) = &mut self.current_block {
+            // e.g. `<!-- freeout:exclude -->` under a heading
+            if line.starts_with("<!--") && self.options.exclusion.is_comment_marker(line) {
+                block.exclude = true;
+                self.open_blocks.last_mut().unwrap().exclude = true;
+            } else if !line.is_empty() {
+                block.self_stats.count += &WordCount::from(words_count::count(line));
+            }
+        }
+        None
+    }
+
+    fn open_block(&mut self, depth: usize, title: &str, header_range: BlockRange) -> Block {
+        // blocks at the same depth or deeper are closed by the heading
+        while self
+            .open_blocks
+            .last()
+            .is_some_and(|block| block.depth >= depth)
+        {
+            self.open_blocks.pop();
+        }
+        let parent = self.open_blocks.last();
+
+        let (title, attributes) = split_heading_attributes(title);
+        let exclude = parent.is_some_and(|parent| parent.exclude)
+            || self.options.exclusion.matches_title(&title)
+            || attributes.iter().any(|attribute| {
+                attribute.strip_prefix('.') == Some(self.options.exclusion.attribute_class.as_str())
+            });
+
+        let block = Block {
+            id: self.next_id,
+            depth,
+            logical_depth: parent.map_or(0, |parent| parent.logical_depth) + 1,
+            marker: "#".repeat(depth),
+            title,
+            parent_id: parent.map(|parent| parent.id),
+            header_range,
+            exclude,
+            ..Default::default()
+        };
+        self.next_id += 1;
+
+        self.open_blocks.push(OpenBlock {
+            id: block.id,
+            depth: block.depth,
+            logical_depth: block.logical_depth,
+            exclude: block.exclude,
+        });
+        block
+    }
+
+    fn get_position(&self, column: usize) -> Position {
+        Position {
+            line: self.line_index,
+            column,
+            offset: self.offset + column,
+        }
+    }
+}
+
+/// Get the depth and the title of an ATX heading, e.g. `## Title ##`.
+fn get_atx_heading(line: &str) -> Option<(usize, &str)> {
+    let content = strip_indent(line)?;
+    let depth = content.len() - content.trim_start_matches('#').len();
+    if !(1..=MAX_HEADING_DEPTH).contains(&depth) {
+        return None;
+    }
+
+    let rest = &content[depth..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+
+    // an optional closing sequence, e.g. `## Title ##`
+    let title = rest.trim();
+    let without_closing = title.trim_end_matches('#');
+    let title = if without_closing.is_empty() || without_closing.ends_with([' ', '\t']) {
+        without_closing.trim_end()
+    } else {
+        title
+    };
+    Some((depth, title))
+}
+
+fn get_opening_fence(line: &str) -> Option<Fence> {
+    let content = strip_indent(line)?;
+    let character = content.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let length = content.len() - content.trim_start_matches(character).len();
+    // the info string of a backtick fence cannot contain backticks
+    let is_valid_info = character == '~' || !content[length..].contains('`');
+    (length >= 3 && is_valid_info).then_some(Fence { character, length })
+}
+
+fn is_closing_fence(line: &str, fence: &Fence) -> bool {
+    strip_indent(line).is_some_and(|content| {
+        let content = content.trim_end();
+        let length = content.len() - content.trim_start_matches(fence.character).len();
+        length >= fence.length && length == content.len()
+    })
+}
+
+/// Strip the indentation of a line, of at most 3 spaces. More makes an indented code block.
+fn strip_indent(line: &str) -> Option<&str> {
+    let content = line.trim_start_matches(' ');
+    (line.len() - content.len() <= 3).then_some(content)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use crate::entities::core::block::Block;
+    use crate::entities::core::freeout::{Freeout, FreeoutOptions};
+    use crate::readers::markdown::MarkdownReader;
+    use crate::utils::test_utils::test_utils::md_get_long_chinese;
+
+    fn stream(text: &str) -> Vec<Block> {
+        MarkdownReader::default()
+            .stream(Cursor::new(text.as_bytes()), &FreeoutOptions::default())
+            .collect::<Result<Vec<Block>, _>>()
+            .unwrap()
+    }
+
+    #[test]
+    fn should_stream_blocks_like_the_markdown_reader() {
+        let text = std::fs::read_to_string(md_get_long_chinese()).unwrap();
+        let mut freeout = Freeout::new(text.clone(), None);
+        freeout.outline(&MarkdownReader::default()).unwrap();
+
+        let blocks = stream(&text);
+        assert_eq!(blocks.len(), freeout.blocks.len());
+        for block in &blocks {
+            let expected = &freeout.blocks[&block.id];
+            assert_eq!(
+                (
+                    &block.title,
+                    block.depth,
+                    block.parent_id,
+                    block.logical_depth
+                ),
+                (
+                    &expected.title,
+                    expected.depth,
+                    expected.parent_id,
+                    expected.logical_depth
+                )
+            );
+            assert_eq!(block.header_range, expected.header_range);
+        }
+    }
+
+    #[test]
+    fn should_count_content_outside_of_headings() {
+        let text = "---\ntitle: Book\n---\n\n# Book ##\n\nOne two\n\n```md\n# Not a heading\n```\n\n    # Code\n\n## Notes {.exclude}\n\n<!-- freeout:exclude -->\n\n### Draft\n\nthree\n";
+        let blocks = stream(text);
+
+        let summary: Vec<(&str, usize, bool)> = blocks
+            .iter()
+            .map(|block| {
+                (
+                    block.title.as_str(),
+                    block.self_stats.count.words,
+                    block.exclude,
+                )
+            })
+            .collect();
+        // code counts as content, fences included, and `words_count` counts each run of punctuation, e.g. "```" or
+        // "#", as a word; the front matter does not count
+        assert_eq!(
+            summary,
+            vec![("Book", 10, false), ("Notes", 0, true), ("Draft", 1, true)]
+        );
+        assert_eq!(
+            blocks[0].header_range.start.offset,
+            text.find("# Book").unwrap()
+        );
+        assert_eq!(blocks[2].header_range.start.line, 18);
+    }
+}
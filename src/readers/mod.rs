@@ -1 +1,17 @@
-pub mod markdown;
\ No newline at end of file
+use crate::entities::reader::reader_trait::ReaderTrait;
+
+pub mod markdown;
+pub mod markdown_links;
+pub mod markdown_stream;
+pub mod opml;
+
+/// # Get a reader by file extension
+///
+/// Returns `None` for an unknown extension. The extension is matched ignoring case, without the dot.
+pub fn get_reader_by_extension(extension: &str) -> Option<Box<dyn ReaderTrait>> {
+    match extension.to_lowercase().as_str() {
+        "md" | "markdown" | "mdx" => Some(Box::new(markdown::MarkdownReader::default())),
+        "opml" => Some(Box::new(opml::OpmlReader::default())),
+        _ => None,
+    }
+}
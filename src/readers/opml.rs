@@ -0,0 +1,187 @@
+use eyre::{eyre, Report};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+use crate::entities::core::block::{Block, Blocks};
+use crate::entities::core::block_range::BlockRange;
+use crate::entities::core::freeout::FreeoutOptions;
+use crate::entities::core::words_statistics::{DistributionMethod, WordsTarget};
+use crate::entities::reader::reader_trait::ReaderTrait;
+use crate::utils::text_utils::TextUtils;
+
+/// # OPML Reader
+///
+/// Reads the `<outline>` elements of an OPML document into blocks, so that an outline planned in an outliner app can become a skeleton document.
+///
+/// - `text` becomes the title, and `_note` the note.
+/// - `targetWords` becomes the `aggregate_stats.target` of the block, distributed uniformly among its children.
+/// - `exclude="true"` marks the block as excluded.
+///
+/// The nesting level of an `<outline>` element is the depth of its block.
+#[derive(Default)]
+pub struct OpmlReader {}
+
+impl ReaderTrait for OpmlReader {
+    fn read(&self, source: &str, _opt: &FreeoutOptions) -> Result<Blocks, Report> {
+        let mut reader = Reader::from_str(source);
+
+        let mut blocks = Blocks::new();
+        // ids of the open `<outline>` elements
+        let mut parent_ids: Vec<usize> = vec![];
+        let mut is_in_body = false;
+
+        loop {
+            let start_offset = reader.buffer_position();
+            let event = reader
+                .read_event()
+                .map_err(|err| eyre!("Failed to parse OPML at {}: {}", start_offset, err))?;
+            let end_offset = reader.buffer_position();
+
+            match event {
+                Event::Start(element) if element.name().as_ref() == b"body" => {
+                    is_in_body = true;
+                }
+                Event::End(element) if element.name().as_ref() == b"body" => {
+                    is_in_body = false;
+                }
+                Event::Start(ref element) | Event::Empty(ref element)
+                    if is_in_body && element.name().as_ref() == b"outline" =>
+                {
+                    let id = blocks.len() + 1;
+                    let parent_id = parent_ids.last().copied();
+
+                    let mut block = read_outline_element(element)?;
+                    block.id = id;
+                    block.depth = parent_ids.len() + 1;
+                    block.parent_id = parent_id;
+                    block.header_range = BlockRange {
+                        start: TextUtils::get_end_position(&source[..start_offset]),
+                        end: TextUtils::get_end_position(&source[..end_offset]),
+                    };
+
+                    if let Some(parent) = parent_id.and_then(|parent_id| blocks.get_mut(&parent_id))
+                    {
+                        parent.children_ids.push(id);
+                    }
+                    blocks.insert(id, block);
+
+                    if let Event::Start(_) = event {
+                        parent_ids.push(id);
+                    }
+                }
+                Event::End(element) if is_in_body && element.name().as_ref() == b"outline" => {
+                    parent_ids.pop();
+                }
+                Event::Eof => break,
+                _ => {}
+            }
+        }
+
+        Ok(blocks)
+    }
+
+    fn name(&self) -> &'static str {
+        "OPML Reader"
+    }
+}
+
+fn read_outline_element(element: &BytesStart) -> Result<Block, Report> {
+    let mut block = Block::default();
+
+    for attribute in element.attributes() {
+        let attribute =
+            attribute.map_err(|err| eyre!("Failed to parse OPML attribute: {}", err))?;
+        let value = attribute
+            .unescape_value()
+            .map_err(|err| eyre!("Failed to unescape OPML attribute: {}", err))?;
+
+        match attribute.key.as_ref() {
+            b"text" => block.title = value.to_string(),
+            b"_note" => block.note = Some(value.to_string()),
+            b"targetWords" => {
+                let words = value
+                    .parse::<usize>()
+                    .map_err(|err| eyre!("Invalid targetWords \"{}\": {}", value, err))?;
+                block.aggregate_stats.target = Some(WordsTarget {
+                    words,
+                    distribution: Some(DistributionMethod::Uniform),
+                    distributed: false,
+                });
+            }
+            b"exclude" => block.exclude = value == "true",
+            _ => {}
+        }
+    }
+
+    Ok(block)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::core::freeout::Freeout;
+    use crate::entities::core::outline::OutlineItemRef;
+    use crate::entities::writer::writer_trait::WriterTrait;
+    use crate::readers::opml::OpmlReader;
+    use crate::utils::test_utils::test_utils::opml_get_book_plan;
+    use crate::writers::opml::OpmlWriter;
+
+    #[test]
+    fn should_read_opml_plan_with_targets() {
+        let source = std::fs::read_to_string(opml_get_book_plan()).unwrap();
+
+        let mut freeout = Freeout::new(source, None);
+        freeout.process(&OpmlReader::default()).unwrap();
+        let outline = freeout.get_outline();
+
+        assert_eq!(outline.items.len(), 1);
+
+        let book = &outline.items[0];
+        assert_eq!(book.block.title, "My Book");
+        assert_eq!(book.subitems.len(), 3);
+
+        let chapter_1 = &book.subitems[0];
+        assert_eq!(chapter_1.block.title, "Chapter 1: Cats & Dogs");
+        assert_eq!(chapter_1.block.depth, 2);
+        assert_eq!(
+            chapter_1.block.note.as_deref(),
+            Some("Open with a \"hook\".\nKeep it short.")
+        );
+
+        // the explicit target of chapter 2 is kept, and the rest is distributed to chapter 1
+        let get_target_words =
+            |item: &OutlineItemRef| item.block.aggregate_stats.target.clone().unwrap().words;
+        assert_eq!(get_target_words(chapter_1), 7000);
+        assert_eq!(get_target_words(&chapter_1.subitems[1]), 3500);
+
+        // the appendix is excluded from the distribution
+        let appendix = &book.subitems[2];
+        assert!(appendix.block.exclude);
+        assert!(appendix.block.aggregate_stats.target.is_none());
+    }
+
+    #[test]
+    fn should_round_trip_opml() {
+        let source = std::fs::read_to_string(opml_get_book_plan()).unwrap();
+
+        let mut freeout = Freeout::new(source, None);
+        freeout.process(&OpmlReader::default()).unwrap();
+        let outline = freeout.to_outline();
+
+        let writer = OpmlWriter {
+            title: Some("My <Book>".to_string()),
+            ..Default::default()
+        };
+        let opml = writer.write(&outline).unwrap();
+
+        assert!(opml.contains("<title>My &lt;Book&gt;</title>"));
+        assert!(opml.contains("text=\"Chapter 1: Cats &amp; Dogs\""));
+        assert!(opml.contains("_note=\"Open with a &quot;hook&quot;.&#10;Keep it short.\""));
+        assert!(opml.contains("targetWords=\"3000\""));
+        assert!(opml.contains("exclude=\"true\""));
+
+        let mut round_trip = Freeout::new(opml.clone(), None);
+        round_trip.process(&OpmlReader::default()).unwrap();
+
+        assert_eq!(writer.write(&round_trip.to_outline()).unwrap(), opml);
+    }
+}
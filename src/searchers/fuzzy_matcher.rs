@@ -0,0 +1,128 @@
+use crate::searchers::tokenizer::is_cjk;
+
+/// Score of a matched character.
+const MATCH_SCORE: i64 = 16;
+/// Bonus of a matched character following the previous matched one.
+const CONSECUTIVE_BONUS: i64 = 8;
+/// Bonus of a matched character starting a word, e.g. `o` in `Outline`, `my outline` or `myOutline`.
+const WORD_START_BONUS: i64 = 8;
+/// Penalty of each character skipped between two matched ones, or before the first one.
+const GAP_PENALTY: i64 = 1;
+
+/// # Fuzzy match a query against a text
+///
+/// The characters of the query, spaces aside, must appear in the text in order, ignoring case, e.g. `gsta` matches
+/// `Getting Started`. Returns the score of the best alignment, and the indices of the matched characters of the
+/// text, or `None` if the text does not match.
+///
+/// Consecutive characters and characters starting words score more, and skipped characters less, so `gs` ranks
+/// `Getting Started` above `Settings`.
+pub fn fuzzy_match(query: &str, text: &str) -> Option<(i64, Vec<usize>)> {
+    let query: Vec<char> = query
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .flat_map(char::to_lowercase)
+        .collect();
+    let chars: Vec<char> = text.chars().collect();
+    if query.is_empty() {
+        return Some((0, vec![]));
+    }
+    if query.len() > chars.len() {
+        return None;
+    }
+
+    let lowercase: Vec<char> = chars
+        .iter()
+        .map(|c| c.to_lowercase().next().unwrap_or(*c))
+        .collect();
+    // the best score of the query up to each character, matched at each index of the text, and the index of the
+    // previous matched character
+    let mut scores: Vec<Vec<Option<(i64, usize)>>> = vec![vec![None; chars.len()]; query.len()];
+
+    for (i, query_char) in query.iter().enumerate() {
+        // the best score of the previous character of the query matched before the previous index, plus the penalty
+        // of the characters before it, so that the penalty of the gap to the current index is `GAP_PENALTY * index`
+        let mut best_before: Option<(i64, usize)> = None;
+        for index in i..chars.len() {
+            if i > 0 && index >= 2 {
+                if let Some((score, _)) = scores[i - 1][index - 2] {
+                    let candidate = score + GAP_PENALTY * (index as i64 - 1);
+                    if best_before.is_none_or(|(best, _)| candidate > best) {
+                        best_before = Some((candidate, index - 2));
+                    }
+                }
+            }
+            if lowercase[index] != *query_char {
+                continue;
+            }
+
+            let bonus = MATCH_SCORE + get_word_start_bonus(&chars, index);
+            scores[i][index] = if i == 0 {
+                Some((bonus - GAP_PENALTY * index as i64, index))
+            } else {
+                let consecutive = scores[i - 1]
+                    .get(index.wrapping_sub(1))
+                    .copied()
+                    .flatten()
+                    .map(|(score, _)| (score + CONSECUTIVE_BONUS, index - 1));
+                let gapped = best_before
+                    .map(|(score, previous)| (score - GAP_PENALTY * index as i64, previous));
+                match (consecutive, gapped) {
+                    (Some(a), Some(b)) => Some(if a.0 >= b.0 { a } else { b }),
+                    (a, b) => a.or(b),
+                }
+                .map(|(score, previous)| (score + bonus, previous))
+            };
+        }
+    }
+
+    let (mut index, (score, _)) = scores[query.len() - 1]
+        .iter()
+        .enumerate()
+        .filter_map(|(index, cell)| cell.map(|cell| (index, cell)))
+        .max_by_key(|(index, (score, _))| (*score, std::cmp::Reverse(*index)))?;
+    let mut indices = vec![index];
+    for i in (1..query.len()).rev() {
+        index = scores[i][index].unwrap().1;
+        indices.push(index);
+    }
+    indices.reverse();
+    Some((score, indices))
+}
+
+fn get_word_start_bonus(chars: &[char], index: usize) -> i64 {
+    let c = chars[index];
+    let starts_word = match index.checked_sub(1).map(|previous| chars[previous]) {
+        None => true,
+        Some(previous) => {
+            !previous.is_alphanumeric()
+                || (previous.is_lowercase() && c.is_uppercase())
+                || is_cjk(c)
+        }
+    };
+    if starts_word {
+        WORD_START_BONUS
+    } else {
+        0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::searchers::fuzzy_matcher::fuzzy_match;
+
+    #[test]
+    fn should_fuzzy_match_titles() {
+        assert_eq!(
+            fuzzy_match("gsta", "Getting Started").unwrap().1,
+            vec![0, 8, 9, 10]
+        );
+        assert_eq!(fuzzy_match("OL", "myOutline").unwrap().1, vec![2, 5]);
+        assert_eq!(fuzzy_match("sx", "Settings"), None);
+
+        let score = |text: &str| fuzzy_match("gs", text).unwrap().0;
+        assert!(score("Getting Started") > score("Settings"));
+        assert!(score("Gs") > score("Getting Started"));
+        assert_eq!(fuzzy_match("", "Anything"), Some((0, vec![])));
+    }
+}
@@ -0,0 +1,11 @@
+//! # Searchers
+//!
+//! Search the blocks of a document or a project with a `SearchIndex`: fuzzy matching of titles, e.g. to jump to a
+//! section, and full-text search of titles and content ranked with BM25, with snippets and the positions of the
+//! matches.
+//!
+//! Text is split into words at the Unicode word boundaries, with pairs of adjacent ideographs and kana as words, so
+//! that Chinese and Japanese can be searched without spaces.
+pub mod fuzzy_matcher;
+pub mod search_index;
+pub mod tokenizer;
@@ -0,0 +1,472 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+use itertools::Itertools;
+
+use crate::entities::core::block::compute_hash;
+use crate::entities::core::block_range::BlockRange;
+use crate::entities::core::freeout::Freeout;
+use crate::entities::core::position::Position;
+use crate::entities::core::position_encoding::PositionEncoding;
+use crate::entities::search::search_hit::{SearchHit, TitleMatch};
+use crate::projects::project::Project;
+use crate::searchers::fuzzy_matcher::fuzzy_match;
+use crate::searchers::tokenizer::tokenize;
+
+/// Saturation of the frequency of a word in BM25.
+const K1: f64 = 1.2;
+/// Normalization of the frequency of a word by the length of the block in BM25.
+const B: f64 = 0.75;
+/// Weight of a word of a title, against 1 for a word of the content.
+const TITLE_WEIGHT: f64 = 2.0;
+/// Characters of a snippet before its first match.
+const SNIPPET_CONTEXT: usize = 20;
+/// Characters of a snippet.
+const SNIPPET_LENGTH: usize = 80;
+const ELLIPSIS: &str = "…";
+
+/// Weighted frequencies of the words of a block.
+type Terms = HashMap<String, f64>;
+
+/// The text of a block to index.
+struct BlockText {
+    block_id: usize,
+    title: String,
+    /// The own content of the block, markup included if it is read from the source.
+    content: String,
+    source_path: Option<PathBuf>,
+    /// The position of the content in the source, if it is read from the source.
+    content_start: Option<Position>,
+    position_encoding: PositionEncoding,
+}
+
+impl BlockText {
+    /// `Block::hash` only covers the title, so the index hashes the text it indexes.
+    fn get_hash(&self) -> u64 {
+        compute_hash(&format!("{}\0{}", self.title, self.content))
+    }
+
+    /// Weighted frequencies of the words of the title and content, and their sum.
+    fn get_terms(&self) -> (Terms, f64) {
+        let mut terms = Terms::new();
+        for token in tokenize(&self.title) {
+            *terms.entry(token.text).or_default() += TITLE_WEIGHT;
+        }
+        for token in tokenize(&self.content) {
+            *terms.entry(token.text).or_default() += 1.0;
+        }
+        let length = terms.values().sum();
+        (terms, length)
+    }
+
+    /// The position in the source of an offset of the content.
+    fn get_position(&self, start: &Position, offset: usize) -> Position {
+        let prefix = &self.content[..offset];
+        match prefix.rfind('\n') {
+            Some(line_start) => Position {
+                line: start.line + prefix.matches('\n').count(),
+                column: self.position_encoding.get_column(&prefix[line_start + 1..]),
+                offset: start.offset + offset,
+            },
+            None => Position {
+                line: start.line,
+                column: start.column + self.position_encoding.get_column(prefix),
+                offset: start.offset + offset,
+            },
+        }
+    }
+}
+
+struct IndexedBlock {
+    text: BlockText,
+    hash: u64,
+    terms: Terms,
+    length: f64,
+}
+
+/// # Search Index
+///
+/// An index of the titles and own content of the blocks of a `Freeout` or a `Project`, for fuzzy title matching,
+/// e.g. in a "jump to section" picker, and full-text search ranked with BM25.
+///
+/// Words are split by `tokenize`, so Chinese and Japanese are searchable without spaces. Words of titles weigh more
+/// than words of the content.
+///
+/// The index is updated with `update`: only the blocks whose title or content changed are tokenized again, blocks
+/// that only moved keep their words.
+#[derive(Default)]
+pub struct SearchIndex {
+    blocks: HashMap<usize, IndexedBlock>,
+    /// The blocks holding each word, with its weighted frequency in the block.
+    postings: HashMap<String, HashMap<usize, f64>>,
+    total_length: f64,
+}
+
+impl SearchIndex {
+    pub fn new(freeout: &Freeout) -> Self {
+        let mut index = Self::default();
+        index.update(freeout);
+        index
+    }
+
+    pub fn from_project(project: &Project) -> Self {
+        let mut index = Self::default();
+        index.update_project(project);
+        index
+    }
+
+    /// # Update the index with the processed blocks of a document
+    ///
+    /// Returns the number of blocks that were tokenized again.
+    pub fn update(&mut self, freeout: &Freeout) -> usize {
+        self.update_texts(get_block_texts(freeout, 1, freeout.get_path()))
+    }
+
+    /// # Update the index with the blocks of a project
+    ///
+    /// Returns the number of blocks that were tokenized again.
+    pub fn update_project(&mut self, project: &Project) -> usize {
+        // the entries of the manifest only have a title
+        let mut texts: Vec<BlockText> = (1..=project.blocks.len())
+            .filter(|block_id| project.get_file_block(*block_id).is_none())
+            .map(|block_id| BlockText {
+                block_id,
+                title: project.blocks[&block_id].title.clone(),
+                content: String::new(),
+                source_path: None,
+                content_start: None,
+                position_encoding: PositionEncoding::default(),
+            })
+            .collect();
+        for file in &project.files {
+            texts.extend(get_block_texts(
+                &file.freeout,
+                file.first_block_id,
+                Some(&file.path),
+            ));
+        }
+        self.update_texts(texts)
+    }
+
+    fn update_texts(&mut self, texts: Vec<BlockText>) -> usize {
+        let mut previous = std::mem::take(&mut self.blocks);
+        let mut stale_blocks = vec![];
+        let mut changed_texts = vec![];
+        for text in texts {
+            let hash = text.get_hash();
+            match previous.remove(&text.block_id) {
+                Some(block) if block.hash == hash => {
+                    // the text is the same, but its position may have changed
+                    self.blocks
+                        .insert(text.block_id, IndexedBlock { text, ..block });
+                }
+                block => {
+                    stale_blocks.extend(block);
+                    changed_texts.push((text, hash));
+                }
+            }
+        }
+        stale_blocks.extend(previous.into_values());
+
+        // the words of the blocks that changed, moved or were removed, by the hash of their text
+        let mut stale_terms: HashMap<u64, Vec<(Terms, f64)>> = HashMap::new();
+        for block in stale_blocks {
+            for term in block.terms.keys() {
+                if let Some(postings) = self.postings.get_mut(term) {
+                    postings.remove(&block.text.block_id);
+                    if postings.is_empty() {
+                        self.postings.remove(term);
+                    }
+                }
+            }
+            self.total_length -= block.length;
+            stale_terms
+                .entry(block.hash)
+                .or_default()
+                .push((block.terms, block.length));
+        }
+
+        let mut tokenized_count = 0;
+        for (text, hash) in changed_texts {
+            let (terms, length) = match stale_terms.get_mut(&hash).and_then(Vec::pop) {
+                Some(terms) => terms,
+                None => {
+                    tokenized_count += 1;
+                    text.get_terms()
+                }
+            };
+            for (term, frequency) in &terms {
+                self.postings
+                    .entry(term.clone())
+                    .or_default()
+                    .insert(text.block_id, *frequency);
+            }
+            self.total_length += length;
+            self.blocks.insert(
+                text.block_id,
+                IndexedBlock {
+                    text,
+                    hash,
+                    terms,
+                    length,
+                },
+            );
+        }
+        tokenized_count
+    }
+
+    /// # Search the titles and content
+    ///
+    /// Returns the `limit` blocks with the best BM25 score for the words of the query, best first, with a snippet
+    /// and the positions of the matched words.
+    pub fn search(&self, query: &str, limit: usize) -> Vec<SearchHit> {
+        let query_terms: Vec<String> = tokenize(query)
+            .into_iter()
+            .map(|token| token.text)
+            .unique()
+            .collect();
+        if self.blocks.is_empty() {
+            return vec![];
+        }
+
+        let block_count = self.blocks.len() as f64;
+        let average_length = (self.total_length / block_count).max(1.0);
+        let mut scores: HashMap<usize, f64> = HashMap::new();
+        for term in &query_terms {
+            let Some(postings) = self.postings.get(term) else {
+                continue;
+            };
+            let document_frequency = postings.len() as f64;
+            let idf =
+                ((block_count - document_frequency + 0.5) / (document_frequency + 0.5) + 1.0).ln();
+            for (block_id, frequency) in postings {
+                let length = self.blocks[block_id].length;
+                *scores.entry(*block_id).or_default() += idf * frequency * (K1 + 1.0)
+                    / (frequency + K1 * (1.0 - B + B * length / average_length));
+            }
+        }
+
+        scores
+            .into_iter()
+            .sorted_by(|a, b| b.1.total_cmp(&a.1).then(a.0.cmp(&b.0)))
+            .take(limit)
+            .map(|(block_id, score)| self.get_hit(block_id, score, &query_terms))
+            .collect()
+    }
+
+    /// # Find blocks by title
+    ///
+    /// Returns the `limit` blocks whose titles best match the query with `fuzzy_match`, best first. An empty query
+    /// matches every block, in document order.
+    pub fn find_titles(&self, query: &str, limit: usize) -> Vec<TitleMatch> {
+        self.blocks
+            .iter()
+            .filter_map(|(block_id, block)| {
+                fuzzy_match(query, &block.text.title).map(|(score, indices)| TitleMatch {
+                    block_id: *block_id,
+                    title: block.text.title.clone(),
+                    score,
+                    indices,
+                })
+            })
+            .sorted_by(|a, b| b.score.cmp(&a.score).then(a.block_id.cmp(&b.block_id)))
+            .take(limit)
+            .collect()
+    }
+
+    fn get_hit(&self, block_id: usize, score: f64, query_terms: &[String]) -> SearchHit {
+        let text = &self.blocks[&block_id].text;
+        let get_matches = |text: &str| {
+            merge_ranges(
+                tokenize(text)
+                    .into_iter()
+                    .filter(|token| query_terms.contains(&token.text))
+                    .map(|token| token.offsets)
+                    .collect(),
+            )
+        };
+
+        let matches = get_matches(&text.content);
+        let (snippet, highlights) = match matches.first() {
+            Some(first_match) => get_snippet(&text.content, first_match.start, &matches),
+            None => (text.title.clone(), get_matches(&text.title)),
+        };
+        let ranges = match &text.content_start {
+            Some(start) => matches
+                .iter()
+                .map(|offsets| BlockRange {
+                    start: text.get_position(start, offsets.start),
+                    end: text.get_position(start, offsets.end),
+                })
+                .collect(),
+            None => vec![],
+        };
+
+        SearchHit {
+            block_id,
+            source_path: text.source_path.clone(),
+            score,
+            snippet,
+            highlights,
+            ranges,
+        }
+    }
+}
+
+/// The titles and own content of the blocks of a document, with ids starting at `first_block_id`.
+fn get_block_texts(
+    freeout: &Freeout,
+    first_block_id: usize,
+    path: Option<&Path>,
+) -> Vec<BlockText> {
+    (1..=freeout.blocks.len())
+        .map(|block_id| {
+            let block = &freeout.blocks[&block_id];
+            let (content, content_start) = match freeout.get_own_content(block_id) {
+                // blocks included from another file have their positions in that file
+                Some(content) => (
+                    content.to_string(),
+                    block
+                        .source_path
+                        .is_none()
+                        .then(|| freeout.get_encoded_position(block.header_range.end.offset)),
+                ),
+                None => (
+                    freeout
+                        .get_content(block_id)
+                        .unwrap_or_default()
+                        .into_owned(),
+                    None,
+                ),
+            };
+            BlockText {
+                block_id: first_block_id + block_id - 1,
+                title: block.title.clone(),
+                content,
+                source_path: block.source_path.clone().or(path.map(Path::to_path_buf)),
+                content_start,
+                position_encoding: freeout.opt.position_encoding,
+            }
+        })
+        .collect()
+}
+
+/// Merge overlapping ranges, e.g. of a Chinese word and of its ideographs.
+fn merge_ranges(ranges: Vec<Range<usize>>) -> Vec<Range<usize>> {
+    let mut merged: Vec<Range<usize>> = vec![];
+    for range in ranges.into_iter().sorted_by_key(|range| range.start) {
+        match merged.last_mut() {
+            Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+            _ => merged.push(range),
+        }
+    }
+    merged
+}
+
+/// An excerpt of a text around an offset, on a single line, with the ranges of the matches in it.
+fn get_snippet(text: &str, offset: usize, matches: &[Range<usize>]) -> (String, Vec<Range<usize>>) {
+    let start = text[..offset]
+        .char_indices()
+        .rev()
+        .nth(SNIPPET_CONTEXT - 1)
+        .map_or(0, |(index, _)| index);
+    let end = text[start..]
+        .char_indices()
+        .nth(SNIPPET_LENGTH)
+        .map_or(text.len(), |(index, _)| start + index);
+    let excerpt = &text[start..end];
+    let start = start + excerpt.len() - excerpt.trim_start().len();
+    let end = end - (excerpt.len() - excerpt.trim_end().len());
+
+    let prefix = if text[..start].trim().is_empty() {
+        ""
+    } else {
+        ELLIPSIS
+    };
+    let suffix = if text[end..].trim().is_empty() {
+        ""
+    } else {
+        ELLIPSIS
+    };
+    let snippet = format!(
+        "{}{}{}",
+        prefix,
+        text[start..end].replace(['\r', '\n'], " "),
+        suffix
+    );
+    let highlights = matches
+        .iter()
+        .filter(|range| range.start >= start && range.end <= end)
+        .map(|range| range.start - start + prefix.len()..range.end - start + prefix.len())
+        .collect();
+    (snippet, highlights)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::core::freeout::Freeout;
+    use crate::entities::project::manifest::ProjectManifest;
+    use crate::projects::project::Project;
+    use crate::readers::markdown::MarkdownReader;
+    use crate::searchers::search_index::SearchIndex;
+    use crate::utils::test_utils::test_utils::get_fixture_folder;
+
+    fn get_freeout(text: &str) -> Freeout {
+        let mut freeout = Freeout::new(text.to_string(), None);
+        freeout.process(&MarkdownReader::default()).unwrap();
+        freeout
+    }
+
+    #[test]
+    fn should_search_titles_and_content() {
+        let text = "# Getting Started\n\nInstall the cli, then run it.\n\n\
+            # Settings\n\nThe settings of the cli.\nSee the cli reference for every setting of the cli.\n\n\
+            # 数据库\n\n连接数据库的方法。\n";
+        let mut freeout = get_freeout(text);
+        let mut index = SearchIndex::new(&freeout);
+
+        let hits = index.search("CLI settings", 10);
+        let block_ids: Vec<usize> = hits.iter().map(|hit| hit.block_id).collect();
+        assert_eq!(block_ids, vec![2, 1]);
+        assert_eq!(
+            hits[0].highlight("[", "]"),
+            "The [settings] of the [cli]. See the [cli] reference for every setting of the [cli]."
+        );
+        assert_eq!(hits[0].ranges[0].start.line, 6);
+        assert_eq!(hits[0].ranges[2].start.line, 7);
+        assert_eq!(hits[0].ranges[2].start.column, 8);
+
+        let hits = index.search("数据", 10);
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].highlight("[", "]"), "连接[数据]库的方法。");
+        assert!(index.search("数字", 10)[0].score < hits[0].score);
+
+        let titles = index.find_titles("gs", 10);
+        let block_ids: Vec<usize> = titles.iter().map(|title| title.block_id).collect();
+        assert_eq!(block_ids, vec![1, 2]);
+        assert_eq!(titles[0].indices, vec![0, 8]);
+
+        // only the new and edited blocks are tokenized again
+        freeout = get_freeout(&format!(
+            "# Intro\n\nHello.\n\n{}",
+            text.replace("run it", "run it with a database")
+        ));
+        assert_eq!(index.update(&freeout), 2);
+        let hits = index.search("database", 10);
+        assert_eq!(hits[0].block_id, 2);
+        assert_eq!(hits[0].ranges[0].start.line, 6);
+        assert_eq!(index.search("reference", 10)[0].ranges[0].start.line, 11);
+        assert_eq!(index.update(&freeout), 0);
+
+        let book_folder = get_fixture_folder().join("book");
+        let manifest = ProjectManifest::Summary(book_folder.join("SUMMARY.md"));
+        let project = Project::open(&manifest, None).unwrap();
+        let hits = SearchIndex::from_project(&project).search("seven", 10);
+        assert_eq!(
+            hits[0].source_path,
+            Some(book_folder.join("part_1").join("chapter_2.md"))
+        );
+        assert_eq!(hits[0].ranges[0].start.line, 2);
+    }
+}
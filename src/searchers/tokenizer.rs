@@ -0,0 +1,93 @@
+use std::ops::Range;
+
+use unicode_segmentation::UnicodeSegmentation;
+
+/// A word of a text, lowercased, with its byte range in the text.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Token {
+    pub text: String,
+    pub offsets: Range<usize>,
+}
+
+/// # Tokenize a text
+///
+/// Splits a text into lowercased words at the Unicode word boundaries, dropping spaces and punctuation.
+///
+/// Chinese and Japanese are written without spaces, so each ideograph or kana is a word of its own. Pairs of adjacent
+/// ones are words as well, so that a search for `数据库` ranks the blocks with `数据` and `据库` above the blocks merely
+/// holding the same characters.
+pub fn tokenize(text: &str) -> Vec<Token> {
+    let mut tokens: Vec<Token> = vec![];
+    // the previous word, if it is Chinese or Japanese
+    let mut previous_cjk: Option<Token> = None;
+
+    for (start, word) in text.unicode_word_indices() {
+        let token = Token {
+            text: word.to_lowercase(),
+            offsets: start..start + word.len(),
+        };
+        let cjk = word.chars().all(is_cjk);
+        if let Some(previous) = previous_cjk
+            .take()
+            .filter(|previous| cjk && previous.offsets.end == start)
+        {
+            tokens.push(Token {
+                text: format!("{}{}", previous.text, token.text),
+                offsets: previous.offsets.start..token.offsets.end,
+            });
+        }
+        if cjk {
+            previous_cjk = Some(token.clone());
+        }
+        tokens.push(token);
+    }
+    tokens
+}
+
+/// Whether a character is a Chinese ideograph or a Japanese kana.
+pub fn is_cjk(c: char) -> bool {
+    matches!(
+        c,
+        '\u{3040}'..='\u{30FF}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{20000}'..='\u{2FA1F}'
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::searchers::tokenizer::tokenize;
+
+    #[test]
+    fn should_tokenize_words_and_ideographs() {
+        let words = |text: &str| {
+            tokenize(text)
+                .into_iter()
+                .map(|token| (token.text, token.offsets))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            words("Hello, World's end!"),
+            vec![
+                ("hello".to_string(), 0..5),
+                ("world's".to_string(), 7..14),
+                ("end".to_string(), 15..18),
+            ]
+        );
+        // each ideograph is 3 bytes
+        assert_eq!(
+            words("数据库 DB"),
+            vec![
+                ("数".to_string(), 0..3),
+                ("数据".to_string(), 0..6),
+                ("据".to_string(), 3..6),
+                ("据库".to_string(), 3..9),
+                ("库".to_string(), 6..9),
+                ("db".to_string(), 10..12),
+            ]
+        );
+    }
+}
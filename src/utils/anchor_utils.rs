@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+
+use crate::entities::core::block::Block;
+
+pub struct AnchorUtils {}
+
+impl AnchorUtils {
+    /// # Convert a title into an anchor
+    ///
+    /// Follows the GitHub flavor: lowercase the title, drop punctuation, and replace spaces with hyphens.
+    ///
+    /// # Example
+    ///
+    /// `"Chapter 1: Methods & Data"` -> `"chapter-1-methods--data"`
+    pub fn slugify(title: &str) -> String {
+        title
+            .trim()
+            .to_lowercase()
+            .chars()
+            .filter_map(|c| match c {
+                ' ' => Some('-'),
+                '-' | '_' => Some(c),
+                c if c.is_alphanumeric() => Some(c),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// # Get the anchors of blocks
+    ///
+    /// Blocks are expected to be in document order. Duplicate anchors get a numbered suffix, e.g. `notes`, `notes-1`, `notes-2`.
+    pub fn get_anchors<'a>(blocks: impl IntoIterator<Item = &'a Block>) -> HashMap<usize, String> {
+        let mut used_anchors: HashMap<String, usize> = HashMap::new();
+        let mut anchors = HashMap::new();
+
+        for block in blocks {
+            let slug = Self::slugify(&block.title);
+            let anchor = match used_anchors.get(&slug) {
+                None => slug.clone(),
+                Some(count) => format!("{}-{}", slug, count),
+            };
+            *used_anchors.entry(slug).or_insert(0) += 1;
+            anchors.insert(block.id, anchor);
+        }
+
+        anchors
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::core::block::Block;
+    use crate::utils::anchor_utils::AnchorUtils;
+
+    #[test]
+    fn test_slugify() {
+        assert_eq!(
+            AnchorUtils::slugify("Chapter 1: Methods & Data"),
+            "chapter-1-methods--data"
+        );
+        assert_eq!(AnchorUtils::slugify("snake_case-Title"), "snake_case-title");
+        assert_eq!(AnchorUtils::slugify("子标题1（草稿）"), "子标题1草稿");
+    }
+
+    #[test]
+    fn test_get_anchors_with_duplicates() {
+        let blocks: Vec<Block> = ["Notes", "Intro", "Notes", "Notes"]
+            .iter()
+            .enumerate()
+            .map(|(index, title)| Block {
+                id: index + 1,
+                title: title.to_string(),
+                ..Default::default()
+            })
+            .collect();
+
+        let anchors = AnchorUtils::get_anchors(&blocks);
+
+        assert_eq!(anchors[&1], "notes");
+        assert_eq!(anchors[&2], "intro");
+        assert_eq!(anchors[&3], "notes-1");
+        assert_eq!(anchors[&4], "notes-2");
+    }
+}
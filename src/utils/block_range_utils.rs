@@ -2,7 +2,6 @@ use unicode_segmentation::UnicodeSegmentation;
 use crate::entities::core::block_range::BlockRange;
 use crate::entities::core::position::Position;
 
-const BACKTRACK_BYTES: usize = 12;
 pub struct PositionUtils {
 
 }
@@ -1,14 +1,28 @@
 use crate::entities::core::block::{Block, Blocks};
 use crate::entities::core::block_range::BlockRange;
+use crate::entities::core::block_tree::BlockTree;
 use crate::entities::core::position::Position;
+use crate::entities::query::selector::Selector;
 use crate::utils::block_range_utils::PositionUtils;
 use crate::utils::text_utils::TextUtils;
-use itertools::Itertools;
-use std::collections::HashMap;
 
 pub struct BlockUtils {}
 
 impl BlockUtils {
+    /// Add a block after the last block, as the last child of its parent, and get its new id.
+    ///
+    /// Adding blocks in document order keeps the ids continuous from 1, and the children of each block in order.
+    pub fn append_block(blocks: &mut Blocks, mut block: Block, parent_id: Option<usize>) -> usize {
+        let block_id = blocks.len() + 1;
+        block.id = block_id;
+        block.parent_id = parent_id;
+        if let Some(parent) = parent_id.and_then(|parent_id| blocks.get_mut(&parent_id)) {
+            parent.children_ids.push(block_id);
+        }
+        blocks.insert(block_id, block);
+        block_id
+    }
+
     /// # Populate the block range
     ///
     /// This operation finds the target block (parent), and its last recursive children (e.g. grandchild) before the next sibling or uncle or the parent,and merge their ranges.
@@ -29,78 +43,94 @@ impl BlockUtils {
     /// # Header 3
     ///
     /// ```
+    ///
+    /// The ranges are computed in a single pass over the `BlockTree` of the blocks, built for the call. See
+    /// `populate_block_ranges_in_tree` to reuse a tree, e.g. `Freeout::tree`.
     pub fn populate_block_ranges(blocks: &mut Blocks, text: &str) {
-        let mut populated_block_range: HashMap<usize, BlockRange> = HashMap::new();
-        for (block_id, block) in blocks.iter() {
-            let mut block_range_end_position: Option<Position> = None;
-            let next_sibling_or_uncle_id =
-                Self::get_next_sibling_or_uncle_id(blocks, block_id, &block.depth);
-
-            if next_sibling_or_uncle_id.is_none() {
-                // if there is no next sibling or uncle, then the end position is the end of the text
-                block_range_end_position = Some(TextUtils::get_end_position(text));
-            } else {
-                let next_sibling_or_uncle = blocks.get(&next_sibling_or_uncle_id.unwrap()).unwrap();
-                // use the prior position of the start of the next sibling or uncle's header_range
-                block_range_end_position = Some(next_sibling_or_uncle.header_range.start.clone());
-            }
-
-            let new_block_range = BlockRange {
-                start: block.header_range.start.clone(),
-                end: block_range_end_position.unwrap(),
-            };
+        let tree = BlockTree::new(blocks);
+        Self::populate_block_ranges_in_tree(blocks, &tree, text);
+    }
 
-            populated_block_range.insert(*block_id, new_block_range);
+    /// # Populate the block range with the tree of the blocks
+    ///
+    /// See `populate_block_ranges`.
+    pub fn populate_block_ranges_in_tree(blocks: &mut Blocks, tree: &BlockTree, text: &str) {
+        // the end of the text is shared by every last block, e.g. the last block of each depth
+        let text_end_position = TextUtils::get_end_position(text);
+        let mut header_starts = vec![None; tree.len() + 1];
+        for block in blocks.values() {
+            header_starts[block.id] = Some(&block.header_range.start);
         }
 
-        // update the block range
-        for (block_id, range) in populated_block_range.into_iter() {
-            blocks.get_mut(&block_id).unwrap().block_range = Some(range);
+        // the end of each block range is settled before the blocks are updated
+        let block_range_ends: Vec<Position> = (0..=tree.len())
+            .map(|block_id| {
+                match tree
+                    .get(block_id)
+                    .and_then(|node| node.next_sibling_or_uncle)
+                {
+                    // if there is no next sibling or uncle, then the end position is the end of the text
+                    None => text_end_position.clone(),
+                    // use the prior position of the start of the next sibling or uncle's header_range
+                    Some(next_sibling_or_uncle_id) => PositionUtils::get_immediate_prior_position(
+                        text,
+                        header_starts[next_sibling_or_uncle_id].unwrap(),
+                    ),
+                }
+            })
+            .collect();
+
+        for block in blocks.values_mut() {
+            block.block_range = Some(BlockRange {
+                start: block.header_range.start.clone(),
+                end: block_range_ends[block.id].clone(),
+            });
         }
     }
+
+    /// # Get the parent block id by current block id
+    ///
+    /// The tree of the blocks is built for the call, and the depth is no longer needed. See
+    /// `get_immediate_parent_in_tree` to reuse a tree.
     pub fn get_immediate_parent(
         blocks: &Blocks,
         current_block_id: &usize,
-        current_block_depth: &usize,
+        _current_block_depth: &usize,
     ) -> Option<usize> {
-        blocks
-            .iter()
-            .filter(|(block_id, block)| {
-                // iterate over the depth in reverse
-                (block.depth < *current_block_depth) && *block_id < current_block_id
-            })
-            // get the one with highest id, because parent might not necessarily be only 1 depth above but must be the closest one
-            .sorted_by(|(_, block_a), (_, block_b)| block_b.id.cmp(&block_a.id))
-            .map(|(block_id, _)| *block_id)
-            .next()
+        Self::get_immediate_parent_in_tree(&BlockTree::new(blocks), current_block_id)
     }
 
+    pub fn get_immediate_parent_in_tree(
+        tree: &BlockTree,
+        current_block_id: &usize,
+    ) -> Option<usize> {
+        tree.get(*current_block_id)?.parent
+    }
+
+    /// # Get the root block id by current block id
+    ///
+    /// The tree of the blocks is built for the call, and the depth is no longer needed. See `get_root_parent_in_tree`
+    /// to reuse a tree.
     pub fn get_root_parent(
         blocks: &Blocks,
         current_block_id: &usize,
-        current_block_depth: &usize,
+        _current_block_depth: &usize,
     ) -> Option<usize> {
-        let mut current_block_id = *current_block_id;
-        let mut current_block_depth = *current_block_depth;
-        let mut root_parent_id = None;
-        while current_block_depth > 1 {
-            root_parent_id =
-                Self::get_immediate_parent(blocks, &current_block_id, &current_block_depth);
-            if let Some(root_parent_id) = root_parent_id {
-                current_block_id = root_parent_id;
-                current_block_depth -= 1;
-            } else {
-                break;
-            }
-        }
-        root_parent_id
+        Self::get_root_parent_in_tree(&BlockTree::new(blocks), current_block_id)
+    }
+
+    pub fn get_root_parent_in_tree(tree: &BlockTree, current_block_id: &usize) -> Option<usize> {
+        std::iter::successors(
+            Self::get_immediate_parent_in_tree(tree, current_block_id),
+            |parent_id| Self::get_immediate_parent_in_tree(tree, parent_id),
+        )
+        .last()
     }
+    /// # Get the ids of the blocks with a title
+    ///
+    /// In document order. See `Selector` for richer queries.
     pub fn get_block_ids_by_title(blocks: &Blocks, title: &str) -> Vec<usize> {
-        blocks
-            .iter()
-            .filter(|(_, block)| block.title == title)
-            .map(|(block_id, _)| *block_id)
-            .collect()
+        Selector::builder().title(title).build().select(blocks)
     }
     /// # check if the block id exists
     pub fn has_block_id(blocks: &Blocks, block_id: &usize) -> bool {
@@ -125,52 +155,61 @@ impl BlockUtils {
     }
 
     /// # Get the next sibling block id by current block id and depth
+    ///
+    /// The tree of the blocks is built for the call, and the depth is no longer needed. See
+    /// `get_next_sibling_id_in_tree` to reuse a tree.
     pub fn get_next_sibling_id(
         blocks: &Blocks,
         current_block_id: &usize,
-        current_block_depth: &usize,
+        _current_block_depth: &usize,
     ) -> Option<usize> {
-        blocks
-            .iter()
-            .filter(|(block_id, _)| *block_id > current_block_id)
-            .filter(|(block_id, block)| block.depth == *current_block_depth)
-            .map(|(block_id, _)| *block_id)
-            .next()
+        Self::get_next_sibling_id_in_tree(&BlockTree::new(blocks), current_block_id)
+    }
+
+    pub fn get_next_sibling_id_in_tree(
+        tree: &BlockTree,
+        current_block_id: &usize,
+    ) -> Option<usize> {
+        tree.get(*current_block_id)?.next_sibling
     }
 
     /// # Get the next sibling or uncle block id by current block id and depth
+    ///
+    /// This is the first block after the subtree of the current block. The tree of the blocks is built for the call,
+    /// and the depth is no longer needed. See `get_next_sibling_or_uncle_id_in_tree` to reuse a tree.
     pub fn get_next_sibling_or_uncle_id(
         blocks: &Blocks,
         current_block_id: &usize,
-        current_block_depth: &usize,
+        _current_block_depth: &usize,
     ) -> Option<usize> {
-        blocks
-            .iter()
-            // id greater than current block id
-            .filter(|(block_id, _)| *block_id > current_block_id)
-            .filter(|(block_id, block)| {
-                // the next sibling or uncle block must have a depth greater than or equal to the current block
-                block.depth <= *current_block_depth
-            })
-            // get the one with lowest id
-            .sorted_by(|(_, block_a), (_, block_b)| block_a.id.cmp(&block_b.id))
-            .map(|(block_id, _)| *block_id)
-            .next()
+        Self::get_next_sibling_or_uncle_id_in_tree(&BlockTree::new(blocks), current_block_id)
+    }
+
+    pub fn get_next_sibling_or_uncle_id_in_tree(
+        tree: &BlockTree,
+        current_block_id: &usize,
+    ) -> Option<usize> {
+        tree.get(*current_block_id)?.next_sibling_or_uncle
     }
 
     /// # Get the last recursive children of the block
     ///
-    /// Looks for the next block whose depth is great or equal to the current block (meaning it's either a sibling or a parent), and then returns the previous block.
+    /// This is the last block of its subtree, i.e. the block before its next sibling or uncle, or the block itself if it has no children.
+    /// The tree of the blocks is built for the call, and the depth is no longer needed. See
+    /// `get_last_recursive_children_id_in_tree` to reuse a tree.
     pub fn get_last_recursive_children_id_and_depth(
         blocks: &Blocks,
         block_id: &usize,
-        block_depth: &usize,
+        _block_depth: &usize,
     ) -> Option<usize> {
-        Self::get_next_sibling_or_uncle_id(blocks, block_id, block_depth).and_then(
-            |next_sibling_or_uncle_id| {
-                Self::get_previous_block_id(blocks, &next_sibling_or_uncle_id.to_string())
-            },
-        )
+        Self::get_last_recursive_children_id_in_tree(&BlockTree::new(blocks), block_id)
+    }
+
+    pub fn get_last_recursive_children_id_in_tree(
+        tree: &BlockTree,
+        block_id: &usize,
+    ) -> Option<usize> {
+        tree.get_last_descendant(*block_id)
     }
 }
 
@@ -182,6 +221,7 @@ mod tests {
     use tracing_test::traced_test;
 
     use crate::entities::core::block::Block;
+    use crate::entities::core::block_tree::BlockTree;
     use crate::entities::core::freeout::Freeout;
     use crate::readers::markdown::MarkdownReader;
     use crate::utils::block_range_utils::PositionUtils;
@@ -193,7 +233,8 @@ mod tests {
         let grandma = Block {
             id: 1,
             depth: 1,
-            children_ids: vec![2],
+            parent_id: None,
+            children_ids: vec![2, 4, 6],
             title: "grandma".to_string(),
             ..Default::default()
         };
@@ -201,6 +242,7 @@ mod tests {
         let bobo = Block {
             id: 2,
             depth: 2,
+            parent_id: Some(1),
             children_ids: vec![3],
             title: "bobo".to_string(),
             ..Default::default()
@@ -209,38 +251,43 @@ mod tests {
         let tang_ge = Block {
             id: 3,
             depth: 3,
+            parent_id: Some(2),
             children_ids: vec![],
             title: "tang_ge".to_string(),
             ..Default::default()
         };
 
-        let dad = Block {
-            id: 4,
-            depth: 2,
-            children_ids: vec![7, 8, 12],
-            title: "dad".to_string(),
-            ..Default::default()
-        };
-
         let shushu = Block {
-            id: 5,
+            id: 4,
             depth: 2,
-            children_ids: vec![6],
+            parent_id: Some(1),
+            children_ids: vec![5],
             title: "shushu".to_string(),
             ..Default::default()
         };
 
         let tang_di = Block {
-            id: 6,
+            id: 5,
             depth: 3,
+            parent_id: Some(4),
             children_ids: vec![],
             title: "tang_di".to_string(),
             ..Default::default()
         };
 
+        let dad = Block {
+            id: 6,
+            depth: 2,
+            parent_id: Some(1),
+            children_ids: vec![7, 8, 12],
+            title: "dad".to_string(),
+            ..Default::default()
+        };
+
         let my_older_sister = Block {
             id: 7,
             depth: 3,
+            parent_id: Some(6),
             children_ids: vec![],
             title: "my_older_sister".to_string(),
             ..Default::default()
@@ -249,6 +296,7 @@ mod tests {
         let me = Block {
             id: 8,
             depth: 3,
+            parent_id: Some(6),
             children_ids: vec![9, 10],
             title: "me".to_string(),
             ..Default::default()
@@ -257,6 +305,7 @@ mod tests {
         let my_first_child = Block {
             id: 9,
             depth: 4,
+            parent_id: Some(8),
             children_ids: vec![],
             title: "my_first_child".to_string(),
             ..Default::default()
@@ -265,6 +314,7 @@ mod tests {
         let my_second_child = Block {
             id: 10,
             depth: 4,
+            parent_id: Some(8),
             children_ids: vec![11],
             title: "my_second_child".to_string(),
             ..Default::default()
@@ -273,6 +323,7 @@ mod tests {
         let my_second_child_first_grand_child = Block {
             id: 11,
             depth: 5,
+            parent_id: Some(10),
             children_ids: vec![],
             title: "my_second_child_first_grand_child".to_string(),
             ..Default::default()
@@ -281,6 +332,7 @@ mod tests {
         let my_younger_sister = Block {
             id: 12,
             depth: 3,
+            parent_id: Some(6),
             children_ids: vec![],
             title: "my_younger_sister".to_string(),
             ..Default::default()
@@ -290,9 +342,9 @@ mod tests {
             grandma,
             bobo,
             tang_ge,
-            dad,
             shushu,
             tang_di,
+            dad,
             my_older_sister,
             me,
             my_first_child,
@@ -311,9 +363,8 @@ mod tests {
     }
     #[test]
     fn test_get_block_by_relation() {
-        let mut freeout = Freeout::new("fake".to_string(), None);
-
         let blocks = &get_test_blocks();
+        let tree = &BlockTree::new(blocks);
 
         let grandma_id = BlockUtils::get_block_ids_by_title(blocks, "grandma")[0];
 
@@ -329,7 +380,7 @@ mod tests {
         let my_younger_sister_id =
             BlockUtils::get_block_ids_by_title(blocks, "my_younger_sister")[0];
 
-        let next_sibling_id = BlockUtils::get_next_sibling_id(blocks, &my_id, &3);
+        let next_sibling_id = BlockUtils::get_next_sibling_id_in_tree(tree, &my_id);
 
         assert_eq!(next_sibling_id, Some(my_younger_sister_id));
 
@@ -339,7 +390,7 @@ mod tests {
 
         // my second_child's next sibling or uncle should be my sister
         let my_second_child_next_sibling_or_uncle_id =
-            BlockUtils::get_next_sibling_or_uncle_id(blocks, &my_second_child_id, &4);
+            BlockUtils::get_next_sibling_or_uncle_id_in_tree(tree, &my_second_child_id);
 
         assert_eq!(
             my_second_child_next_sibling_or_uncle_id,
@@ -351,7 +402,7 @@ mod tests {
 
         // this should get the next sibling or uncle of my first grand child, which should be my younger sister.
         let my_first_child_next_sibling_or_uncle_id =
-            BlockUtils::get_next_sibling_or_uncle_id(blocks, &my_first_child_id, &4);
+            BlockUtils::get_next_sibling_or_uncle_id_in_tree(tree, &my_first_child_id);
 
         assert_eq!(
             my_first_child_next_sibling_or_uncle_id,
@@ -359,22 +410,58 @@ mod tests {
         );
 
         let my_last_recursive_child_id =
-            BlockUtils::get_last_recursive_children_id_and_depth(blocks, &my_id, &3);
+            BlockUtils::get_last_recursive_children_id_in_tree(tree, &my_id);
 
         assert_eq!(
             my_last_recursive_child_id,
             Some(my_second_child_first_grand_child_id)
         );
 
+        // the last block of the document is the last of the subtrees of its ancestors as well
+        let dad_id = BlockUtils::get_block_ids_by_title(blocks, "dad")[0];
+
+        assert_eq!(
+            BlockUtils::get_last_recursive_children_id_in_tree(tree, &dad_id),
+            Some(my_younger_sister_id)
+        );
+
         let immediate_parent =
-            BlockUtils::get_immediate_parent(blocks, &my_second_child_first_grand_child_id, &5);
+            BlockUtils::get_immediate_parent_in_tree(tree, &my_second_child_first_grand_child_id);
 
         assert_eq!(immediate_parent, Some(my_second_child_id));
 
         let root_parent =
-            BlockUtils::get_root_parent(blocks, &my_second_child_first_grand_child_id, &5);
+            BlockUtils::get_root_parent_in_tree(tree, &my_second_child_first_grand_child_id);
 
         assert_eq!(root_parent, Some(grandma_id));
+
+        // the former signatures build the tree for the call
+        let depth = |block_id: &usize| blocks[block_id].depth;
+        let grand_child_id = &my_second_child_first_grand_child_id;
+        assert_eq!(
+            BlockUtils::get_immediate_parent(blocks, grand_child_id, &depth(grand_child_id)),
+            Some(my_second_child_id)
+        );
+        assert_eq!(
+            BlockUtils::get_root_parent(blocks, grand_child_id, &depth(grand_child_id)),
+            Some(grandma_id)
+        );
+        assert_eq!(
+            BlockUtils::get_next_sibling_id(blocks, &my_id, &depth(&my_id)),
+            Some(my_younger_sister_id)
+        );
+        assert_eq!(
+            BlockUtils::get_next_sibling_or_uncle_id(
+                blocks,
+                &my_first_child_id,
+                &depth(&my_first_child_id)
+            ),
+            Some(my_second_child_id)
+        );
+        assert_eq!(
+            BlockUtils::get_last_recursive_children_id_and_depth(blocks, &dad_id, &depth(&dad_id)),
+            Some(my_younger_sister_id)
+        );
     }
 
     #[traced_test]
@@ -467,7 +554,8 @@ mod tests {
 
         let mut freeout = Freeout::new(markdown, None);
 
-        let outline = freeout.outline(&MarkdownReader::default()).unwrap();
+        freeout.process(&MarkdownReader::default()).unwrap();
+        let outline = freeout.get_outline();
 
         debug!(
             "outline: {:}",
@@ -475,7 +563,7 @@ mod tests {
         );
 
         // let first item
-        let first_item = outline.items.get(0).unwrap();
+        let first_item = outline.items.first().unwrap();
 
         // let first item range
         let first_item_block_range = first_item.block.block_range.as_ref().unwrap();
@@ -4,3 +4,5 @@ pub mod freeout_utils;
 pub mod block_utils;
 pub mod text_utils;
 pub mod block_range_utils;
+pub mod anchor_utils;
+pub mod visit_utils;
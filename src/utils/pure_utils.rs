@@ -1,2 +1 @@
-use crate::entities::core::block_range::BlockRange;
 
@@ -1,15 +1,14 @@
 #[cfg(test)]
+#[allow(clippy::module_inception)]
 pub mod test_utils {
     use std::path::PathBuf;
 
     pub fn get_test_folder() -> PathBuf {
-        let tests_folder = std::env::current_dir().unwrap().join("tests");
-        tests_folder
+        std::env::current_dir().unwrap().join("tests")
     }
 
     pub fn get_fixture_folder() -> PathBuf {
-        let fixture_folder = get_test_folder().join("fixtures");
-        fixture_folder
+        get_test_folder().join("fixtures")
     }
 
     pub fn get_temp_folder() -> PathBuf {
@@ -22,25 +21,29 @@ pub mod test_utils {
     }
 
     pub fn get_markdown_folder() -> PathBuf {
-        let markdown_folder = get_fixture_folder().join("markdown");
-        markdown_folder
+        get_fixture_folder().join("markdown")
     }
 
 
     pub fn md_get_simple_eng() -> PathBuf {
-        let markdown_file = get_markdown_folder().join("simple_eng.md");
-        markdown_file
+        get_markdown_folder().join("simple_eng.md")
     }
 
     pub fn md_get_minimal_eng() -> PathBuf {
-        let markdown_file = get_markdown_folder().join("minimal_eng.md");
-        markdown_file
+        get_markdown_folder().join("minimal_eng.md")
     }
 
 
     pub fn md_get_long_chinese() -> PathBuf {
-        let markdown_file = get_markdown_folder().join("long_chinese.md");
-        markdown_file
+        get_markdown_folder().join("long_chinese.md")
+    }
+
+    pub fn get_opml_folder() -> PathBuf {
+        get_fixture_folder().join("opml")
+    }
+
+    pub fn opml_get_book_plan() -> PathBuf {
+        get_opml_folder().join("book_plan.opml")
     }
 
 }
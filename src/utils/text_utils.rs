@@ -16,6 +16,8 @@ impl TextUtils {
     /// 2. The `offset` value is the byte length of the entire string, which in most practical purposes works, but
     ///    remember it's not the character count. If you need character count, use `text.chars().count()` instead.
     ///
+    /// Positions stored in blocks do not keep these byte columns: `Freeout` encodes them again in the
+    /// `PositionEncoding` of its options.
     pub fn get_end_position(text: &str) -> Position {
         // Counting the number of newline characters to determine the line count.
         let line_count = text.as_bytes().iter().filter(|&&b| b == b'\n').count();
@@ -33,4 +35,30 @@ impl TextUtils {
             offset: text.len(),
         }
     }
+
+    /// Escape text for use in an XML or HTML attribute value.
+    ///
+    /// Newlines are encoded as `&#10;`, because XML parsers normalize literal newlines in attribute values into spaces.
+    pub fn escape_xml_attribute(text: &str) -> String {
+        quick_xml::escape::escape(text).replace('\n', "&#10;")
+    }
+
+    /// Escape text for use as the text of a Markdown link, e.g. a title with brackets.
+    pub fn escape_link_text(text: &str) -> String {
+        text.replace('\\', "\\\\")
+            .replace('[', "\\[")
+            .replace(']', "\\]")
+    }
+
+    /// Write a path as the destination of a Markdown link, between angle brackets if it has spaces or parentheses.
+    pub fn get_link_destination(path: &str) -> String {
+        if path
+            .chars()
+            .any(|c| c.is_whitespace() || matches!(c, '(' | ')' | '<' | '>'))
+        {
+            format!("<{}>", path.replace('<', "\\<").replace('>', "\\>"))
+        } else {
+            path.to_string()
+        }
+    }
 }
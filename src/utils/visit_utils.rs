@@ -0,0 +1,313 @@
+use crate::entities::core::block::{Block, Blocks};
+use crate::entities::core::outline::{Outline, OutlineItem, OutlineItemRef, OutlineRef};
+use crate::entities::visitor::visitor_trait::{VisitContext, VisitFlow, Visitor, VisitorMut};
+
+/// An item of a tree of blocks, e.g. of an `Outline` or an `OutlineRef`.
+trait TreeItem {
+    fn get_block(&self) -> &Block;
+    fn get_subitems(&self) -> &[Self]
+    where
+        Self: Sized;
+}
+
+impl TreeItem for OutlineItem {
+    fn get_block(&self) -> &Block {
+        &self.block
+    }
+
+    fn get_subitems(&self) -> &[Self] {
+        &self.subitems
+    }
+}
+
+impl TreeItem for OutlineItemRef<'_> {
+    fn get_block(&self) -> &Block {
+        self.block
+    }
+
+    fn get_subitems(&self) -> &[Self] {
+        &self.subitems
+    }
+}
+
+pub struct VisitUtils {}
+
+impl VisitUtils {
+    /// # Walk the blocks
+    ///
+    /// Visits the root blocks and their subtrees in document order. Returns `VisitFlow::Stop` if the visitor stopped,
+    /// and `VisitFlow::Continue` otherwise.
+    pub fn walk_blocks(blocks: &Blocks, visitor: &mut dyn Visitor) -> VisitFlow {
+        walk_children(blocks, &get_root_ids(blocks), 1, visitor)
+    }
+
+    /// Visits a block and its subtree, with the block at level 1.
+    pub fn walk_subtree(block: &Block, blocks: &Blocks, visitor: &mut dyn Visitor) -> VisitFlow {
+        let context = VisitContext {
+            level: 1,
+            index: 0,
+            child_count: count_children(block, blocks),
+        };
+        walk_block(block, blocks, &context, visitor)
+    }
+
+    /// # Walk the blocks, changing them
+    ///
+    /// Like `walk_blocks`, with a `VisitorMut`.
+    pub fn walk_blocks_mut(blocks: &mut Blocks, visitor: &mut dyn VisitorMut) -> VisitFlow {
+        let root_ids = get_root_ids(blocks);
+        walk_children_mut(blocks, &root_ids, 1, visitor)
+    }
+
+    /// # Walk an outline
+    ///
+    /// Like `walk_blocks`, over the items of an outline.
+    pub fn walk_outline(outline: &Outline, visitor: &mut dyn Visitor) -> VisitFlow {
+        walk_items(&outline.items, 1, visitor)
+    }
+
+    /// Like `walk_outline`, over an outline borrowing its blocks.
+    pub fn walk_outline_ref(outline: &OutlineRef, visitor: &mut dyn Visitor) -> VisitFlow {
+        walk_items(&outline.items, 1, visitor)
+    }
+
+    /// Like `walk_outline`, with a `VisitorMut`.
+    pub fn walk_outline_mut(outline: &mut Outline, visitor: &mut dyn VisitorMut) -> VisitFlow {
+        walk_items_mut(&mut outline.items, 1, visitor)
+    }
+}
+
+/// Ids of the root blocks, in document order.
+fn get_root_ids(blocks: &Blocks) -> Vec<usize> {
+    let mut root_ids: Vec<usize> = blocks
+        .values()
+        .filter(|block| block.parent_id.is_none())
+        .map(|block| block.id)
+        .collect();
+    root_ids.sort_unstable();
+    root_ids
+}
+
+fn count_children(block: &Block, blocks: &Blocks) -> usize {
+    block
+        .children_ids
+        .iter()
+        .filter(|child_id| blocks.contains_key(child_id))
+        .count()
+}
+
+fn walk_children(
+    blocks: &Blocks,
+    block_ids: &[usize],
+    level: usize,
+    visitor: &mut dyn Visitor,
+) -> VisitFlow {
+    let children = block_ids.iter().filter_map(|block_id| blocks.get(block_id));
+    for (index, block) in children.enumerate() {
+        let context = VisitContext {
+            level,
+            index,
+            child_count: count_children(block, blocks),
+        };
+        if walk_block(block, blocks, &context, visitor) == VisitFlow::Stop {
+            return VisitFlow::Stop;
+        }
+    }
+    VisitFlow::Continue
+}
+
+fn walk_block(
+    block: &Block,
+    blocks: &Blocks,
+    context: &VisitContext,
+    visitor: &mut dyn Visitor,
+) -> VisitFlow {
+    match visitor.enter_block(block, context) {
+        VisitFlow::Stop => return VisitFlow::Stop,
+        VisitFlow::SkipChildren => {}
+        VisitFlow::Continue => {
+            if walk_children(blocks, &block.children_ids, context.level + 1, visitor)
+                == VisitFlow::Stop
+            {
+                return VisitFlow::Stop;
+            }
+        }
+    }
+    visitor.exit_block(block, context);
+    VisitFlow::Continue
+}
+
+fn walk_children_mut(
+    blocks: &mut Blocks,
+    block_ids: &[usize],
+    level: usize,
+    visitor: &mut dyn VisitorMut,
+) -> VisitFlow {
+    let mut index = 0;
+    for block_id in block_ids {
+        let Some(block) = blocks.get(block_id) else {
+            continue;
+        };
+        let context = VisitContext {
+            level,
+            index,
+            child_count: count_children(block, blocks),
+        };
+        index += 1;
+
+        let block = blocks.get_mut(block_id).unwrap();
+        match visitor.enter_block(block, &context) {
+            VisitFlow::Stop => return VisitFlow::Stop,
+            VisitFlow::SkipChildren => {}
+            VisitFlow::Continue => {
+                // read after entering the block, which may have changed them
+                let children_ids = block.children_ids.clone();
+                if walk_children_mut(blocks, &children_ids, level + 1, visitor) == VisitFlow::Stop {
+                    return VisitFlow::Stop;
+                }
+            }
+        }
+        visitor.exit_block(blocks.get_mut(block_id).unwrap(), &context);
+    }
+    VisitFlow::Continue
+}
+
+fn walk_items<I: TreeItem>(items: &[I], level: usize, visitor: &mut dyn Visitor) -> VisitFlow {
+    for (index, item) in items.iter().enumerate() {
+        let context = VisitContext {
+            level,
+            index,
+            child_count: item.get_subitems().len(),
+        };
+        match visitor.enter_block(item.get_block(), &context) {
+            VisitFlow::Stop => return VisitFlow::Stop,
+            VisitFlow::SkipChildren => {}
+            VisitFlow::Continue => {
+                if walk_items(item.get_subitems(), level + 1, visitor) == VisitFlow::Stop {
+                    return VisitFlow::Stop;
+                }
+            }
+        }
+        visitor.exit_block(item.get_block(), &context);
+    }
+    VisitFlow::Continue
+}
+
+fn walk_items_mut(
+    items: &mut [OutlineItem],
+    level: usize,
+    visitor: &mut dyn VisitorMut,
+) -> VisitFlow {
+    for (index, item) in items.iter_mut().enumerate() {
+        let context = VisitContext {
+            level,
+            index,
+            child_count: item.subitems.len(),
+        };
+        match visitor.enter_block(&mut item.block, &context) {
+            VisitFlow::Stop => return VisitFlow::Stop,
+            VisitFlow::SkipChildren => {}
+            VisitFlow::Continue => {
+                if walk_items_mut(&mut item.subitems, level + 1, visitor) == VisitFlow::Stop {
+                    return VisitFlow::Stop;
+                }
+            }
+        }
+        visitor.exit_block(&mut item.block, &context);
+    }
+    VisitFlow::Continue
+}
+
+#[cfg(test)]
+mod tests {
+    use itertools::Itertools;
+
+    use crate::entities::core::block::Block;
+    use crate::entities::core::freeout::Freeout;
+    use crate::entities::visitor::visitor_trait::{VisitContext, VisitFlow, Visitor, VisitorMut};
+    use crate::readers::markdown::MarkdownReader;
+    use crate::utils::visit_utils::VisitUtils;
+
+    /// Records the entered and exited blocks, skipping the children of `skipped` and stopping at `stop`.
+    #[derive(Default)]
+    struct Recorder {
+        events: Vec<String>,
+        skipped: &'static str,
+        stop: &'static str,
+    }
+
+    impl Visitor for Recorder {
+        fn enter_block(&mut self, block: &Block, context: &VisitContext) -> VisitFlow {
+            self.events
+                .push(format!("{}{}", "+".repeat(context.level), block.title));
+            match block.title.as_str() {
+                title if title == self.stop => VisitFlow::Stop,
+                title if title == self.skipped => VisitFlow::SkipChildren,
+                _ => VisitFlow::Continue,
+            }
+        }
+
+        fn exit_block(&mut self, block: &Block, context: &VisitContext) {
+            self.events
+                .push(format!("-{}/{}", block.title, context.child_count));
+        }
+    }
+
+    struct Numberer {
+        numbers: Vec<usize>,
+    }
+
+    impl VisitorMut for Numberer {
+        fn enter_block(&mut self, block: &mut Block, context: &VisitContext) -> VisitFlow {
+            self.numbers.truncate(context.level - 1);
+            self.numbers.push(context.index + 1);
+            block.title = format!("{} {}", self.numbers.iter().join("."), block.title);
+            VisitFlow::Continue
+        }
+    }
+
+    #[test]
+    fn should_enter_skip_and_stop() {
+        let mut freeout = Freeout::new(
+            "# A\n\n## B\n\n### C\n\n## D\n\n# E\n\n## F\n".to_string(),
+            None,
+        );
+        freeout.process(&MarkdownReader::default()).unwrap();
+        let mut outline = freeout.to_outline();
+
+        let mut recorder = Recorder {
+            skipped: "B",
+            stop: "F",
+            ..Default::default()
+        };
+        let flow = VisitUtils::walk_blocks(&freeout.blocks, &mut recorder);
+        assert_eq!(flow, VisitFlow::Stop);
+        assert_eq!(
+            recorder.events,
+            vec!["+A", "++B", "-B/1", "++D", "-D/0", "-A/2", "+E", "++F"]
+        );
+
+        // outlines are walked the same way, borrowed or not
+        let mut recorder = Recorder::default();
+        assert_eq!(
+            VisitUtils::walk_outline(&outline, &mut recorder),
+            VisitFlow::Continue
+        );
+        let mut borrowed_recorder = Recorder::default();
+        VisitUtils::walk_outline_ref(&freeout.get_outline(), &mut borrowed_recorder);
+        assert_eq!(recorder.events, borrowed_recorder.events);
+        assert_eq!(recorder.events.len(), 12);
+
+        let mut numberer = Numberer { numbers: vec![] };
+        VisitUtils::walk_blocks_mut(&mut freeout.blocks, &mut numberer);
+        VisitUtils::walk_outline_mut(&mut outline, &mut Numberer { numbers: vec![] });
+        let titles: Vec<&str> = (1..=6)
+            .map(|block_id| freeout.blocks[&block_id].title.as_str())
+            .collect();
+        assert_eq!(
+            titles,
+            vec!["1 A", "1.1 B", "1.1.1 C", "1.2 D", "2 E", "2.1 F"]
+        );
+        assert_eq!(outline.items[0].subitems[1].block.title, "1.2 D");
+    }
+}
@@ -0,0 +1,6 @@
+//! # Watchers
+//!
+//! Outline documents again whenever they change, and report how the word count of each block moved since the last
+//! run and since the start of the session, e.g. to follow a writing sprint toward a `WordsTarget`.
+pub mod outline_watcher;
+pub mod watch_session;
@@ -0,0 +1,236 @@
+use std::collections::{BTreeSet, HashMap};
+use std::ops::ControlFlow;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, RecvTimeoutError};
+use std::time::Duration;
+
+use eyre::{eyre, Report};
+use notify::{RecursiveMode, Watcher};
+use tracing::debug;
+
+use crate::entities::core::freeout::FreeoutOptions;
+use crate::entities::reader::reader_trait::ReaderTrait;
+use crate::outliners::batch_outliner::{process_file, process_file_with_reader};
+use crate::readers::get_reader_by_extension;
+use crate::watchers::watch_session::{WatchSession, WatchUpdate};
+
+/// # Watch Options
+#[derive(Clone)]
+pub struct WatchOptions {
+    /// How long to wait for more changes before a run, as editors often write a file in several steps.
+    pub debounce: Duration,
+    pub freeout_options: FreeoutOptions,
+    /// The extension of the reader of every document, e.g. `md`, instead of the reader of each file extension.
+    pub reader: Option<String>,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            debounce: Duration::from_millis(200),
+            freeout_options: FreeoutOptions::default(),
+            reader: None,
+        }
+    }
+}
+
+/// An event of a watch.
+#[derive(Debug)]
+pub enum WatchEvent {
+    /// A document was outlined.
+    Updated(WatchUpdate),
+    /// A document could not be read or outlined, e.g. while it is half saved. Its session goes on.
+    Failed { path: PathBuf, error: String },
+}
+
+/// # Watch a file or a directory
+///
+/// Outlines the documents at `path`, and outlines them again on every change, until `on_event` breaks. A directory is
+/// watched recursively, for every file with a known reader extension.
+///
+/// The first event of each document starts its `WatchSession`, so later events have deltas since the last run and
+/// since the start.
+pub fn watch<F>(path: &Path, options: &WatchOptions, mut on_event: F) -> Result<(), Report>
+where
+    F: FnMut(WatchEvent) -> ControlFlow<()>,
+{
+    let reader = match &options.reader {
+        Some(reader_name) => Some(
+            get_reader_by_extension(reader_name)
+                .ok_or_else(|| eyre!("Unknown reader: {}", reader_name))?,
+        ),
+        None => None,
+    };
+    let path = path
+        .canonicalize()
+        .map_err(|err| eyre!("Failed to watch {}: {}", path.display(), err))?;
+
+    let (sender, receiver) = channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+    if path.is_dir() {
+        watcher.watch(&path, RecursiveMode::Recursive)?;
+    } else {
+        // editors often save by replacing the file, which would end a watch of the file itself
+        let folder = path
+            .parent()
+            .ok_or_else(|| eyre!("Failed to watch {}: no parent folder", path.display()))?;
+        watcher.watch(folder, RecursiveMode::NonRecursive)?;
+    }
+
+    let mut sessions: HashMap<PathBuf, WatchSession> = HashMap::new();
+    for file in get_watched_files(&path)? {
+        if run(
+            &file,
+            reader.as_deref(),
+            options,
+            &mut sessions,
+            &mut on_event,
+        )
+        .is_break()
+        {
+            return Ok(());
+        }
+    }
+
+    loop {
+        let mut changed_files = BTreeSet::new();
+        let mut next_event = receiver.recv().map_err(|err| eyre!(err));
+        loop {
+            for changed_path in next_event??.paths {
+                if is_watched(&path, &changed_path) {
+                    changed_files.insert(changed_path);
+                }
+            }
+            next_event = match receiver.recv_timeout(options.debounce) {
+                Ok(event) => Ok(event),
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(err) => Err(eyre!(err)),
+            };
+        }
+
+        for file in changed_files {
+            // e.g. a deleted file, or the temporary file of an editor
+            if !file.is_file() {
+                continue;
+            }
+            if run(
+                &file,
+                reader.as_deref(),
+                options,
+                &mut sessions,
+                &mut on_event,
+            )
+            .is_break()
+            {
+                return Ok(());
+            }
+        }
+    }
+}
+
+fn run<F>(
+    file: &Path,
+    reader: Option<&dyn ReaderTrait>,
+    options: &WatchOptions,
+    sessions: &mut HashMap<PathBuf, WatchSession>,
+    on_event: &mut F,
+) -> ControlFlow<()>
+where
+    F: FnMut(WatchEvent) -> ControlFlow<()>,
+{
+    debug!("Outlining {}", file.display());
+    let result = match reader {
+        Some(reader) => process_file_with_reader(file, reader, &options.freeout_options),
+        None => process_file(file, &options.freeout_options),
+    };
+    let event = match result {
+        Ok(freeout) => WatchEvent::Updated(
+            sessions
+                .entry(file.to_path_buf())
+                .or_insert_with(|| WatchSession::new(file.to_path_buf()))
+                .update(&freeout),
+        ),
+        Err(err) => WatchEvent::Failed {
+            path: file.to_path_buf(),
+            error: err.to_string(),
+        },
+    };
+    on_event(event)
+}
+
+fn get_file_reader(file: &Path) -> Option<Box<dyn ReaderTrait>> {
+    get_reader_by_extension(&file.extension()?.to_string_lossy())
+}
+
+/// Whether a changed path is the watched file, or a document in the watched directory.
+fn is_watched(path: &Path, changed_path: &Path) -> bool {
+    if path.is_dir() {
+        changed_path.starts_with(path) && get_file_reader(changed_path).is_some()
+    } else {
+        changed_path == path
+    }
+}
+
+/// The watched file, or the documents of the watched directory, in path order.
+///
+/// Symlinked folders are not followed, as they may point back up the tree.
+fn get_watched_files(path: &Path) -> Result<Vec<PathBuf>, Report> {
+    if !path.is_dir() {
+        return Ok(vec![path.to_path_buf()]);
+    }
+
+    let mut files = vec![];
+    let mut folders = vec![path.to_path_buf()];
+    while let Some(folder) = folders.pop() {
+        for entry in std::fs::read_dir(&folder)? {
+            let entry = entry?;
+            let entry_path = entry.path();
+            if entry_path.is_dir() {
+                if !entry.file_type()?.is_symlink() {
+                    folders.push(entry_path);
+                }
+            } else if get_file_reader(&entry_path).is_some() {
+                files.push(entry_path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::utils::test_utils::test_utils::{get_fixture_folder, get_temp_folder};
+    use crate::watchers::outline_watcher::{get_watched_files, is_watched};
+
+    #[test]
+    fn should_get_watched_documents() {
+        let folder = get_fixture_folder();
+        let files = get_watched_files(&folder).unwrap();
+
+        assert!(!files.is_empty());
+        assert!(files.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(files.iter().any(|file| file.ends_with("book_plan.opml")));
+        assert!(files.iter().all(|file| is_watched(&folder, file)));
+        assert!(!is_watched(&folder, &folder.join("notes.txt")));
+
+        let file = &files[0];
+        assert_eq!(get_watched_files(file).unwrap(), vec![file.clone()]);
+        assert!(is_watched(file, file));
+        assert!(!is_watched(file, &files[1]));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn should_not_follow_symlinked_folders() {
+        let folder = get_temp_folder().join("watched_symlinks");
+        let _ = std::fs::remove_dir_all(&folder);
+        std::fs::create_dir_all(folder.join("part")).unwrap();
+        std::fs::write(folder.join("part").join("chapter.md"), "# Chapter\n").unwrap();
+        // a loop back up the tree
+        std::os::unix::fs::symlink(&folder, folder.join("part").join("loop")).unwrap();
+
+        let files = get_watched_files(&folder).unwrap();
+        assert_eq!(files, vec![folder.join("part").join("chapter.md")]);
+    }
+}
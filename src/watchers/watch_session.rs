@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use serde::{Deserialize, Serialize};
+
+use crate::entities::core::freeout::Freeout;
+
+/// # Block Delta
+///
+/// The aggregate word count of a block, and how it moved.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BlockDelta {
+    pub block_id: usize,
+    /// `logical_depth` of the block
+    pub depth: usize,
+    pub title: String,
+    /// `aggregate_stats` words
+    pub words: usize,
+    /// `aggregate_stats` target words
+    pub target: Option<usize>,
+    /// Words added since the previous run, negative if removed.
+    pub since_last: isize,
+    /// Words added since the start of the session, negative if removed.
+    pub since_start: isize,
+    pub exclude: bool,
+}
+
+/// # Watch Update
+///
+/// The deltas of a document after a run, with a block per line in document order.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct WatchUpdate {
+    pub path: PathBuf,
+    /// Sum of the aggregate words of the root blocks that are not excluded.
+    pub words: usize,
+    pub since_last: isize,
+    pub since_start: isize,
+    pub blocks: Vec<BlockDelta>,
+}
+
+/// Aggregate words by block key.
+type Snapshot = HashMap<String, usize>;
+
+/// # Watch Session
+///
+/// Keeps the word counts of a document at the start of the session and at the last run, to compute deltas.
+///
+/// Block ids shift when a heading is added, so blocks are matched across runs by the path of titles from their root,
+/// e.g. `Book/Chapter 1`, with a number for duplicate siblings.
+#[derive(Default)]
+pub struct WatchSession {
+    path: PathBuf,
+    start: Option<(usize, Snapshot)>,
+    last: Option<(usize, Snapshot)>,
+}
+
+impl WatchSession {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            ..Default::default()
+        }
+    }
+
+    /// Record an outlined run of the document, and get its deltas. The first run starts the session.
+    pub fn update(&mut self, freeout: &Freeout) -> WatchUpdate {
+        let keys = get_block_keys(freeout);
+        let words = get_total_words(freeout);
+
+        let mut snapshot = Snapshot::new();
+        let mut blocks = vec![];
+        for block_id in 1..=freeout.blocks.len() {
+            let block = &freeout.blocks[&block_id];
+            let block_words = block.aggregate_stats.count.words;
+            let key = &keys[&block_id];
+            snapshot.insert(key.clone(), block_words);
+
+            blocks.push(BlockDelta {
+                block_id,
+                depth: block.logical_depth,
+                title: block.title.clone(),
+                words: block_words,
+                target: block
+                    .aggregate_stats
+                    .target
+                    .as_ref()
+                    .map(|target| target.words),
+                since_last: get_delta(block_words, key, &self.last),
+                since_start: get_delta(block_words, key, &self.start),
+                exclude: block.exclude,
+            });
+        }
+
+        let since_last = get_total_delta(words, &self.last);
+        let since_start = get_total_delta(words, &self.start);
+        if self.start.is_none() {
+            self.start = Some((words, snapshot.clone()));
+        }
+        self.last = Some((words, snapshot));
+
+        WatchUpdate {
+            path: self.path.clone(),
+            words,
+            since_last,
+            since_start,
+            blocks,
+        }
+    }
+}
+
+/// Delta of a block against a snapshot. Blocks missing from the snapshot are new, unless there is no snapshot yet.
+fn get_delta(words: usize, key: &str, snapshot: &Option<(usize, Snapshot)>) -> isize {
+    snapshot.as_ref().map_or(0, |(_, snapshot)| {
+        words as isize - snapshot.get(key).copied().unwrap_or(0) as isize
+    })
+}
+
+fn get_total_delta(words: usize, snapshot: &Option<(usize, Snapshot)>) -> isize {
+    snapshot
+        .as_ref()
+        .map_or(0, |(total, _)| words as isize - *total as isize)
+}
+
+fn get_total_words(freeout: &Freeout) -> usize {
+    freeout
+        .blocks
+        .values()
+        .filter(|block| block.parent_id.is_none() && !block.exclude)
+        .map(|block| block.aggregate_stats.count.words)
+        .sum()
+}
+
+/// The title path of each block, e.g. `Book/Notes#2` for the second sibling titled `Notes`.
+fn get_block_keys(freeout: &Freeout) -> HashMap<usize, String> {
+    let mut keys: HashMap<usize, String> = HashMap::new();
+    let mut title_counts: HashMap<String, usize> = HashMap::new();
+
+    // block ids follow the document order, so a parent always has its key before its children
+    for block_id in 1..=freeout.blocks.len() {
+        let block = &freeout.blocks[&block_id];
+        let parent_key = block
+            .parent_id
+            .and_then(|parent_id| keys.get(&parent_id))
+            .map_or("", String::as_str);
+
+        let key = format!("{}/{}", parent_key, block.title);
+        let count = title_counts.entry(key.clone()).or_insert(0);
+        *count += 1;
+        let key = match count {
+            1 => key,
+            _ => format!("{}#{}", key, count),
+        };
+        keys.insert(block_id, key);
+    }
+
+    keys
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use crate::entities::core::freeout::Freeout;
+    use crate::readers::markdown::MarkdownReader;
+    use crate::watchers::watch_session::WatchSession;
+
+    fn outline(markdown: &str) -> Freeout {
+        let mut freeout = Freeout::new(markdown.to_string(), None);
+        freeout.outline(&MarkdownReader::default()).unwrap();
+        freeout
+    }
+
+    fn get_deltas(
+        session: &mut WatchSession,
+        markdown: &str,
+    ) -> Vec<(String, usize, isize, isize)> {
+        session
+            .update(&outline(markdown))
+            .blocks
+            .into_iter()
+            .map(|block| {
+                (
+                    block.title,
+                    block.words,
+                    block.since_last,
+                    block.since_start,
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn should_compute_deltas_since_last_run_and_start() {
+        let mut session = WatchSession::new(PathBuf::from("book.md"));
+
+        let first = session.update(&outline("# Book\n\n## One\n\na b\n\n## Two\n\nc\n"));
+        assert_eq!((first.since_last, first.since_start), (0, 0));
+        assert!(first.blocks.iter().all(|block| block.since_last == 0));
+
+        // a new heading before "Two" shifts its id, but it is matched by its title
+        assert_eq!(
+            get_deltas(
+                &mut session,
+                "# Book\n\n## One\n\na b c d\n\n## New\n\ne\n\n## Two\n\nc\n"
+            ),
+            vec![
+                ("Book".to_string(), 10, 4, 4),
+                ("One".to_string(), 5, 2, 2),
+                ("New".to_string(), 2, 2, 2),
+                ("Two".to_string(), 2, 0, 0),
+            ]
+        );
+
+        assert_eq!(
+            get_deltas(&mut session, "# Book\n\n## One\n\na\n\n## Two\n\nc\n"),
+            vec![
+                ("Book".to_string(), 5, -5, -1),
+                ("One".to_string(), 2, -3, -1),
+                ("Two".to_string(), 2, 0, 0),
+            ]
+        );
+    }
+}
@@ -0,0 +1,162 @@
+use eyre::Report;
+
+use crate::entities::core::block::Block;
+use crate::entities::core::outline::Outline;
+use crate::entities::core::words_statistics::TargetState;
+use crate::entities::visitor::visitor_trait::{VisitContext, VisitFlow, Visitor};
+use crate::entities::writer::writer_trait::WriterTrait;
+use crate::utils::visit_utils::VisitUtils;
+use crate::writers::mind_map::MindMapOptions;
+
+const UNDER_TARGET_COLOR: &str = "#fff3c4";
+const ON_TARGET_COLOR: &str = "#c8e6c9";
+const OVER_TARGET_COLOR: &str = "#ffcdd2";
+const EXCLUDED_COLOR: &str = "#eeeeee";
+
+/// # Graphviz DOT Writer
+///
+/// Renders an `Outline` as a Graphviz `digraph`, with one node per block and an edge from each parent to its children.
+///
+/// Nodes are labeled with the title and statistics of the block, and colored by how the block compares with its target:
+/// yellow under target, green on target, red over target. Excluded blocks are gray and dashed.
+#[derive(Default)]
+pub struct DotWriter {
+    pub options: MindMapOptions,
+}
+
+impl WriterTrait for DotWriter {
+    fn write(&self, outline: &Outline) -> Result<String, Report> {
+        let mut visitor = DotVisitor {
+            writer: self,
+            parent_ids: vec![],
+            dot: String::new(),
+        };
+        visitor.dot.push_str("digraph outline {\n");
+        visitor.dot.push_str("  rankdir=LR;\n");
+        visitor
+            .dot
+            .push_str("  node [shape=box, style=\"rounded,filled\", fillcolor=\"#ffffff\"];\n");
+        VisitUtils::walk_outline(outline, &mut visitor);
+        visitor.dot.push_str("}\n");
+        Ok(visitor.dot)
+    }
+
+    fn name(&self) -> &'static str {
+        "Graphviz DOT Writer"
+    }
+}
+
+impl DotWriter {
+    /// The title, followed by one statistic per line.
+    fn get_label(&self, block: &Block) -> String {
+        let mut lines = vec![block.title.clone()];
+        for (name, value) in self.options.get_stats(block) {
+            lines.push(format!("{}: {}", name, value));
+        }
+        lines.join("\n")
+    }
+}
+
+struct DotVisitor<'a> {
+    writer: &'a DotWriter,
+    /// The ids of the written blocks being visited, to link them to their children.
+    parent_ids: Vec<usize>,
+    dot: String,
+}
+
+impl DotVisitor<'_> {
+    fn is_written(&self, block: &Block, context: &VisitContext) -> bool {
+        let options = &self.writer.options;
+        options.is_level_included(context.level) && options.is_block_included(block)
+    }
+}
+
+impl Visitor for DotVisitor<'_> {
+    fn enter_block(&mut self, block: &Block, context: &VisitContext) -> VisitFlow {
+        if !self.is_written(block, context) {
+            return VisitFlow::SkipChildren;
+        }
+
+        self.dot.push_str(&format!(
+            "  n{} [label=\"{}\"{}];\n",
+            block.id,
+            escape_dot(&self.writer.get_label(block)),
+            get_node_style(block)
+        ));
+        if let Some(parent_id) = self.parent_ids.last() {
+            self.dot
+                .push_str(&format!("  n{} -> n{};\n", parent_id, block.id));
+        }
+        self.parent_ids.push(block.id);
+        VisitFlow::Continue
+    }
+
+    fn exit_block(&mut self, block: &Block, context: &VisitContext) {
+        if self.is_written(block, context) {
+            self.parent_ids.pop();
+        }
+    }
+}
+
+fn get_node_style(block: &Block) -> String {
+    if block.exclude {
+        return format!(
+            ", style=\"rounded,filled,dashed\", fillcolor=\"{}\"",
+            EXCLUDED_COLOR
+        );
+    }
+
+    match block.aggregate_stats.get_target_state() {
+        Some(TargetState::Under) => format!(", fillcolor=\"{}\"", UNDER_TARGET_COLOR),
+        Some(TargetState::OnTarget) => format!(", fillcolor=\"{}\"", ON_TARGET_COLOR),
+        Some(TargetState::Over) => format!(", fillcolor=\"{}\"", OVER_TARGET_COLOR),
+        None => "".to_string(),
+    }
+}
+
+/// Escape text for a quoted DOT string, with newlines as centered line breaks.
+fn escape_dot(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::core::freeout::Freeout;
+    use crate::entities::writer::writer_trait::WriterTrait;
+    use crate::readers::opml::OpmlReader;
+    use crate::utils::test_utils::test_utils::opml_get_book_plan;
+    use crate::writers::dot::DotWriter;
+    use crate::writers::mind_map::{MindMapOptions, MindMapStat};
+
+    #[test]
+    fn should_write_dot_digraph() {
+        let source = std::fs::read_to_string(opml_get_book_plan()).unwrap();
+        let mut freeout = Freeout::new(source, None);
+        freeout.process(&OpmlReader::default()).unwrap();
+        let outline = freeout.to_outline();
+
+        let writer = DotWriter {
+            options: MindMapOptions {
+                max_depth: Some(2),
+                stats: vec![MindMapStat::AggregateWords, MindMapStat::Target],
+                include_excluded: true,
+                ..Default::default()
+            },
+        };
+        let dot = writer.write(&outline).unwrap();
+
+        assert!(dot.starts_with("digraph outline {\n"));
+        assert!(dot.contains(
+            "  n2 [label=\"Chapter 1: Cats & Dogs\\naggregate words: 0\\ntarget: 7000\", fillcolor=\"#fff3c4\"];\n"
+        ));
+        assert!(dot.contains("  n1 -> n2;\n"));
+        assert!(dot.contains(
+            "  n6 [label=\"Appendix\\naggregate words: 0\", style=\"rounded,filled,dashed\""
+        ));
+        // sections are below the maximum depth
+        assert!(!dot.contains("Section 1.1"));
+        assert!(dot.ends_with("}\n"));
+    }
+}
@@ -0,0 +1,151 @@
+use eyre::Report;
+
+use crate::entities::core::block::Block;
+use crate::entities::core::outline::Outline;
+use crate::entities::visitor::visitor_trait::{VisitContext, VisitFlow, Visitor};
+use crate::entities::writer::writer_trait::WriterTrait;
+use crate::utils::text_utils::TextUtils;
+use crate::utils::visit_utils::VisitUtils;
+use crate::writers::mind_map::MindMapOptions;
+
+const EXCLUDED_COLOR: &str = "#999999";
+
+/// # FreeMind Writer
+///
+/// Renders an `Outline` as a FreeMind `.mm` file, which Freeplane opens as well.
+///
+/// Statistics become node attributes, and notes become node notes. Excluded blocks are grayed out.
+#[derive(Default)]
+pub struct FreeMindWriter {
+    pub options: MindMapOptions,
+}
+
+impl WriterTrait for FreeMindWriter {
+    fn write(&self, outline: &Outline) -> Result<String, Report> {
+        let mut visitor = FreeMindVisitor {
+            options: &self.options,
+            mm: "<map version=\"1.0.1\">\n".to_string(),
+        };
+
+        // a mind map has a single root, which is added for several root items
+        let has_single_root = self.options.filter_items(&outline.items).len() == 1;
+        if !has_single_root {
+            visitor.mm.push_str(&format!(
+                "<node ID=\"ID_root\" TEXT=\"{}\">\n",
+                TextUtils::escape_xml_attribute(&self.options.root_title)
+            ));
+        }
+        VisitUtils::walk_outline(outline, &mut visitor);
+        if !has_single_root {
+            visitor.mm.push_str("</node>\n");
+        }
+
+        visitor.mm.push_str("</map>\n");
+        Ok(visitor.mm)
+    }
+
+    fn name(&self) -> &'static str {
+        "FreeMind Writer"
+    }
+}
+
+struct FreeMindVisitor<'a> {
+    options: &'a MindMapOptions,
+    mm: String,
+}
+
+impl FreeMindVisitor<'_> {
+    /// The root items are written at any maximum depth, as the map needs a root.
+    fn is_written(&self, block: &Block, context: &VisitContext) -> bool {
+        (context.level == 1 || self.options.is_level_included(context.level))
+            && self.options.is_block_included(block)
+    }
+}
+
+impl Visitor for FreeMindVisitor<'_> {
+    fn enter_block(&mut self, block: &Block, context: &VisitContext) -> VisitFlow {
+        if !self.is_written(block, context) {
+            return VisitFlow::SkipChildren;
+        }
+
+        let mut node_attributes = format!(
+            "ID=\"ID_{}\" TEXT=\"{}\"",
+            block.id,
+            TextUtils::escape_xml_attribute(&block.title)
+        );
+        if block.exclude {
+            node_attributes.push_str(&format!(" COLOR=\"{}\"", EXCLUDED_COLOR));
+        }
+        self.mm.push_str(&format!("<node {}>\n", node_attributes));
+
+        for (name, value) in self.options.get_stats(block) {
+            self.mm.push_str(&format!(
+                "<attribute NAME=\"{}\" VALUE=\"{}\"/>\n",
+                name,
+                TextUtils::escape_xml_attribute(&value)
+            ));
+        }
+        if let Some(note) = &block.note {
+            write_note(note, &mut self.mm);
+        }
+        VisitFlow::Continue
+    }
+
+    fn exit_block(&mut self, block: &Block, context: &VisitContext) {
+        if self.is_written(block, context) {
+            self.mm.push_str("</node>\n");
+        }
+    }
+}
+
+/// Write a note as rich content, with one paragraph per line.
+fn write_note(note: &str, mm: &mut String) {
+    mm.push_str("<richcontent TYPE=\"NOTE\"><html><head></head><body>");
+    for line in note.lines() {
+        mm.push_str(&format!("<p>{}</p>", quick_xml::escape::escape(line)));
+    }
+    mm.push_str("</body></html></richcontent>\n");
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::core::freeout::Freeout;
+    use crate::entities::writer::writer_trait::WriterTrait;
+    use crate::readers::opml::OpmlReader;
+    use crate::utils::test_utils::test_utils::opml_get_book_plan;
+    use crate::writers::freemind::FreeMindWriter;
+    use crate::writers::mind_map::{MindMapOptions, MindMapStat};
+
+    #[test]
+    fn should_write_freemind_map() {
+        let source = std::fs::read_to_string(opml_get_book_plan()).unwrap();
+        let mut freeout = Freeout::new(source, None);
+        freeout.process(&OpmlReader::default()).unwrap();
+        let outline = freeout.to_outline();
+
+        let writer = FreeMindWriter {
+            options: MindMapOptions {
+                max_depth: Some(2),
+                stats: vec![MindMapStat::Target],
+                ..Default::default()
+            },
+        };
+
+        assert_eq!(
+            writer.write(&outline).unwrap(),
+            r#"<map version="1.0.1">
+<node ID="ID_1" TEXT="My Book">
+<attribute NAME="target" VALUE="10000"/>
+<node ID="ID_2" TEXT="Chapter 1: Cats &amp; Dogs">
+<attribute NAME="target" VALUE="7000"/>
+<richcontent TYPE="NOTE"><html><head></head><body><p>Open with a &quot;hook&quot;.</p><p>Keep it short.</p></body></html></richcontent>
+</node>
+<node ID="ID_5" TEXT="Chapter 2">
+<attribute NAME="target" VALUE="3000"/>
+</node>
+</node>
+</map>
+"#
+        );
+    }
+}
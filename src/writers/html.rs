@@ -0,0 +1,265 @@
+use eyre::Report;
+
+use crate::entities::core::block::Block;
+use crate::entities::core::outline::Outline;
+use crate::entities::core::words_statistics::TargetState;
+use crate::entities::visitor::visitor_trait::{VisitContext, VisitFlow, Visitor};
+use crate::entities::writer::writer_trait::WriterTrait;
+use crate::utils::visit_utils::VisitUtils;
+
+const STYLE: &str = r#"
+body { font-family: -apple-system, "Segoe UI", "Noto Sans", "PingFang SC", sans-serif; margin: 0; display: flex; color: #222; }
+main { flex: 3; padding: 1.5em; overflow: auto; height: 100vh; box-sizing: border-box; }
+aside { flex: 2; padding: 1.5em; border-left: 1px solid #ddd; overflow: auto; height: 100vh; box-sizing: border-box; background: #fafafa; }
+aside pre { white-space: pre-wrap; font-family: inherit; }
+details, .leaf { margin-left: 1.2em; }
+summary, .leaf { padding: 0.15em 0; }
+.title { color: inherit; text-decoration: none; font-weight: 600; }
+.title:hover { text-decoration: underline; }
+.excluded > summary, .leaf.excluded { opacity: 0.45; }
+.stats { color: #666; font-size: 0.85em; margin-left: 0.5em; }
+.bar { display: inline-block; vertical-align: middle; width: 8em; height: 0.6em; margin-left: 0.5em; background: #eee; border-radius: 0.3em; overflow: hidden; position: relative; }
+.bar span { position: absolute; left: 0; top: 0; bottom: 0; }
+.bar .self { background: #5c6bc0; }
+.bar .aggregate { background: #c5cae9; }
+.progress .under { background: #ffb300; }
+.progress .on-target { background: #43a047; }
+.progress .over { background: #e53935; }
+"#;
+
+const SCRIPT: &str = r#"
+document.querySelectorAll(".title").forEach(function (title) {
+  title.addEventListener("click", function (event) {
+    event.preventDefault();
+    var content = document.getElementById("content-" + title.dataset.block);
+    document.getElementById("content-title").textContent = title.textContent;
+    document.getElementById("content-text").textContent = content ? content.content.textContent : "";
+  });
+});
+"#;
+
+/// # HTML Writer
+///
+/// Renders an `Outline` as a single static HTML report without external assets, e.g. to email a manuscript status page.
+///
+/// - The outline is a collapsible tree.
+/// - Each block has word count bars for its `self_stats` and `aggregate_stats`, relative to the largest root item.
+/// - Blocks with a target have a progress bar, colored by how the block compares with its target.
+/// - Clicking a title shows the content of the block.
+pub struct HtmlWriter {
+    /// Title of the report.
+    pub title: String,
+    /// Number of outline levels expanded when the report opens.
+    pub expanded_depth: usize,
+    /// Render excluded blocks, grayed out.
+    pub include_excluded: bool,
+    /// Show the time of generation under the title.
+    pub show_generated_at: bool,
+}
+
+impl Default for HtmlWriter {
+    fn default() -> Self {
+        Self {
+            title: "Outline".to_string(),
+            expanded_depth: 1,
+            include_excluded: true,
+            show_generated_at: true,
+        }
+    }
+}
+
+impl WriterTrait for HtmlWriter {
+    fn write(&self, outline: &Outline) -> Result<String, Report> {
+        let max_words = outline
+            .items
+            .iter()
+            .map(|item| item.block.aggregate_stats.count.words)
+            .max()
+            .unwrap_or(0);
+
+        let mut html = String::new();
+        html.push_str("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+        html.push_str(&format!("<title>{}</title>\n", escape_html(&self.title)));
+        html.push_str(&format!("<style>{}</style>\n", STYLE));
+        html.push_str("</head>\n<body>\n<main>\n");
+        html.push_str(&format!("<h1>{}</h1>\n", escape_html(&self.title)));
+        if self.show_generated_at {
+            html.push_str(&format!(
+                "<p class=\"stats\">Generated at {}</p>\n",
+                chrono::Local::now().format("%Y-%m-%d %H:%M")
+            ));
+        }
+
+        let mut visitor = HtmlVisitor {
+            writer: self,
+            max_words,
+            html,
+        };
+        VisitUtils::walk_outline(outline, &mut visitor);
+
+        let mut html = visitor.html;
+        html.push_str("</main>\n");
+        html.push_str("<aside>\n<h2 id=\"content-title\">Content</h2>\n<pre id=\"content-text\">Click on a title to show its content.</pre>\n</aside>\n");
+        html.push_str(&format!("<script>{}</script>\n", SCRIPT));
+        html.push_str("</body>\n</html>\n");
+        Ok(html)
+    }
+
+    fn name(&self) -> &'static str {
+        "HTML Writer"
+    }
+}
+
+struct HtmlVisitor<'a> {
+    writer: &'a HtmlWriter,
+    /// Words of the largest root item, the full width of the bars.
+    max_words: usize,
+    html: String,
+}
+
+impl HtmlVisitor<'_> {
+    fn is_written(&self, block: &Block) -> bool {
+        !block.exclude || self.writer.include_excluded
+    }
+}
+
+impl Visitor for HtmlVisitor<'_> {
+    fn enter_block(&mut self, block: &Block, context: &VisitContext) -> VisitFlow {
+        if !self.is_written(block) {
+            return VisitFlow::SkipChildren;
+        }
+
+        let class = if block.exclude { " excluded" } else { "" };
+        let row = get_row(block, self.max_words);
+
+        if context.child_count == 0 {
+            self.html
+                .push_str(&format!("<div class=\"leaf{}\">{}</div>\n", class, row));
+        } else {
+            let open = if context.level <= self.writer.expanded_depth {
+                " open"
+            } else {
+                ""
+            };
+            self.html.push_str(&format!(
+                "<details class=\"node{}\"{}>\n<summary>{}</summary>\n",
+                class, open, row
+            ));
+        }
+        VisitFlow::Continue
+    }
+
+    fn exit_block(&mut self, block: &Block, context: &VisitContext) {
+        if !self.is_written(block) {
+            return;
+        }
+
+        if context.child_count > 0 {
+            self.html.push_str("</details>\n");
+        }
+        if let Some(content) = &block.content {
+            self.html.push_str(&format!(
+                "<template id=\"content-{}\">{}</template>\n",
+                block.id,
+                escape_html(content)
+            ));
+        }
+    }
+}
+
+/// The title, statistics and bars of a block.
+fn get_row(block: &Block, max_words: usize) -> String {
+    let self_words = block.self_stats.count.words;
+    let aggregate_words = block.aggregate_stats.count.words;
+
+    let mut stats = format!("{} / {} words", self_words, aggregate_words);
+    let mut progress = String::new();
+
+    if let (Some(target), Some(status)) =
+        (&block.aggregate_stats.target, &block.aggregate_stats.status)
+    {
+        stats.push_str(&format!(", target {} ({:+})", target.words, status.balance));
+
+        let class = match block.aggregate_stats.get_target_state() {
+            Some(TargetState::Under) => "under",
+            Some(TargetState::Over) => "over",
+            _ => "on-target",
+        };
+        progress = format!(
+            "<span class=\"bar progress\" title=\"{} of {} words\"><span class=\"{}\" style=\"width: {:.1}%\"></span></span>",
+            aggregate_words,
+            target.words,
+            class,
+            get_percentage(aggregate_words, target.words)
+        );
+    }
+
+    format!(
+        "<a href=\"#\" class=\"title\" data-block=\"{}\">{}</a><span class=\"bar\" title=\"{} / {} words\"><span class=\"aggregate\" style=\"width: {:.1}%\"></span><span class=\"self\" style=\"width: {:.1}%\"></span></span>{}<span class=\"stats\">{}</span>",
+        block.id,
+        escape_html(&block.title),
+        self_words,
+        aggregate_words,
+        get_percentage(aggregate_words, max_words),
+        get_percentage(self_words, max_words),
+        progress,
+        stats
+    )
+}
+
+/// Percentage of `part` in `whole`, capped at 100.
+fn get_percentage(part: usize, whole: usize) -> f64 {
+    if whole == 0 {
+        return 0.0;
+    }
+    (part as f64 / whole as f64 * 100.0).min(100.0)
+}
+
+fn escape_html(text: &str) -> String {
+    quick_xml::escape::escape(text).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::core::freeout::Freeout;
+    use crate::entities::core::words_statistics::{DistributionMethod, WordsTarget};
+    use crate::entities::writer::writer_trait::WriterTrait;
+    use crate::readers::markdown::MarkdownReader;
+    use crate::writers::html::HtmlWriter;
+
+    #[test]
+    fn should_write_self_contained_report() {
+        let markdown = "# Book & Draft\n\nOne two three four\n\n## Chapter 1\n\nFive & six\n\n## Notes {.exclude}\n\nSeven\n";
+        let mut freeout = Freeout::new(markdown.to_string(), None);
+        freeout.outline(&MarkdownReader::default()).unwrap();
+
+        // plan a target, as the reader of a planned outline would
+        freeout.blocks.get_mut(&1).unwrap().aggregate_stats.target = Some(WordsTarget {
+            words: 20,
+            distribution: Some(DistributionMethod::Uniform),
+            distributed: false,
+        });
+        freeout.process_stats();
+        let outline = freeout.to_outline();
+
+        let writer = HtmlWriter {
+            title: "Weekly <Status>".to_string(),
+            show_generated_at: false,
+            ..Default::default()
+        };
+        let html = writer.write(&outline).unwrap();
+
+        // no external assets
+        assert!(!html.contains("src="));
+        assert!(!html.contains("<link"));
+
+        assert!(html.contains("<title>Weekly &lt;Status&gt;</title>"));
+        assert!(html.contains("<details class=\"node\" open>"));
+        assert!(html.contains(">Book &amp; Draft</a>"));
+        assert!(html.contains("<div class=\"leaf excluded\">"));
+        // the excluded notes do not count toward the book
+        assert!(html.contains("<span class=\"under\" style=\"width: 60.0%\"></span>"));
+        assert!(html.contains("7 / 12 words, target 20 (-8)"));
+        assert!(html.contains("<template id=\"content-2\">Chapter 1\nFive &amp; six</template>"));
+    }
+}
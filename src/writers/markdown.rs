@@ -0,0 +1,157 @@
+use eyre::Report;
+
+use crate::entities::core::block::Block;
+use crate::entities::core::exclusion_rules::ExclusionRules;
+use crate::entities::core::outline::Outline;
+use crate::entities::visitor::visitor_trait::Visitor;
+use crate::entities::writer::writer_trait::WriterTrait;
+use crate::utils::visit_utils::VisitUtils;
+
+/// Markdown has six heading levels, deeper blocks are written at the sixth.
+const MAX_HEADING_DEPTH: usize = 6;
+
+/// # Markdown Writer
+///
+/// Renders an `Outline` as a Markdown document with one heading per block, followed by its note and content without
+/// its heading, see `Block::get_body`.
+///
+/// This turns an outline read from another format, e.g. an OPML plan, into a skeleton document.
+/// Word targets set on a block and exclusions are kept as heading attributes, e.g. `{.exclude target=N}`, which the
+/// Markdown reader reads back. Distributed targets are left out, so that they are distributed again.
+#[derive(Default)]
+pub struct MarkdownWriter {}
+
+impl WriterTrait for MarkdownWriter {
+    fn write(&self, outline: &Outline) -> Result<String, Report> {
+        let mut visitor = MarkdownVisitor::default();
+        VisitUtils::walk_outline(outline, &mut visitor);
+        Ok(visitor.markdown)
+    }
+
+    fn name(&self) -> &'static str {
+        "Markdown Writer"
+    }
+}
+
+struct MarkdownVisitor {
+    markdown: String,
+    exclusion_class: String,
+}
+
+impl Default for MarkdownVisitor {
+    fn default() -> Self {
+        Self {
+            markdown: String::new(),
+            exclusion_class: ExclusionRules::default().attribute_class,
+        }
+    }
+}
+
+impl Visitor for MarkdownVisitor {
+    fn visit_block(&mut self, block: &Block) {
+        let depth = block.depth.clamp(1, MAX_HEADING_DEPTH);
+
+        if !self.markdown.is_empty() {
+            self.markdown.push('\n');
+        }
+        self.markdown
+            .push_str(&format!("{} {}", "#".repeat(depth), block.title));
+        let mut attributes = vec![];
+        if block.exclude {
+            attributes.push(format!(".{}", self.exclusion_class));
+        }
+        // distributed targets are distributed again when the document is read back
+        if let Some(target) = block
+            .aggregate_stats
+            .target
+            .as_ref()
+            .filter(|target| !target.distributed)
+        {
+            attributes.push(format!("target={}", target.words));
+        }
+        if !attributes.is_empty() {
+            self.markdown
+                .push_str(&format!(" {{{}}}", attributes.join(" ")));
+        }
+        self.markdown.push('\n');
+
+        for text in [block.note.as_deref(), block.get_body()]
+            .into_iter()
+            .flatten()
+        {
+            self.markdown.push_str(&format!("\n{}\n", text.trim()));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::core::freeout::Freeout;
+    use crate::entities::core::words_statistics::{DistributionMethod, WordsTarget};
+    use crate::entities::writer::writer_trait::WriterTrait;
+    use crate::readers::markdown::MarkdownReader;
+    use crate::readers::opml::OpmlReader;
+    use crate::utils::test_utils::test_utils::opml_get_book_plan;
+    use crate::writers::markdown::MarkdownWriter;
+
+    #[test]
+    fn should_write_skeleton_from_opml() {
+        let source = std::fs::read_to_string(opml_get_book_plan()).unwrap();
+
+        let mut freeout = Freeout::new(source, None);
+        freeout.process(&OpmlReader::default()).unwrap();
+        let outline = freeout.to_outline();
+
+        let markdown = MarkdownWriter::default().write(&outline).unwrap();
+
+        assert_eq!(
+            markdown,
+            "# My Book {target=10000}\n\n## Chapter 1: Cats & Dogs\n\nOpen with a \"hook\".\nKeep it short.\n\n### Section 1.1\n\n### Section 1.2\n\n## Chapter 2 {target=3000}\n\n## Appendix {.exclude}\n\n### Sources {.exclude}\n"
+        );
+
+        // the targets survive a round-trip through the Markdown reader
+        let mut freeout = Freeout::new(markdown, None);
+        freeout.outline(&MarkdownReader::default()).unwrap();
+        let targets: Vec<Option<usize>> = (1..=freeout.blocks.len())
+            .map(|id| {
+                let target = freeout.blocks[&id].aggregate_stats.target.as_ref();
+                target.map(|target| target.words)
+            })
+            .collect();
+        assert_eq!(
+            targets,
+            vec![
+                Some(10000),
+                Some(7000),
+                Some(3500),
+                Some(3500),
+                Some(3000),
+                None,
+                None
+            ]
+        );
+
+        // a new target on the book is distributed again
+        freeout.blocks.get_mut(&1).unwrap().aggregate_stats.target = Some(WordsTarget {
+            words: 12000,
+            distribution: Some(DistributionMethod::Uniform),
+            distributed: false,
+        });
+        freeout.process_stats();
+        let chapter_1_target = freeout.blocks[&2].aggregate_stats.target.as_ref().unwrap();
+        assert_eq!(chapter_1_target.words, 9000);
+    }
+
+    #[test]
+    fn should_round_trip_markdown_with_content() {
+        let markdown = "# Book\n\nIntro words.\n\n## Chapter 1 {target=500}\n\nOne two three.\n\n## Notes {.exclude}\n\nFour.\n";
+
+        let mut freeout = Freeout::new(markdown.to_string(), None);
+        freeout.process(&MarkdownReader::default()).unwrap();
+        let written = MarkdownWriter::default()
+            .write(&freeout.to_outline())
+            .unwrap();
+
+        assert_eq!(written, markdown);
+    }
+}
@@ -0,0 +1,167 @@
+use eyre::Report;
+
+use crate::entities::core::block::Block;
+use crate::entities::core::outline::Outline;
+use crate::entities::visitor::visitor_trait::{VisitContext, VisitFlow, Visitor};
+use crate::entities::writer::writer_trait::WriterTrait;
+use crate::utils::visit_utils::VisitUtils;
+use crate::writers::mind_map::MindMapOptions;
+
+/// # Mermaid Writer
+///
+/// Renders an `Outline` as a Mermaid `mindmap`.
+///
+/// ## Example
+///
+/// ```text
+/// mindmap
+///   root(("Book (aggregate words: 1200)"))
+///     n2["Chapter 1 (aggregate words: 700)"]
+///     n3["Chapter 2 (aggregate words: 500)"]
+/// ```
+#[derive(Default)]
+pub struct MermaidWriter {
+    pub options: MindMapOptions,
+    /// Wrap the mind map in a ```` ```mermaid ```` code fence, e.g. to embed it in Markdown.
+    pub fenced: bool,
+}
+
+impl WriterTrait for MermaidWriter {
+    fn write(&self, outline: &Outline) -> Result<String, Report> {
+        let mut mermaid = String::new();
+        if self.fenced {
+            mermaid.push_str("```mermaid\n");
+        }
+        mermaid.push_str("mindmap\n");
+
+        // a mind map has a single root, which is added for several root items
+        let has_single_root = self.options.filter_items(&outline.items).len() == 1;
+        if !has_single_root {
+            push_node(&mut mermaid, 1, "root((", &self.options.root_title, "))");
+        }
+        let mut visitor = MermaidVisitor {
+            options: &self.options,
+            has_single_root,
+            mermaid,
+        };
+        VisitUtils::walk_outline(outline, &mut visitor);
+        let mut mermaid = visitor.mermaid;
+
+        if self.fenced {
+            mermaid.push_str("```\n");
+        }
+        Ok(mermaid)
+    }
+
+    fn name(&self) -> &'static str {
+        "Mermaid Writer"
+    }
+}
+
+struct MermaidVisitor<'a> {
+    options: &'a MindMapOptions,
+    /// Whether the only root item is the root of the mind map, or the items are under an added root.
+    has_single_root: bool,
+    mermaid: String,
+}
+
+impl Visitor for MermaidVisitor<'_> {
+    fn enter_block(&mut self, block: &Block, context: &VisitContext) -> VisitFlow {
+        if !self.options.is_block_included(block) {
+            return VisitFlow::SkipChildren;
+        }
+
+        let label = self.options.get_label(block);
+        if self.has_single_root && context.level == 1 {
+            push_node(&mut self.mermaid, 1, "root((", &label, "))");
+            return VisitFlow::Continue;
+        }
+        if !self.options.is_level_included(context.level) {
+            return VisitFlow::SkipChildren;
+        }
+
+        // the nodes are indented under the root of the mind map
+        let indent_level = if self.has_single_root {
+            context.level
+        } else {
+            context.level + 1
+        };
+        push_node(
+            &mut self.mermaid,
+            indent_level,
+            &format!("{}[", get_node_id(block)),
+            &label,
+            "]",
+        );
+        VisitFlow::Continue
+    }
+}
+
+fn get_node_id(block: &Block) -> String {
+    format!("n{}", block.id)
+}
+
+/// Push a node with a quoted label, indented by its level in the mind map.
+fn push_node(mermaid: &mut String, indent_level: usize, open: &str, label: &str, close: &str) {
+    // Mermaid has no escaping inside quoted labels, but accepts entity codes such as `#quot;`.
+    let label = label.replace('"', "#quot;").replace('\n', " ");
+    mermaid.push_str(&format!(
+        "{}{}\"{}\"{}\n",
+        "  ".repeat(indent_level),
+        open,
+        label,
+        close
+    ));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::core::freeout::Freeout;
+    use crate::entities::writer::writer_trait::WriterTrait;
+    use crate::readers::markdown::MarkdownReader;
+    use crate::writers::mermaid::MermaidWriter;
+    use crate::writers::mind_map::{MindMapOptions, MindMapStat};
+
+    #[test]
+    fn should_write_mermaid_mindmap() {
+        let markdown = "# Book\n\n## The \"First\" Chapter\n\nOne two three\n\n### Section\n\n## Notes {.exclude}\n";
+        let mut freeout = Freeout::new(markdown.to_string(), None);
+        freeout.process(&MarkdownReader::default()).unwrap();
+        let outline = freeout.to_outline();
+
+        let writer = MermaidWriter {
+            options: MindMapOptions {
+                stats: vec![MindMapStat::Words],
+                ..Default::default()
+            },
+            fenced: true,
+        };
+
+        assert_eq!(
+            writer.write(&outline).unwrap(),
+            "```mermaid\nmindmap\n  root((\"Book (words: 1)\"))\n    n2[\"The #quot;First#quot; Chapter (words: 6)\"]\n      n3[\"Section (words: 1)\"]\n```\n"
+        );
+    }
+
+    #[test]
+    fn should_add_root_for_several_root_items_with_max_depth() {
+        let markdown = "# Part 1\n\n## Chapter 1\n\n# Part 2\n";
+        let mut freeout = Freeout::new(markdown.to_string(), None);
+        freeout.process(&MarkdownReader::default()).unwrap();
+        let outline = freeout.to_outline();
+
+        let writer = MermaidWriter {
+            options: MindMapOptions {
+                max_depth: Some(1),
+                stats: vec![],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        assert_eq!(
+            writer.write(&outline).unwrap(),
+            "mindmap\n  root((\"Outline\"))\n    n1[\"Part 1\"]\n    n3[\"Part 2\"]\n"
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! # Mind Map
+//!
+//! Shared options of the mind map writers: `MermaidWriter`, `DotWriter` and `FreeMindWriter`.
+use crate::entities::core::block::Block;
+use crate::entities::core::outline::OutlineItem;
+
+/// A statistic that can be shown on the nodes of a mind map.
+#[derive(Clone, Debug, PartialEq)]
+pub enum MindMapStat {
+    /// `self_stats` words
+    Words,
+    /// `aggregate_stats` words
+    AggregateWords,
+    /// `self_stats` characters
+    Characters,
+    /// `aggregate_stats` characters
+    AggregateCharacters,
+    /// `aggregate_stats` target words
+    Target,
+    /// `aggregate_stats` balance
+    Balance,
+}
+
+impl MindMapStat {
+    /// Short name of the statistic, e.g. for attribute names.
+    pub fn name(&self) -> &'static str {
+        match self {
+            MindMapStat::Words => "words",
+            MindMapStat::AggregateWords => "aggregate words",
+            MindMapStat::Characters => "characters",
+            MindMapStat::AggregateCharacters => "aggregate characters",
+            MindMapStat::Target => "target",
+            MindMapStat::Balance => "balance",
+        }
+    }
+
+    /// Value of the statistic for a block, `None` if the block has no target or status.
+    pub fn get_value(&self, block: &Block) -> Option<String> {
+        match self {
+            MindMapStat::Words => Some(block.self_stats.count.words.to_string()),
+            MindMapStat::AggregateWords => Some(block.aggregate_stats.count.words.to_string()),
+            MindMapStat::Characters => Some(block.self_stats.count.characters.to_string()),
+            MindMapStat::AggregateCharacters => {
+                Some(block.aggregate_stats.count.characters.to_string())
+            }
+            MindMapStat::Target => block
+                .aggregate_stats
+                .target
+                .as_ref()
+                .map(|target| target.words.to_string()),
+            MindMapStat::Balance => block
+                .aggregate_stats
+                .status
+                .as_ref()
+                .map(|status| format!("{:+}", status.balance)),
+        }
+    }
+}
+
+/// # Mind Map Options
+#[derive(Clone)]
+pub struct MindMapOptions {
+    /// Maximum number of outline levels to render, counting the root items as level 1. `None` renders all levels.
+    pub max_depth: Option<usize>,
+    /// Statistics shown on each node, in order.
+    pub stats: Vec<MindMapStat>,
+    /// Render excluded blocks as well.
+    pub include_excluded: bool,
+    /// Title of the root node added when the outline has several root items, as mind maps have a single root.
+    pub root_title: String,
+}
+
+impl Default for MindMapOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: None,
+            stats: vec![MindMapStat::AggregateWords],
+            include_excluded: false,
+            root_title: "Outline".to_string(),
+        }
+    }
+}
+
+impl MindMapOptions {
+    /// Whether the items at this level are rendered.
+    pub fn is_level_included(&self, level: usize) -> bool {
+        self.max_depth.is_none_or(|max_depth| level <= max_depth)
+    }
+
+    /// Whether a block is rendered, whatever its level.
+    pub fn is_block_included(&self, block: &Block) -> bool {
+        self.include_excluded || !block.exclude
+    }
+
+    /// Get the items to render among the subitems of a node.
+    pub fn filter_items<'a>(&self, items: &'a [OutlineItem]) -> Vec<&'a OutlineItem> {
+        items
+            .iter()
+            .filter(|item| self.is_block_included(&item.block))
+            .collect()
+    }
+
+    /// Get the statistics of a block as `(name, value)` pairs, skipping the missing ones.
+    pub fn get_stats(&self, block: &Block) -> Vec<(&'static str, String)> {
+        self.stats
+            .iter()
+            .filter_map(|stat| stat.get_value(block).map(|value| (stat.name(), value)))
+            .collect()
+    }
+
+    /// Get the label of a block: its title followed by its statistics.
+    ///
+    /// # Example
+    ///
+    /// `Methods (aggregate words: 120, target: 200)`
+    pub fn get_label(&self, block: &Block) -> String {
+        let stats = self.get_stats(block);
+        if stats.is_empty() {
+            return block.title.clone();
+        }
+
+        let stats = stats
+            .iter()
+            .map(|(name, value)| format!("{}: {}", name, value))
+            .collect::<Vec<String>>()
+            .join(", ");
+        format!("{} ({})", block.title, stats)
+    }
+}
@@ -0,0 +1,9 @@
+pub mod dot;
+pub mod freemind;
+pub mod html;
+pub mod markdown;
+pub mod mermaid;
+pub mod mind_map;
+pub mod opml;
+pub mod table;
+pub mod toc;
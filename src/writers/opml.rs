@@ -0,0 +1,127 @@
+use eyre::Report;
+
+use crate::entities::core::block::Block;
+use crate::entities::core::outline::Outline;
+use crate::entities::visitor::visitor_trait::{VisitContext, VisitFlow, Visitor};
+use crate::entities::writer::writer_trait::WriterTrait;
+use crate::utils::text_utils::TextUtils;
+use crate::utils::visit_utils::VisitUtils;
+
+/// # OPML Writer
+///
+/// Serializes an `Outline` into OPML 2.0, the format spoken by outliner apps such as OmniOutliner, Workflowy and Dynalist.
+///
+/// Each block becomes an `<outline>` element:
+/// - `text`: the title
+/// - `_note`: the note
+/// - `words`, `characters`: the `self_stats` count
+/// - `aggregateWords`, `aggregateCharacters`: the `aggregate_stats` count
+/// - `targetWords`, `balance`: the `aggregate_stats` target and status, if any
+/// - `exclude`: `true` for excluded blocks
+pub struct OpmlWriter {
+    /// Title in the OPML `<head>`.
+    pub title: Option<String>,
+    /// Write the statistics attributes.
+    pub include_stats: bool,
+}
+
+impl Default for OpmlWriter {
+    fn default() -> Self {
+        Self {
+            title: None,
+            include_stats: true,
+        }
+    }
+}
+
+impl WriterTrait for OpmlWriter {
+    fn write(&self, outline: &Outline) -> Result<String, Report> {
+        let mut opml = String::new();
+        opml.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        opml.push_str("<opml version=\"2.0\">\n");
+        opml.push_str("  <head>\n");
+        if let Some(title) = &self.title {
+            opml.push_str(&format!(
+                "    <title>{}</title>\n",
+                quick_xml::escape::escape(title)
+            ));
+        }
+        opml.push_str("  </head>\n");
+        opml.push_str("  <body>\n");
+        let mut visitor = OpmlVisitor { writer: self, opml };
+        VisitUtils::walk_outline(outline, &mut visitor);
+        let mut opml = visitor.opml;
+        opml.push_str("  </body>\n");
+        opml.push_str("</opml>\n");
+        Ok(opml)
+    }
+
+    fn name(&self) -> &'static str {
+        "OPML Writer"
+    }
+}
+
+impl OpmlWriter {
+    fn get_attributes(&self, block: &Block) -> String {
+        let mut attributes = vec![("text", block.title.clone())];
+        if let Some(note) = &block.note {
+            attributes.push(("_note", note.clone()));
+        }
+        if self.include_stats {
+            attributes.push(("words", block.self_stats.count.words.to_string()));
+            attributes.push(("characters", block.self_stats.count.characters.to_string()));
+            attributes.push((
+                "aggregateWords",
+                block.aggregate_stats.count.words.to_string(),
+            ));
+            attributes.push((
+                "aggregateCharacters",
+                block.aggregate_stats.count.characters.to_string(),
+            ));
+            if let Some(target) = &block.aggregate_stats.target {
+                attributes.push(("targetWords", target.words.to_string()));
+            }
+            if let Some(status) = &block.aggregate_stats.status {
+                attributes.push(("balance", status.balance.to_string()));
+            }
+        }
+        if block.exclude {
+            attributes.push(("exclude", "true".to_string()));
+        }
+
+        attributes
+            .iter()
+            .map(|(key, value)| format!("{}=\"{}\"", key, TextUtils::escape_xml_attribute(value)))
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+}
+
+/// Writes an `<outline>` element when entering a block, closed when exiting it if it has children.
+struct OpmlVisitor<'a> {
+    writer: &'a OpmlWriter,
+    opml: String,
+}
+
+impl Visitor for OpmlVisitor<'_> {
+    fn enter_block(&mut self, block: &Block, context: &VisitContext) -> VisitFlow {
+        // items are nested in `<opml>` and `<body>`
+        let indent = "  ".repeat(context.level + 1);
+        let attributes = self.writer.get_attributes(block);
+        if context.child_count == 0 {
+            self.opml
+                .push_str(&format!("{}<outline {}/>\n", indent, attributes));
+        } else {
+            self.opml
+                .push_str(&format!("{}<outline {}>\n", indent, attributes));
+        }
+        VisitFlow::Continue
+    }
+
+    fn exit_block(&mut self, _block: &Block, context: &VisitContext) {
+        if context.child_count > 0 {
+            let indent = "  ".repeat(context.level + 1);
+            self.opml.push_str(&format!("{}</outline>\n", indent));
+        }
+    }
+}
@@ -0,0 +1,321 @@
+use std::str::FromStr;
+
+use eyre::{eyre, Report};
+
+use crate::entities::core::block::Block;
+use crate::entities::core::outline::Outline;
+use crate::entities::visitor::visitor_trait::{VisitContext, VisitFlow, Visitor};
+use crate::entities::writer::writer_trait::WriterTrait;
+use crate::utils::visit_utils::VisitUtils;
+
+/// UTF-8 byte order mark, which spreadsheet apps such as Excel need to detect UTF-8, e.g. for CJK titles.
+const UTF8_BOM: &str = "\u{feff}";
+
+#[derive(Clone, Debug, PartialEq, Default)]
+pub enum TableFormat {
+    #[default]
+    Csv,
+    Tsv,
+}
+
+/// A column of the table.
+#[derive(Clone, Debug, PartialEq)]
+pub enum TableColumn {
+    Id,
+    /// Position in the outline, e.g. `1.2.3` for the third child of the second child of the first root item.
+    Path,
+    Depth,
+    Title,
+    ParentId,
+    /// First line of the `block_range`
+    StartLine,
+    /// Last line of the `block_range`
+    EndLine,
+    Words,
+    Characters,
+    AggregateWords,
+    AggregateCharacters,
+    /// Target words of the `aggregate_stats`
+    Target,
+    /// Balance of the `aggregate_stats`
+    Balance,
+    Hash,
+    Exclude,
+}
+
+impl TableColumn {
+    pub const ALL: [TableColumn; 15] = [
+        TableColumn::Id,
+        TableColumn::Path,
+        TableColumn::Depth,
+        TableColumn::Title,
+        TableColumn::ParentId,
+        TableColumn::StartLine,
+        TableColumn::EndLine,
+        TableColumn::Words,
+        TableColumn::Characters,
+        TableColumn::AggregateWords,
+        TableColumn::AggregateCharacters,
+        TableColumn::Target,
+        TableColumn::Balance,
+        TableColumn::Hash,
+        TableColumn::Exclude,
+    ];
+
+    /// Name of the column in the header row.
+    pub fn name(&self) -> &'static str {
+        match self {
+            TableColumn::Id => "id",
+            TableColumn::Path => "path",
+            TableColumn::Depth => "depth",
+            TableColumn::Title => "title",
+            TableColumn::ParentId => "parent_id",
+            TableColumn::StartLine => "start_line",
+            TableColumn::EndLine => "end_line",
+            TableColumn::Words => "words",
+            TableColumn::Characters => "characters",
+            TableColumn::AggregateWords => "aggregate_words",
+            TableColumn::AggregateCharacters => "aggregate_characters",
+            TableColumn::Target => "target",
+            TableColumn::Balance => "balance",
+            TableColumn::Hash => "hash",
+            TableColumn::Exclude => "exclude",
+        }
+    }
+
+    /// Value of the column for a block at a path. Missing values are empty.
+    fn get_value(&self, block: &Block, path: &str) -> String {
+        let optional = |value: Option<String>| value.unwrap_or_default();
+        match self {
+            TableColumn::Id => block.id.to_string(),
+            TableColumn::Path => path.to_string(),
+            TableColumn::Depth => block.depth.to_string(),
+            TableColumn::Title => block.title.clone(),
+            TableColumn::ParentId => optional(block.parent_id.map(|id| id.to_string())),
+            TableColumn::StartLine => optional(
+                block
+                    .block_range
+                    .as_ref()
+                    .map(|range| range.start.line.to_string()),
+            ),
+            TableColumn::EndLine => optional(
+                block
+                    .block_range
+                    .as_ref()
+                    .map(|range| range.end.line.to_string()),
+            ),
+            TableColumn::Words => block.self_stats.count.words.to_string(),
+            TableColumn::Characters => block.self_stats.count.characters.to_string(),
+            TableColumn::AggregateWords => block.aggregate_stats.count.words.to_string(),
+            TableColumn::AggregateCharacters => block.aggregate_stats.count.characters.to_string(),
+            TableColumn::Target => optional(
+                block
+                    .aggregate_stats
+                    .target
+                    .as_ref()
+                    .map(|target| target.words.to_string()),
+            ),
+            TableColumn::Balance => optional(
+                block
+                    .aggregate_stats
+                    .status
+                    .as_ref()
+                    .map(|status| status.balance.to_string()),
+            ),
+            TableColumn::Hash => optional(block.hash.map(|hash| format!("{:016x}", hash))),
+            TableColumn::Exclude => block.exclude.to_string(),
+        }
+    }
+}
+
+impl FromStr for TableColumn {
+    type Err = Report;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        TableColumn::ALL
+            .iter()
+            .find(|column| column.name() == name)
+            .cloned()
+            .ok_or_else(|| eyre!("Unknown table column: {}", name))
+    }
+}
+
+/// # Table Writer
+///
+/// Flattens an `Outline` into CSV or TSV rows in document order, one row per block, e.g. for spreadsheets.
+///
+/// Fields containing delimiters, quotes or newlines are quoted.
+pub struct TableWriter {
+    pub format: TableFormat,
+    /// Columns to write, in order.
+    pub columns: Vec<TableColumn>,
+    /// Write excluded blocks as well.
+    pub include_excluded: bool,
+    /// Start with a UTF-8 byte order mark.
+    pub include_bom: bool,
+}
+
+impl Default for TableWriter {
+    fn default() -> Self {
+        Self {
+            format: TableFormat::default(),
+            columns: TableColumn::ALL.to_vec(),
+            include_excluded: true,
+            include_bom: false,
+        }
+    }
+}
+
+impl WriterTrait for TableWriter {
+    fn write(&self, outline: &Outline) -> Result<String, Report> {
+        let delimiter = match self.format {
+            TableFormat::Csv => b',',
+            TableFormat::Tsv => b'\t',
+        };
+        let mut writer = csv::WriterBuilder::new()
+            .delimiter(delimiter)
+            .from_writer(vec![]);
+
+        writer.write_record(self.columns.iter().map(|column| column.name()))?;
+        let mut visitor = TableVisitor {
+            table_writer: self,
+            paths: vec![],
+            writer,
+            error: None,
+        };
+        VisitUtils::walk_outline(outline, &mut visitor);
+        if let Some(err) = visitor.error {
+            return Err(err);
+        }
+
+        let table = String::from_utf8(
+            visitor
+                .writer
+                .into_inner()
+                .map_err(|err| eyre!("Failed to write table: {}", err))?,
+        )?;
+
+        Ok(if self.include_bom {
+            format!("{}{}", UTF8_BOM, table)
+        } else {
+            table
+        })
+    }
+
+    fn name(&self) -> &'static str {
+        "Table Writer"
+    }
+}
+
+struct TableVisitor<'a> {
+    table_writer: &'a TableWriter,
+    /// The paths of the written blocks being visited, e.g. `1.2` for the second child of the first root item.
+    paths: Vec<String>,
+    writer: csv::Writer<Vec<u8>>,
+    /// The first error of the CSV writer, which stops the visit.
+    error: Option<Report>,
+}
+
+impl TableVisitor<'_> {
+    fn is_written(&self, block: &Block) -> bool {
+        !block.exclude || self.table_writer.include_excluded
+    }
+}
+
+impl Visitor for TableVisitor<'_> {
+    fn enter_block(&mut self, block: &Block, context: &VisitContext) -> VisitFlow {
+        if !self.is_written(block) {
+            return VisitFlow::SkipChildren;
+        }
+
+        // the path is stable whether excluded blocks are written or not
+        let path = match self.paths.last() {
+            Some(parent_path) => format!("{}.{}", parent_path, context.index + 1),
+            None => (context.index + 1).to_string(),
+        };
+
+        let record = self
+            .table_writer
+            .columns
+            .iter()
+            .map(|column| column.get_value(block, &path));
+        if let Err(err) = self.writer.write_record(record) {
+            self.error = Some(err.into());
+            return VisitFlow::Stop;
+        }
+        self.paths.push(path);
+        VisitFlow::Continue
+    }
+
+    fn exit_block(&mut self, block: &Block, _context: &VisitContext) {
+        if self.is_written(block) {
+            self.paths.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::core::freeout::Freeout;
+    use crate::entities::writer::writer_trait::WriterTrait;
+    use crate::readers::markdown::MarkdownReader;
+    use crate::utils::test_utils::test_utils::md_get_long_chinese;
+    use crate::writers::table::{TableColumn, TableFormat, TableWriter};
+
+    const MARKDOWN: &str = "# Book\n\n## Chapter 1, \"Cats\"\n\nOne two\n\n## Notes {.exclude}\n\n### Draft\n\n## Chapter 2\n";
+
+    #[test]
+    fn should_write_csv_with_quoted_fields() {
+        let mut freeout = Freeout::new(MARKDOWN.to_string(), None);
+        freeout.process(&MarkdownReader::default()).unwrap();
+        let outline = freeout.to_outline();
+
+        let writer = TableWriter {
+            columns: vec![
+                TableColumn::Path,
+                TableColumn::Title,
+                TableColumn::ParentId,
+                TableColumn::AggregateWords,
+                TableColumn::Target,
+            ],
+            include_excluded: false,
+            ..Default::default()
+        };
+
+        assert_eq!(
+            writer.write(&outline).unwrap(),
+            "path,title,parent_id,aggregate_words,target\n1,Book,,8,\n1.1,\"Chapter 1, \"\"Cats\"\"\",1,5,\n1.3,Chapter 2,1,2,\n"
+        );
+    }
+
+    #[test]
+    fn should_write_tsv_with_cjk_and_newlines() {
+        let mut freeout = Freeout::new(
+            std::fs::read_to_string(md_get_long_chinese()).unwrap(),
+            None,
+        );
+        freeout.process(&MarkdownReader::default()).unwrap();
+        let mut outline = freeout.to_outline();
+        outline.items[0].block.title = "大标题\n第二行".to_string();
+
+        let writer = TableWriter {
+            format: TableFormat::Tsv,
+            columns: vec![TableColumn::Id, TableColumn::Title, TableColumn::Depth],
+            include_bom: true,
+            ..Default::default()
+        };
+        let tsv = writer.write(&outline).unwrap();
+
+        assert!(
+            tsv.starts_with("\u{feff}id\ttitle\tdepth\n1\t\"大标题\n第二行\"\t1\n2\t子标题1\t2\n")
+        );
+
+        // every block has a row
+        let rows = csv::ReaderBuilder::new()
+            .delimiter(b'\t')
+            .from_reader(tsv.trim_start_matches('\u{feff}').as_bytes())
+            .into_records()
+            .count();
+        assert_eq!(rows, outline.get_blocks().len());
+    }
+}
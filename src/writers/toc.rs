@@ -0,0 +1,235 @@
+use std::collections::HashMap;
+
+use eyre::{eyre, Report};
+
+use crate::entities::core::block::Block;
+use crate::entities::core::outline::Outline;
+use crate::entities::visitor::visitor_trait::{VisitContext, VisitFlow, Visitor};
+use crate::entities::writer::writer_trait::WriterTrait;
+use crate::utils::anchor_utils::AnchorUtils;
+use crate::utils::text_utils::TextUtils;
+use crate::utils::visit_utils::VisitUtils;
+
+pub const TOC_START_MARKER: &str = "<!-- toc -->";
+pub const TOC_END_MARKER: &str = "<!-- /toc -->";
+
+/// # Table of Contents Writer
+///
+/// Renders an `Outline` as a nested Markdown list of links to the anchors of the blocks.
+///
+/// ## Example
+///
+/// ```markdown
+/// - [Introduction](#introduction)
+///   - [Background](#background)
+/// - [Methods](#methods)
+/// ```
+#[derive(Default)]
+pub struct TocWriter {
+    /// Maximum number of outline levels to render, counting the root items as level 1. `None` renders all levels.
+    pub max_depth: Option<usize>,
+    /// Render ordered lists (`1.`) instead of bullet lists (`-`).
+    pub numbered: bool,
+    /// Render excluded blocks as well.
+    pub include_excluded: bool,
+}
+
+impl WriterTrait for TocWriter {
+    fn write(&self, outline: &Outline) -> Result<String, Report> {
+        // anchors are generated for all blocks, so that duplicates are numbered the same way as in the rendered document
+        let mut visitor = TocVisitor {
+            writer: self,
+            anchors: AnchorUtils::get_anchors(outline.get_blocks()),
+            numbers: vec![],
+            indents: vec![],
+            toc: String::new(),
+        };
+        VisitUtils::walk_outline(outline, &mut visitor);
+        Ok(visitor.toc)
+    }
+
+    fn name(&self) -> &'static str {
+        "Table of Contents Writer"
+    }
+}
+
+impl TocWriter {
+    /// # Update the table of contents in a source text
+    ///
+    /// Replaces the region between the `<!-- toc -->` and `<!-- /toc -->` markers with the table of contents of the outline, keeping the markers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the markers are missing or in the wrong order.
+    pub fn update_toc(&self, source: &str, outline: &Outline) -> Result<String, Report> {
+        let start = source
+            .find(TOC_START_MARKER)
+            .ok_or_else(|| eyre!("Missing table of contents marker {}", TOC_START_MARKER))?
+            + TOC_START_MARKER.len();
+        let end = source[start..].find(TOC_END_MARKER).ok_or_else(|| {
+            eyre!(
+                "Missing table of contents marker {} after {}",
+                TOC_END_MARKER,
+                TOC_START_MARKER
+            )
+        })? + start;
+
+        let toc = self.write(outline)?;
+
+        Ok(format!(
+            "{}\n\n{}\n{}",
+            &source[..start],
+            toc,
+            &source[end..]
+        ))
+    }
+}
+
+struct TocVisitor<'a> {
+    writer: &'a TocWriter,
+    anchors: HashMap<usize, String>,
+    /// The number of the last written item of each level, for numbered lists.
+    numbers: Vec<usize>,
+    /// The indent of the children of each written item being visited.
+    indents: Vec<usize>,
+    toc: String,
+}
+
+impl TocVisitor<'_> {
+    fn is_written(&self, block: &Block, context: &VisitContext) -> bool {
+        (!block.exclude || self.writer.include_excluded)
+            && self
+                .writer
+                .max_depth
+                .is_none_or(|max_depth| context.level <= max_depth)
+    }
+}
+
+impl Visitor for TocVisitor<'_> {
+    fn enter_block(&mut self, block: &Block, context: &VisitContext) -> VisitFlow {
+        if !self.is_written(block, context) {
+            return VisitFlow::SkipChildren;
+        }
+
+        let marker = if self.writer.numbered {
+            self.numbers.resize(context.level, 0);
+            self.numbers[context.level - 1] += 1;
+            format!("{}.", self.numbers[context.level - 1])
+        } else {
+            "-".to_string()
+        };
+
+        let indent = self.indents.last().copied().unwrap_or(0);
+        self.toc.push_str(&format!(
+            "{}{} [{}](#{})\n",
+            " ".repeat(indent),
+            marker,
+            TextUtils::escape_link_text(&block.title),
+            self.anchors[&block.id]
+        ));
+        // nested lists are indented to the content of the parent item
+        self.indents.push(indent + marker.len() + 1);
+        VisitFlow::Continue
+    }
+
+    fn exit_block(&mut self, block: &Block, context: &VisitContext) {
+        if self.is_written(block, context) {
+            self.indents.pop();
+            // the next list under another item is numbered from 1
+            self.numbers.truncate(context.level);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::entities::core::freeout::Freeout;
+    use crate::entities::writer::writer_trait::WriterTrait;
+    use crate::readers::markdown::MarkdownReader;
+    use crate::writers::toc::TocWriter;
+
+    const MARKDOWN: &str = r#"# Guide
+
+<!-- toc -->
+- [Outdated](#outdated)
+<!-- /toc -->
+
+## Install
+
+### From [Source]
+
+## Usage
+
+## Notes {.exclude}
+
+## Usage
+"#;
+
+    #[test]
+    fn should_write_nested_toc() {
+        let mut freeout = Freeout::new(MARKDOWN.to_string(), None);
+        freeout.process(&MarkdownReader::default()).unwrap();
+        let outline = freeout.to_outline();
+
+        let toc = TocWriter::default().write(&outline).unwrap();
+
+        assert_eq!(
+            toc,
+            "- [Guide](#guide)\n  - [Install](#install)\n    - [From \\[Source\\]](#from-source)\n  - [Usage](#usage)\n  - [Usage](#usage-1)\n"
+        );
+    }
+
+    #[test]
+    fn should_write_numbered_toc_with_depth_limit() {
+        let mut freeout = Freeout::new(MARKDOWN.to_string(), None);
+        freeout.process(&MarkdownReader::default()).unwrap();
+        let outline = freeout.to_outline();
+
+        let writer = TocWriter {
+            max_depth: Some(2),
+            numbered: true,
+            include_excluded: true,
+        };
+        let toc = writer.write(&outline).unwrap();
+
+        assert_eq!(
+            toc,
+            "1. [Guide](#guide)\n   1. [Install](#install)\n   2. [Usage](#usage)\n   3. [Notes](#notes)\n   4. [Usage](#usage-1)\n"
+        );
+    }
+
+    #[test]
+    fn should_write_empty_toc_without_levels() {
+        let mut freeout = Freeout::new(MARKDOWN.to_string(), None);
+        freeout.process(&MarkdownReader::default()).unwrap();
+        let outline = freeout.to_outline();
+
+        let writer = TocWriter {
+            max_depth: Some(0),
+            ..Default::default()
+        };
+        assert_eq!(writer.write(&outline).unwrap(), "");
+    }
+
+    #[test]
+    fn should_update_toc_between_markers() {
+        let mut freeout = Freeout::new(MARKDOWN.to_string(), None);
+        freeout.process(&MarkdownReader::default()).unwrap();
+        let outline = freeout.to_outline();
+
+        let writer = TocWriter {
+            max_depth: Some(1),
+            ..Default::default()
+        };
+        let updated = writer.update_toc(MARKDOWN, &outline).unwrap();
+
+        assert!(updated.starts_with(
+            "# Guide\n\n<!-- toc -->\n\n- [Guide](#guide)\n\n<!-- /toc -->\n\n## Install"
+        ));
+
+        // updating again is stable
+        assert_eq!(writer.update_toc(&updated, &outline).unwrap(), updated);
+
+        assert!(writer.update_toc("# No markers", &outline).is_err());
+    }
+}
@@ -0,0 +1,83 @@
+//! Runs the `freeout` binary on the fixtures, and checks its outputs and exit codes.
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn get_cli_fixture(name: &str) -> String {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("fixtures")
+        .join("cli")
+        .join(name)
+        .display()
+        .to_string()
+}
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_freeout"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn get_stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+#[test]
+fn should_print_outline_tree() {
+    let output = run(&["outline", &get_cli_fixture("clean.md")]);
+
+    assert!(output.status.success());
+    assert_eq!(get_stdout(&output), "Book (7 words)\n  Chapter (3 words)\n");
+}
+
+#[test]
+fn should_pass_check_without_errors() {
+    let output = run(&["check", &get_cli_fixture("clean.md")]);
+    assert!(output.status.success());
+
+    // warnings only fail the check with --strict
+    let skipped_level = get_cli_fixture("skipped_level.md");
+    let output = run(&["check", &skipped_level]);
+    assert!(output.status.success());
+    assert!(get_stdout(&output).contains("warning [skipped-heading-level]"));
+}
+
+#[test]
+fn should_fail_check_on_errors() {
+    let skipped_level = get_cli_fixture("skipped_level.md");
+
+    let output = run(&["check", "--strict", &skipped_level]);
+    assert_eq!(output.status.code(), Some(1));
+
+    let config = get_cli_fixture("strict_levels.yaml");
+    let output = run(&[
+        "check",
+        "--config",
+        &config,
+        "--format",
+        "json",
+        &skipped_level,
+    ]);
+    assert_eq!(output.status.code(), Some(1));
+    let reports: serde_json::Value = serde_json::from_str(&get_stdout(&output)).unwrap();
+    assert_eq!(reports[0]["diagnostics"][0]["severity"], "error");
+
+    // a missing file fails the check as well
+    let output = run(&["check", &get_cli_fixture("missing.md")]);
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn should_export_a_single_document() {
+    let clean = get_cli_fixture("clean.md");
+
+    let output = run(&["export", "--format", "csv", &clean]);
+    assert!(output.status.success());
+    assert_eq!(get_stdout(&output).lines().count(), 3);
+
+    // the outputs of several documents cannot be concatenated
+    let output = run(&["export", "--format", "csv", &clean, &clean]);
+    assert_eq!(output.status.code(), Some(1));
+    assert!(get_stdout(&output).is_empty());
+}